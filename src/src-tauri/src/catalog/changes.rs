@@ -0,0 +1,116 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last change before the models are recomputed,
+/// a pull or a removal touches many files in a row
+pub const DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// What a filesystem event touched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// `model_list.json`, the model directory may have moved
+    Catalog,
+    Models,
+}
+
+/// Sort a changed path, `None` for the other files next to the catalog
+pub fn classify(path: &Path, catalog: &Path, model_path: Option<&Path>) -> Option<Change> {
+    if path == catalog {
+        Some(Change::Catalog)
+    } else if model_path.is_some_and(|dir| path.starts_with(dir)) {
+        Some(Change::Models)
+    } else {
+        None
+    }
+}
+
+/// Changes waiting for the quiet period to end
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    last_change: Option<Instant>,
+    catalog_changed: bool,
+}
+
+impl Debouncer {
+    pub fn record(&mut self, change: Change, now: Instant) {
+        self.last_change = Some(now);
+        self.catalog_changed |= change == Change::Catalog;
+    }
+
+    /// When the pending changes settle, `None` when there are none
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_change.map(|at| at + DEBOUNCE)
+    }
+
+    /// Once settled, whether the catalog was among the changes; the debouncer is then reset
+    pub fn take_due(&mut self, now: Instant) -> Option<bool> {
+        if self.deadline()? > now {
+            return None;
+        }
+        self.last_change = None;
+        Some(std::mem::take(&mut self.catalog_changed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_catalog_and_model_paths() {
+        let catalog = Path::new("/opt/flm/model_list.json");
+        let models = Some(Path::new("/home/me/models"));
+        assert_eq!(classify(catalog, catalog, models), Some(Change::Catalog));
+        assert_eq!(
+            classify(
+                Path::new("/home/me/models/Qwen3-8B/model.bin"),
+                catalog,
+                models
+            ),
+            Some(Change::Models)
+        );
+        assert_eq!(
+            classify(Path::new("/opt/flm/flm.exe"), catalog, models),
+            None
+        );
+        assert_eq!(
+            classify(Path::new("/home/me/models-old/a.bin"), catalog, models),
+            None
+        );
+        assert_eq!(
+            classify(Path::new("/home/me/models/a.bin"), catalog, None),
+            None
+        );
+    }
+
+    #[test]
+    fn waits_for_the_changes_to_settle() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::default();
+        assert_eq!(debouncer.deadline(), None);
+        assert_eq!(debouncer.take_due(start), None);
+
+        debouncer.record(Change::Models, start);
+        let second = start + Duration::from_secs(1);
+        debouncer.record(Change::Models, second);
+        // The quiet period restarts on each change
+        assert_eq!(debouncer.take_due(start + DEBOUNCE), None);
+        assert_eq!(debouncer.deadline(), Some(second + DEBOUNCE));
+        assert_eq!(debouncer.take_due(second + DEBOUNCE), Some(false));
+
+        // Settled changes are reported once
+        assert_eq!(debouncer.take_due(second + DEBOUNCE * 2), None);
+    }
+
+    #[test]
+    fn remembers_a_catalog_change_until_settled() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::default();
+        debouncer.record(Change::Catalog, start);
+        debouncer.record(Change::Models, start + Duration::from_millis(500));
+        assert_eq!(debouncer.take_due(start + DEBOUNCE * 2), Some(true));
+
+        debouncer.record(Change::Models, start + DEBOUNCE * 3);
+        assert_eq!(debouncer.take_due(start + DEBOUNCE * 5), Some(false));
+    }
+}
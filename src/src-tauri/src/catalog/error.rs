@@ -0,0 +1,69 @@
+use std::fmt;
+
+use serde::Serialize;
+
+/// Errors raised while locating or reading `model_list.json`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum CatalogError {
+    /// No FLM installation directory could be found
+    NotFound,
+    Io(String),
+    /// The file is not valid JSON or lacks the top-level structure
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// An entry does not match the expected format
+    InvalidEntry {
+        model: String,
+        message: String,
+    },
+    /// The model is not in the catalog
+    UnknownModel(String),
+    /// The catalog gives no `model_path` or no directory name for the model
+    NoModelDirectory(String),
+    /// The new pull of a damaged model could not be queued
+    RepairFailed {
+        model: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(
+                f,
+                "FLM installation not found, set its path in the settings"
+            ),
+            Self::Io(e) => write!(f, "Cannot read the model catalog: {}", e),
+            Self::Syntax {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "Malformed model catalog at line {}, column {}: {}",
+                line, column, message
+            ),
+            Self::InvalidEntry { model, message } => {
+                write!(f, "Invalid catalog entry '{}': {}", model, message)
+            }
+            Self::UnknownModel(model) => write!(f, "{} is not in the model catalog", model),
+            Self::NoModelDirectory(model) => {
+                write!(
+                    f,
+                    "The model catalog does not tell where {} is stored",
+                    model
+                )
+            }
+            Self::RepairFailed { model, message } => {
+                write!(f, "Cannot queue the repair of {}: {}", model, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {}
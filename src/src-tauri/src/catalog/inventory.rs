@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::catalog::list::ListedModel;
+use crate::catalog::Catalog;
+use crate::types::{DiskState, InstallIssue, InventoryEntry, ModelInfo, ModelInventory};
+
+/// Extensions of files still being downloaded
+const PARTIAL_EXTENSIONS: [&str; 5] = ["part", "partial", "download", "tmp", "incomplete"];
+
+/// Split the catalog into installed and available models.
+/// `listed` is the output of `flm list --filter installed`, `None` when the CLI could not run;
+/// the disk then decides alone.
+pub fn reconcile(
+    catalog: &Catalog,
+    listed: Option<&[ListedModel]>,
+    disk: impl Fn(&ModelInfo) -> DiskState,
+) -> ModelInventory {
+    let is_listed = |name: &str| {
+        listed.map(|listed| {
+            listed
+                .iter()
+                .any(|model| model.name == name && model.installed != Some(false))
+        })
+    };
+
+    let mut inventory = ModelInventory::default();
+    for info in &catalog.models {
+        let disk = disk(info);
+        let (installed, issue) = match (is_listed(&info.name), disk) {
+            (Some(true), DiskState::Missing) => (true, Some(InstallIssue::MissingFiles)),
+            (Some(true), DiskState::Partial) => (true, Some(InstallIssue::PartialFiles)),
+            (Some(true), _) => (true, None),
+            (Some(false), DiskState::Complete) => (false, Some(InstallIssue::NotListed)),
+            (None, DiskState::Complete) => (true, None),
+            (_, DiskState::Partial) => (false, Some(InstallIssue::PartialFiles)),
+            (_, _) => (false, None),
+        };
+        let entry = InventoryEntry {
+            name: info.name.clone(),
+            info: Some(info.clone()),
+            disk,
+            issue,
+        };
+        if installed {
+            inventory.installed.push(entry);
+        } else {
+            inventory.available.push(entry);
+        }
+    }
+
+    // Installed through another catalog version
+    for model in listed.unwrap_or_default() {
+        let known = catalog.models.iter().any(|info| info.name == model.name);
+        if !known && model.installed != Some(false) {
+            inventory.installed.push(InventoryEntry {
+                name: model.name.clone(),
+                info: None,
+                disk: DiskState::Unknown,
+                issue: None,
+            });
+        }
+    }
+    inventory
+}
+
+/// Directory FLM downloads a model into, named after its catalog `name`
+pub fn model_dir(model_path: &Path, info: &ModelInfo) -> Option<PathBuf> {
+    info.dir_name
+        .as_deref()
+        .filter(|name| !name.is_empty())
+        .map(|name| model_path.join(name))
+}
+
+/// File still being downloaded, or left by an interrupted download
+pub fn is_partial(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PARTIAL_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// State of the model files, checked against the file list of the catalog when it has one
+pub fn disk_state(model_path: Option<&Path>, info: &ModelInfo) -> DiskState {
+    let Some(dir) = model_path.and_then(|path| model_dir(path, info)) else {
+        return DiskState::Unknown;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return DiskState::Missing;
+    };
+
+    let files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    if files.iter().any(|path| is_partial(path)) {
+        return DiskState::Partial;
+    }
+
+    if info.files.is_empty() {
+        return if files.is_empty() {
+            DiskState::Missing
+        } else {
+            DiskState::Complete
+        };
+    }
+    let present = info
+        .files
+        .iter()
+        .filter(|file| fs::metadata(dir.join(file)).is_ok_and(|m| m.is_file() && m.len() > 0))
+        .count();
+    match present {
+        0 if files.is_empty() => DiskState::Missing,
+        n if n == info.files.len() => DiskState::Complete,
+        _ => DiskState::Partial,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::model_list::parse;
+
+    const MODEL_LIST: &str = include_str!("fixtures/model_list.json");
+
+    fn listed(name: &str) -> ListedModel {
+        ListedModel {
+            name: name.to_string(),
+            installed: None,
+        }
+    }
+
+    fn names(entries: &[InventoryEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    fn issue(inventory: &ModelInventory, name: &str) -> Option<InstallIssue> {
+        inventory
+            .installed
+            .iter()
+            .chain(&inventory.available)
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.issue)
+    }
+
+    #[test]
+    fn cli_and_disk_agree() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let cli = [listed("llama3.2:1b"), listed("qwen3:8b")];
+        let inventory = reconcile(&catalog, Some(&cli), |info| {
+            if cli.iter().any(|model| model.name == info.name) {
+                DiskState::Complete
+            } else {
+                DiskState::Missing
+            }
+        });
+
+        assert_eq!(names(&inventory.installed), ["llama3.2:1b", "qwen3:8b"]);
+        assert_eq!(
+            names(&inventory.available),
+            ["embed-gemma:300m", "gemma3:4b", "whisper-v3:turbo"]
+        );
+        assert!(inventory
+            .installed
+            .iter()
+            .chain(&inventory.available)
+            .all(|entry| entry.issue.is_none()));
+    }
+
+    #[test]
+    fn flags_disagreements() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let cli = [listed("llama3.2:1b"), listed("qwen3:8b")];
+        let inventory = reconcile(&catalog, Some(&cli), |info| match info.name.as_str() {
+            "llama3.2:1b" => DiskState::Missing,
+            "qwen3:8b" => DiskState::Partial,
+            // Interrupted pull
+            "gemma3:4b" => DiskState::Partial,
+            // Copied by hand
+            "whisper-v3:turbo" => DiskState::Complete,
+            _ => DiskState::Missing,
+        });
+
+        assert_eq!(names(&inventory.installed), ["llama3.2:1b", "qwen3:8b"]);
+        assert_eq!(
+            issue(&inventory, "llama3.2:1b"),
+            Some(InstallIssue::MissingFiles)
+        );
+        assert_eq!(
+            issue(&inventory, "qwen3:8b"),
+            Some(InstallIssue::PartialFiles)
+        );
+        assert_eq!(
+            issue(&inventory, "gemma3:4b"),
+            Some(InstallIssue::PartialFiles)
+        );
+        assert_eq!(
+            issue(&inventory, "whisper-v3:turbo"),
+            Some(InstallIssue::NotListed)
+        );
+        assert!(inventory
+            .installed
+            .iter()
+            .all(|entry| !entry.is_startable()));
+    }
+
+    #[test]
+    fn disk_decides_without_cli() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let inventory = reconcile(&catalog, None, |info| {
+            if info.name == "gemma3:4b" {
+                DiskState::Complete
+            } else {
+                DiskState::Unknown
+            }
+        });
+        assert_eq!(names(&inventory.installed), ["gemma3:4b"]);
+        assert_eq!(inventory.available.len(), 4);
+    }
+
+    #[test]
+    fn keeps_listed_models_missing_from_catalog() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let mut retired = listed("mistral:7b");
+        let mut removed = listed("phi4:14b");
+        removed.installed = Some(false);
+        retired.installed = Some(true);
+
+        let inventory = reconcile(&catalog, Some(&[retired, removed]), |_| DiskState::Missing);
+        assert_eq!(names(&inventory.installed), ["mistral:7b"]);
+        assert!(inventory.installed[0].info.is_none());
+        assert!(inventory.installed[0].is_startable());
+    }
+
+    #[test]
+    fn audio_and_embedding_models_are_not_startable() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let cli = [listed("embed-gemma:300m"), listed("whisper-v3:turbo")];
+        let inventory = reconcile(&catalog, Some(&cli), |_| DiskState::Unknown);
+        assert_eq!(inventory.installed.len(), 2);
+        assert!(inventory
+            .installed
+            .iter()
+            .all(|entry| !entry.is_startable()));
+    }
+}
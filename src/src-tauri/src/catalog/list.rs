@@ -0,0 +1,223 @@
+/// Model named by a line of `flm list`, with the status column when the line has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedModel {
+    pub name: String,
+    pub installed: Option<bool>,
+}
+
+/// List markers put in front of the names
+const BULLETS: [char; 7] = ['-', '*', '+', '•', '>', '·', '|'];
+const INSTALLED_MARKS: [&str; 4] = ["✅", "✔", "✓", "[x]"];
+const MISSING_MARKS: [&str; 4] = ["❌", "✗", "✘", "[ ]"];
+
+/// Parse the output of `flm list`, with or without `--quiet` and `--filter`.
+/// Headers, separators and section titles are skipped; a line counts only when it starts with
+/// a `family:tag` name, after an optional bullet, index or status mark.
+pub fn parse_list(output: &str) -> Vec<ListedModel> {
+    let mut models: Vec<ListedModel> = Vec::new();
+    for line in output.lines() {
+        let Some(model) = parse_line(&strip_ansi(line)) else {
+            continue;
+        };
+        match models.iter_mut().find(|m| m.name == model.name) {
+            Some(existing) => existing.installed = existing.installed.or(model.installed),
+            None => models.push(model),
+        }
+    }
+    models
+}
+
+fn parse_line(line: &str) -> Option<ListedModel> {
+    let mut rest = line.trim();
+    let mut installed = None;
+    // Bullets, `1.` indices and status marks may be stacked in any order
+    loop {
+        let before = rest;
+        rest = rest.trim_start_matches(BULLETS).trim_start();
+        rest = strip_index(rest).trim_start();
+        if let Some((status, after)) = strip_mark(rest) {
+            installed = Some(status);
+            rest = after.trim_start();
+        }
+        if rest == before {
+            break;
+        }
+    }
+
+    let (token, status) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let name = token.trim_end_matches([',', ';']);
+    if !is_model_name(name) {
+        return None;
+    }
+    Some(ListedModel {
+        name: name.to_string(),
+        installed: status_column(status).or(installed),
+    })
+}
+
+/// `family:tag`, e.g. `llama3.2:1b` or `gemma3:4b-it`
+fn is_model_name(token: &str) -> bool {
+    let Some((family, tag)) = token.split_once(':') else {
+        return false;
+    };
+    let valid = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    };
+    valid(family) && valid(tag)
+}
+
+/// `1.` or `1)` in front of a name
+fn strip_index(line: &str) -> &str {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return line;
+    }
+    line[digits..]
+        .strip_prefix(['.', ')'])
+        .filter(|after| after.starts_with(char::is_whitespace))
+        .unwrap_or(line)
+}
+
+fn strip_mark(line: &str) -> Option<(bool, &str)> {
+    let lower = line.to_lowercase();
+    if let Some(mark) = INSTALLED_MARKS.iter().find(|mark| lower.starts_with(*mark)) {
+        return Some((true, &line[mark.len()..]));
+    }
+    MISSING_MARKS
+        .iter()
+        .find(|mark| lower.starts_with(*mark))
+        .map(|mark| (false, &line[mark.len()..]))
+}
+
+/// Trailing columns after the name: `installed`, `not installed`, `available`, marks
+fn status_column(columns: &str) -> Option<bool> {
+    let lower = columns.to_lowercase();
+    let has = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+    if has(&MISSING_MARKS)
+        || has(&[
+            "not installed",
+            "not-installed",
+            "uninstalled",
+            "available",
+            "missing",
+        ])
+    {
+        Some(false)
+    } else if has(&INSTALLED_MARKS) || has(&["installed", "downloaded"]) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Remove color codes, `flm` colors the status column on some terminals
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences end with a letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(name: &str, installed: Option<bool>) -> ListedModel {
+        ListedModel {
+            name: name.to_string(),
+            installed,
+        }
+    }
+
+    #[test]
+    fn parses_quiet_output() {
+        let output = "llama3.2:1b\nqwen3:8b\n\n";
+        assert_eq!(
+            parse_list(output),
+            [listed("llama3.2:1b", None), listed("qwen3:8b", None)]
+        );
+    }
+
+    #[test]
+    fn skips_headers_and_separators() {
+        let output = "\
+Models:
+NAME            SIZE     STATUS
+--------------- -------- ------
+- llama3.2:1b   1.3GB    installed
+- gemma3:4b     3.4GB    not installed
+Found 2 models.
+";
+        assert_eq!(
+            parse_list(output),
+            [
+                listed("llama3.2:1b", Some(true)),
+                listed("gemma3:4b", Some(false))
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_bullets_indices_and_marks() {
+        let output = "\
+Installed models:
+  • qwen3:8b
+  * gemma3:4b-it ✅
+  1. whisper-v3:turbo
+  2) embed-gemma:300m [x]
+✅ llama3.2:1b
+❌ deepseek-r1:8b
+- ✔ phi4-mini:3.8b
+";
+        assert_eq!(
+            parse_list(output),
+            [
+                listed("qwen3:8b", None),
+                listed("gemma3:4b-it", Some(true)),
+                listed("whisper-v3:turbo", None),
+                listed("embed-gemma:300m", Some(true)),
+                listed("llama3.2:1b", Some(true)),
+                listed("deepseek-r1:8b", Some(false)),
+                listed("phi4-mini:3.8b", Some(true)),
+            ]
+        );
+    }
+
+    #[test]
+    fn trailing_status_column_wins_over_mark() {
+        assert_eq!(
+            parse_list("✅ qwen3:8b  (available)"),
+            [listed("qwen3:8b", Some(false))]
+        );
+    }
+
+    #[test]
+    fn ignores_colors_and_duplicates() {
+        let output = "\u{1b}[32mllama3.2:1b\u{1b}[0m  \u{1b}[32minstalled\u{1b}[0m\nllama3.2:1b";
+        assert_eq!(parse_list(output), [listed("llama3.2:1b", Some(true))]);
+    }
+
+    #[test]
+    fn rejects_non_model_tokens() {
+        let output = "\
+Error: cannot reach https://huggingface.co
+Note: run flm pull <model>
+Total: 3
+";
+        assert!(parse_list(output).is_empty());
+    }
+}
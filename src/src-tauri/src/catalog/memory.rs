@@ -0,0 +1,294 @@
+use crate::catalog::{Catalog, CatalogError};
+use crate::types::{MemoryEstimate, MemoryVerdict, ModelInfo, ServerOptions};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const MIB: u64 = 1024 * 1024;
+
+/// Runtime buffers and NPU kernels loaded with any model
+const RUNTIME_OVERHEAD: u64 = 512 * MIB;
+/// Side models assumed when the catalog does not list them
+const FALLBACK_ASR_BYTES: u64 = GIB;
+const FALLBACK_EMBED_BYTES: u64 = 640 * MIB;
+/// KV cache per token: a base plus a share per billion parameters, a fit of the
+/// grouped-query attention models of the catalog (Llama 3.2, Qwen3, Gemma 3) in BF16
+const KV_BYTES_PER_TOKEN_BASE: u64 = 16 * 1024;
+const KV_BYTES_PER_TOKEN_PER_BILLION: u64 = 16 * 1024;
+/// Bytes per parameter of the quantized weights, to size models without `parameter_size`
+const BYTES_PER_PARAMETER: f64 = 0.6;
+/// Share of the shared memory above which the fit is tight
+const TIGHT_PERCENT: u64 = 85;
+
+/// RAM of the machine, `total_bytes` sets the shared memory budget
+#[derive(Debug, Clone, Copy)]
+pub struct SystemMemory {
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl SystemMemory {
+    pub fn current() -> Self {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+        Self {
+            total_bytes: system.total_memory(),
+            available_bytes: system.available_memory(),
+        }
+    }
+
+    /// Memory known by its total only: the verdicts then depend on the shared memory
+    /// budget alone, not on what other apps currently use
+    pub fn from_total(total_bytes: u64) -> Self {
+        Self {
+            total_bytes,
+            available_bytes: total_bytes,
+        }
+    }
+}
+
+/// Estimate the memory `flm serve` needs for a model, empty when only side models run,
+/// with the given options
+pub fn estimate(
+    catalog: &Catalog,
+    model: &str,
+    options: &ServerOptions,
+    memory: SystemMemory,
+) -> Result<MemoryEstimate, CatalogError> {
+    let info = match model {
+        "" => None,
+        model => Some(
+            catalog
+                .models
+                .iter()
+                .find(|info| info.name == model)
+                .ok_or_else(|| CatalogError::UnknownModel(model.to_string()))?,
+        ),
+    };
+    Ok(estimate_info(catalog, info, options, memory))
+}
+
+/// Verdict for a model alone at its default context, as listed in the tray
+pub fn verdict(info: &ModelInfo, memory: SystemMemory) -> MemoryVerdict {
+    estimate_info(
+        &Catalog::default(),
+        Some(info),
+        &ServerOptions::default(),
+        memory,
+    )
+    .verdict
+}
+
+fn estimate_info(
+    catalog: &Catalog,
+    info: Option<&ModelInfo>,
+    options: &ServerOptions,
+    memory: SystemMemory,
+) -> MemoryEstimate {
+    let ctx_len = match (options.ctx_len.filter(|len| *len > 0), info) {
+        (Some(len), Some(_)) => len,
+        (None, Some(info)) => info.context_length.unwrap_or(0),
+        (_, None) => 0,
+    };
+    let weights_bytes = info.map_or(0, |info| info.size_bytes);
+    let kv_cache_bytes = info.map_or(0, |info| kv_bytes_per_token(info) * u64::from(ctx_len));
+    let side_model = |enabled: Option<bool>, matches: fn(&ModelInfo) -> bool, fallback: u64| {
+        if !enabled.unwrap_or(false) {
+            return 0;
+        }
+        catalog
+            .models
+            .iter()
+            .find(|info| matches(info) && info.size_bytes > 0)
+            .map_or(fallback, |info| info.size_bytes)
+    };
+    let asr_bytes = side_model(
+        options.asr,
+        |info| info.capabilities.audio,
+        FALLBACK_ASR_BYTES,
+    );
+    let embed_bytes = side_model(
+        options.embed,
+        |info| info.capabilities.embed,
+        FALLBACK_EMBED_BYTES,
+    );
+    let total_bytes = weights_bytes + kv_cache_bytes + asr_bytes + embed_bytes + RUNTIME_OVERHEAD;
+
+    let shared_memory_bytes = memory.total_bytes / 2;
+    let verdict = if total_bytes > shared_memory_bytes {
+        MemoryVerdict::WontFit
+    } else if total_bytes * 100 > shared_memory_bytes * TIGHT_PERCENT
+        || total_bytes > memory.available_bytes
+    {
+        MemoryVerdict::Tight
+    } else {
+        MemoryVerdict::Fits
+    };
+
+    MemoryEstimate {
+        model: info.map(|info| info.name.clone()).unwrap_or_default(),
+        ctx_len,
+        weights_bytes,
+        kv_cache_bytes,
+        asr_bytes,
+        embed_bytes,
+        overhead_bytes: RUNTIME_OVERHEAD,
+        total_bytes,
+        shared_memory_bytes,
+        available_bytes: memory.available_bytes,
+        verdict,
+    }
+}
+
+fn kv_bytes_per_token(info: &ModelInfo) -> u64 {
+    let billions = info
+        .parameter_size
+        .as_deref()
+        .and_then(parse_billions)
+        .unwrap_or(info.size_bytes as f64 / BYTES_PER_PARAMETER / 1e9);
+    KV_BYTES_PER_TOKEN_BASE + (billions * KV_BYTES_PER_TOKEN_PER_BILLION as f64) as u64
+}
+
+/// Parameter count in billions from labels like `8B`, `3.8B` or `300M`
+fn parse_billions(label: &str) -> Option<f64> {
+    let label = label.trim();
+    let (number, scale) = match label.chars().last()?.to_ascii_uppercase() {
+        'B' => (&label[..label.len() - 1], 1.0),
+        'M' => (&label[..label.len() - 1], 1e-3),
+        'K' => (&label[..label.len() - 1], 1e-6),
+        _ => (label, 1e-9),
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    (value.is_finite() && value >= 0.0).then_some(value * scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::model_list::parse;
+
+    const MODEL_LIST: &str = include_str!("fixtures/model_list.json");
+
+    fn memory(total_gib: u64, available_gib: u64) -> SystemMemory {
+        SystemMemory {
+            total_bytes: total_gib * GIB,
+            available_bytes: available_gib * GIB,
+        }
+    }
+
+    fn options(ctx_len: Option<u32>, asr: bool, embed: bool) -> ServerOptions {
+        ServerOptions {
+            ctx_len,
+            asr: Some(asr),
+            embed: Some(embed),
+            ..ServerOptions::default()
+        }
+    }
+
+    fn estimate_for(
+        catalog: &Catalog,
+        model: &str,
+        ctx_len: Option<u32>,
+        memory: SystemMemory,
+    ) -> MemoryEstimate {
+        estimate(catalog, model, &options(ctx_len, false, false), memory).unwrap()
+    }
+
+    #[test]
+    fn small_model_fits() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let estimate = estimate(
+            &catalog,
+            "llama3.2:1b",
+            &options(Some(4096), false, false),
+            memory(32, 20),
+        )
+        .unwrap();
+        assert_eq!(estimate.ctx_len, 4096);
+        assert_eq!(estimate.weights_bytes, 1_325_000_000);
+        // 1B parameters: 32 KiB per token
+        assert_eq!(estimate.kv_cache_bytes, 4096 * 32 * 1024);
+        assert_eq!(estimate.asr_bytes + estimate.embed_bytes, 0);
+        assert_eq!(estimate.shared_memory_bytes, 16 * GIB);
+        assert_eq!(estimate.verdict, MemoryVerdict::Fits);
+    }
+
+    #[test]
+    fn default_context_makes_large_models_wont_fit() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        // 32K tokens of an 8B model need about 4.5 GiB of KV cache
+        let estimate = estimate(
+            &catalog,
+            "qwen3:8b",
+            &options(None, false, false),
+            memory(16, 12),
+        )
+        .unwrap();
+        assert_eq!(estimate.ctx_len, 32768);
+        assert_eq!(estimate.verdict, MemoryVerdict::WontFit);
+
+        let shorter = estimate_for(&catalog, "qwen3:8b", Some(2048), memory(16, 12));
+        assert_eq!(shorter.verdict, MemoryVerdict::Fits);
+    }
+
+    #[test]
+    fn side_models_come_from_the_catalog() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let whisper = catalog
+            .models
+            .iter()
+            .find(|info| info.capabilities.audio)
+            .unwrap();
+        let estimate =
+            estimate(&catalog, "", &options(Some(0), true, false), memory(32, 20)).unwrap();
+        assert_eq!(estimate.ctx_len, 0);
+        assert_eq!(estimate.weights_bytes, 0);
+        assert_eq!(estimate.asr_bytes, whisper.size_bytes);
+        assert_eq!(estimate.total_bytes, whisper.size_bytes + RUNTIME_OVERHEAD);
+    }
+
+    #[test]
+    fn little_free_memory_is_tight() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let estimate = estimate_for(&catalog, "llama3.2:1b", Some(4096), memory(32, 1));
+        assert_eq!(estimate.verdict, MemoryVerdict::Tight);
+    }
+
+    #[test]
+    fn tray_verdict_uses_the_default_context() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let find = |name: &str| {
+            catalog
+                .models
+                .iter()
+                .find(|info| info.name == name)
+                .unwrap()
+        };
+        assert_eq!(
+            verdict(find("llama3.2:1b"), memory(32, 20)),
+            MemoryVerdict::Fits
+        );
+        assert_eq!(
+            verdict(find("qwen3:8b"), memory(16, 12)),
+            MemoryVerdict::WontFit
+        );
+    }
+
+    #[test]
+    fn unknown_models_are_refused() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let result = estimate(
+            &catalog,
+            "nope:1b",
+            &ServerOptions::default(),
+            memory(32, 20),
+        );
+        assert!(matches!(result, Err(CatalogError::UnknownModel(_))));
+    }
+
+    #[test]
+    fn parses_parameter_sizes() {
+        assert_eq!(parse_billions("8B"), Some(8.0));
+        assert_eq!(parse_billions("3.8b"), Some(3.8));
+        assert_eq!(parse_billions("300M"), Some(0.3));
+        assert_eq!(parse_billions("large"), None);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod changes;
+pub mod error;
+pub mod inventory;
+pub mod list;
+pub mod memory;
+pub mod model_list;
+pub mod repair;
+pub mod store;
+pub mod verify;
+pub mod watcher;
+
+pub use error::CatalogError;
+pub use model_list::{load, locate, Catalog};
+pub use store::ModelStore;
+
+/// Event emitted with a `ModelInventory` payload whenever the model lists change
+pub const MODELS_EVENT: &str = "models-changed";
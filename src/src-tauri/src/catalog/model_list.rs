@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::catalog::CatalogError;
+use crate::types::{ModelCapabilities, ModelInfo};
+
+/// Written by the FLM installer next to the executable
+pub const MODEL_LIST_FILENAME: &str = "model_list.json";
+/// Value of `flmPath` when no directory was configured
+const DEFAULT_FLM_PATH: &str = "flm";
+/// Families without explicit capability flags in older catalogs
+const AUDIO_FAMILIES: [&str; 1] = ["whisper"];
+const EMBED_FAMILIES: [&str; 2] = ["embed", "bert"];
+/// Leading words naming a variant of another family, `embed-gemma` belongs with `gemma`
+const VARIANT_PREFIXES: [&str; 2] = ["embed", "embedding"];
+
+/// Parsed `model_list.json`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Catalog {
+    /// Directory holding the downloaded models
+    pub model_path: Option<PathBuf>,
+    /// Sorted by name
+    pub models: Vec<ModelInfo>,
+    /// Entries left out because they do not match the expected format, named `family:tag`
+    pub skipped: Vec<CatalogError>,
+}
+
+/// Top level of the file, entries are decoded one by one to report which one is invalid
+#[derive(Deserialize)]
+struct ModelListFile {
+    #[serde(default)]
+    model_path: Option<String>,
+    models: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    modified_at: Option<String>,
+    /// Bytes, some catalogs write it as a float
+    #[serde(default)]
+    size: Option<f64>,
+    #[serde(default)]
+    default_context_length: Option<u32>,
+    #[serde(default)]
+    vlm: bool,
+    #[serde(default)]
+    embed: Option<bool>,
+    #[serde(default)]
+    asr: Option<bool>,
+    #[serde(default)]
+    files: Vec<String>,
+    /// SHA-256 by file name, published by some catalogs
+    #[serde(default)]
+    sha256: BTreeMap<String, String>,
+    #[serde(default)]
+    details: ModelDetails,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ModelDetails {
+    format: Option<String>,
+    family: Option<String>,
+    think: bool,
+    parameter_size: Option<String>,
+    quantization_level: Option<String>,
+}
+
+/// Path of `model_list.json` for the configured `flmPath`, searching PATH when it is unset
+pub fn locate(flm_path: &str) -> Result<PathBuf, CatalogError> {
+    let dir = match flm_path.trim() {
+        "" | DEFAULT_FLM_PATH => find_in_path().ok_or(CatalogError::NotFound)?,
+        dir => PathBuf::from(dir),
+    };
+    Ok(dir.join(MODEL_LIST_FILENAME))
+}
+
+/// Read and parse the file, a relative `model_path` is resolved against its directory
+pub fn load(path: &Path) -> Result<Catalog, CatalogError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CatalogError::Io(format!("{}: {}", path.display(), e)))?;
+    let mut catalog = parse(&content)?;
+    if let (Some(model_path), Some(dir)) = (catalog.model_path.as_mut(), path.parent()) {
+        if model_path.is_relative() {
+            *model_path = dir.join(&*model_path);
+        }
+    }
+    Ok(catalog)
+}
+
+/// Parse the content of `model_list.json`, unknown fields are ignored.
+/// Only a malformed file is an error, a malformed entry is skipped so one schema change
+/// upstream does not hide every model.
+pub fn parse(content: &str) -> Result<Catalog, CatalogError> {
+    let file: ModelListFile = serde_json::from_str(content).map_err(syntax_error)?;
+
+    let mut models = Vec::new();
+    let mut skipped = Vec::new();
+    for (family, tags) in file.models {
+        for (tag, value) in tags {
+            let name = format!("{}:{}", family, tag);
+            let info = serde_json::from_value(value)
+                .map_err(|e| CatalogError::InvalidEntry {
+                    model: name.clone(),
+                    message: e.to_string(),
+                })
+                .and_then(|entry| model_info(name, &family, tag, entry));
+            match info {
+                Ok(info) => models.push(info),
+                Err(e) => skipped.push(e),
+            }
+        }
+    }
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Catalog {
+        model_path: file
+            .model_path
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from),
+        models,
+        skipped,
+    })
+}
+
+fn model_info(
+    name: String,
+    family: &str,
+    tag: String,
+    entry: ModelEntry,
+) -> Result<ModelInfo, CatalogError> {
+    let size_bytes = match entry.size {
+        None => 0,
+        Some(size) if size.is_finite() && size >= 0.0 => size as u64,
+        Some(size) => {
+            return Err(CatalogError::InvalidEntry {
+                model: name,
+                message: format!("invalid size {}", size),
+            })
+        }
+    };
+    let capabilities = capabilities(family, &entry);
+
+    Ok(ModelInfo {
+        name,
+        family: family.to_string(),
+        tag,
+        // FLM names the model directory after the entry `name`
+        dir_name: entry.name.clone(),
+        description: entry.name,
+        url: entry.url,
+        size_bytes,
+        modified_at: entry.modified_at,
+        context_length: entry.default_context_length,
+        parameter_size: entry.details.parameter_size,
+        quantization: entry.details.quantization_level,
+        format: entry.details.format,
+        files: entry.files,
+        checksums: entry
+            .sha256
+            .into_iter()
+            .map(|(file, hash)| (file, hash.to_lowercase()))
+            .collect(),
+        capabilities,
+    })
+}
+
+/// Explicit flags win, the declared family is the fallback for catalogs without them
+fn capabilities(family: &str, entry: &ModelEntry) -> ModelCapabilities {
+    let families = [
+        family.to_lowercase(),
+        entry
+            .details
+            .family
+            .as_deref()
+            .unwrap_or_default()
+            .to_lowercase(),
+    ];
+    let in_families = |known: &[&str]| {
+        families
+            .iter()
+            .any(|family| known.iter().any(|k| family.contains(k)))
+    };
+
+    ModelCapabilities {
+        think: entry.details.think,
+        vision: entry.vlm,
+        embed: entry.embed.unwrap_or_else(|| in_families(&EMBED_FAMILIES)),
+        audio: entry.asr.unwrap_or_else(|| in_families(&AUDIO_FAMILIES)),
+    }
+}
+
+/// Group of a family, its name up to the version: `llama3.2`, `whisper-v3` and
+/// `embed-gemma` go under `llama`, `whisper` and `gemma`, `gpt-oss` stays whole
+pub fn family_group(family: &str) -> String {
+    let family = family.to_lowercase();
+    let mut words: Vec<&str> = family.split('-').filter(|word| !word.is_empty()).collect();
+    while words.len() > 1 && VARIANT_PREFIXES.contains(&words[0]) {
+        words.remove(0);
+    }
+
+    let has_digit = |word: &str| word.contains(|c: char| c.is_ascii_digit());
+    let Some((first, rest)) = words.split_first() else {
+        return family;
+    };
+    // `llama3.2`, the name ends where the version begins
+    let name = first
+        .split(|c: char| c.is_ascii_digit())
+        .next()
+        .unwrap_or_default();
+    if name.is_empty() {
+        return family;
+    }
+    if has_digit(first) {
+        return name.to_string();
+    }
+    // `whisper-v3`, `phi-4-mini`: the words after the first versioned one are variants
+    let mut group = vec![name];
+    group.extend(rest.iter().take_while(|word| !has_digit(word)));
+    group.join("-")
+}
+
+fn syntax_error(e: serde_json::Error) -> CatalogError {
+    let message = e.to_string();
+    // The position is reported separately
+    let suffix = format!(" at line {} column {}", e.line(), e.column());
+    CatalogError::Syntax {
+        line: e.line(),
+        column: e.column(),
+        message: message
+            .strip_suffix(&suffix)
+            .unwrap_or(&message)
+            .to_string(),
+    }
+}
+
+/// Directory of the `flm` executable found in PATH
+fn find_in_path() -> Option<PathBuf> {
+    let program = if cfg!(windows) { "flm.exe" } else { "flm" };
+    std::env::split_paths(&std::env::var_os("PATH")?).find(|dir| dir.join(program).is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL_LIST: &str = include_str!("fixtures/model_list.json");
+
+    impl Catalog {
+        fn get(&self, name: &str) -> Option<&ModelInfo> {
+            self.models.iter().find(|model| model.name == name)
+        }
+    }
+
+    #[test]
+    fn parses_fixture() {
+        let catalog = parse(MODEL_LIST).expect("fixture parses");
+        assert_eq!(
+            catalog.model_path,
+            Some(PathBuf::from("C:\\Users\\me\\Documents\\flm\\models"))
+        );
+        let names: Vec<&str> = catalog.models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "embed-gemma:300m",
+                "gemma3:4b",
+                "llama3.2:1b",
+                "qwen3:8b",
+                "whisper-v3:turbo"
+            ]
+        );
+
+        let llama = catalog.get("llama3.2:1b").unwrap();
+        assert_eq!(llama.family, "llama3.2");
+        assert_eq!(llama.tag, "1b");
+        assert_eq!(llama.description.as_deref(), Some("Llama-3.2-1B-NPU2"));
+        assert_eq!(llama.dir_name.as_deref(), Some("Llama-3.2-1B-NPU2"));
+        assert_eq!(llama.size_bytes, 1_325_000_000);
+        assert_eq!(llama.context_length, Some(131072));
+        assert_eq!(llama.parameter_size.as_deref(), Some("1B"));
+        assert_eq!(llama.quantization.as_deref(), Some("Q4_1"));
+        assert_eq!(llama.capabilities, ModelCapabilities::default());
+    }
+
+    #[test]
+    fn reads_capabilities() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let capabilities = |name: &str| catalog.get(name).unwrap().capabilities;
+
+        assert!(capabilities("qwen3:8b").think);
+        assert!(capabilities("gemma3:4b").vision);
+        // Explicit flag
+        assert!(capabilities("embed-gemma:300m").embed);
+        // Family fallback
+        assert!(capabilities("whisper-v3:turbo").audio);
+        assert!(!capabilities("whisper-v3:turbo").embed);
+    }
+
+    #[test]
+    fn groups_families_by_name() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        let groups: Vec<(&str, String)> = catalog
+            .models
+            .iter()
+            .map(|model| (model.name.as_str(), family_group(&model.family)))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("embed-gemma:300m", String::from("gemma")),
+                ("gemma3:4b", String::from("gemma")),
+                ("llama3.2:1b", String::from("llama")),
+                ("qwen3:8b", String::from("qwen")),
+                ("whisper-v3:turbo", String::from("whisper")),
+            ]
+        );
+
+        for (family, group) in [
+            ("gpt-oss", "gpt-oss"),
+            ("Qwen2.5-VL", "qwen"),
+            ("phi-4-mini", "phi"),
+            ("deepseek-r1", "deepseek"),
+            ("embed", "embed"),
+            ("3b-model", "3b-model"),
+        ] {
+            assert_eq!(family_group(family), group, "{}", family);
+        }
+    }
+
+    #[test]
+    fn size_may_be_a_float() {
+        let catalog = parse(MODEL_LIST).unwrap();
+        assert_eq!(catalog.get("gemma3:4b").unwrap().size_bytes, 3_400_000_000);
+    }
+
+    #[test]
+    fn ignores_unknown_fields_and_missing_details() {
+        let catalog = parse(
+            r#"{"version": 3, "models": {"tiny": {"1m": {"size": 10, "files": ["a.bin"]}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(catalog.model_path, None);
+        let tiny = catalog.get("tiny:1m").unwrap();
+        assert_eq!(tiny.size_bytes, 10);
+        assert_eq!(tiny.description, None);
+    }
+
+    #[test]
+    fn reads_checksums_in_lowercase() {
+        let catalog = parse(
+            r#"{"models": {"tiny": {"1m": {"files": ["a.bin"], "sha256": {"a.bin": "ABC123"}}}}}"#,
+        )
+        .unwrap();
+        let tiny = catalog.get("tiny:1m").unwrap();
+        assert_eq!(
+            tiny.checksums.get("a.bin").map(String::as_str),
+            Some("abc123")
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_with_position() {
+        let error = parse("{\n  \"models\": {,\n}").unwrap_err();
+        let CatalogError::Syntax {
+            line,
+            column,
+            message,
+        } = error
+        else {
+            panic!("unexpected error: {:?}", error);
+        };
+        // On the stray comma
+        assert_eq!((line, column), (2, 14));
+        assert!(!message.contains("line"), "{}", message);
+    }
+
+    #[test]
+    fn reports_missing_models() {
+        let error = parse(r#"{"model_path": "C:\\models"}"#).unwrap_err();
+        assert!(
+            error.to_string().contains("missing field `models`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn skips_invalid_entries_by_name() {
+        let catalog = parse(
+            r#"{"models": {
+                "qwen3": {"8b": {"size": "large"}, "4b": {"size": 10}},
+                "gemma3": {"1b": {"size": -1}}
+            }}"#,
+        )
+        .unwrap();
+        let names: Vec<&str> = catalog.models.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["qwen3:4b"]);
+
+        let skipped: Vec<(&str, &str)> = catalog
+            .skipped
+            .iter()
+            .map(|error| match error {
+                CatalogError::InvalidEntry { model, message } => (model.as_str(), message.as_str()),
+                _ => panic!("unexpected error: {:?}", error),
+            })
+            .collect();
+        assert_eq!(skipped.len(), 2);
+        assert_eq!(skipped[0].0, "gemma3:1b");
+        assert_eq!(skipped[1].0, "qwen3:8b");
+        assert!(skipped[1].1.contains("invalid type"), "{}", skipped[1].1);
+
+        assert!(parse(MODEL_LIST).unwrap().skipped.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use tauri::{AppHandle, Manager};
+
+use crate::catalog::inventory::model_dir;
+use crate::catalog::verify::verify;
+use crate::catalog::{load, locate, CatalogError, ModelStore};
+use crate::config;
+use crate::download::{DownloadError, DownloadManager};
+use crate::types::VerifyReport;
+
+/// Check the files of a model against the catalog, hashing runs off the async runtime
+pub async fn verify_model(app: &AppHandle, model: &str) -> Result<VerifyReport, CatalogError> {
+    let catalog = load(&locate(&config::load(app).flm_path)?)?;
+    let info = catalog
+        .models
+        .into_iter()
+        .find(|info| info.name == model)
+        .ok_or_else(|| CatalogError::UnknownModel(model.to_string()))?;
+    let dir = catalog
+        .model_path
+        .as_deref()
+        .and_then(|path| model_dir(path, &info))
+        .ok_or_else(|| CatalogError::NoModelDirectory(model.to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || verify(&dir, &info))
+        .await
+        .map_err(|e| CatalogError::Io(e.to_string()))
+}
+
+/// Verify the given models, all installed ones when empty, and pull the damaged ones again.
+/// Returns the report of every model checked.
+pub async fn repair_models(
+    app: &AppHandle,
+    models: Vec<String>,
+) -> Result<Vec<VerifyReport>, CatalogError> {
+    let models = if models.is_empty() {
+        installed_models(app).await?
+    } else {
+        models
+    };
+
+    let mut reports = Vec::new();
+    for model in models {
+        let report = verify_model(app, &model).await?;
+        if !report.healthy {
+            // Progress is reported by the download queue
+            match app.state::<DownloadManager>().repair(app, &model) {
+                // Being pulled already, the download fixes it
+                Ok(_) | Err(DownloadError::AlreadyQueued(_)) => {}
+                Err(e) => {
+                    return Err(CatalogError::RepairFailed {
+                        model,
+                        message: e.to_string(),
+                    })
+                }
+            }
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+/// Installed models known to the catalog, the others cannot be checked
+async fn installed_models(app: &AppHandle) -> Result<Vec<String>, CatalogError> {
+    let store = app.state::<ModelStore>();
+    let inventory = match store.inventory() {
+        Some(inventory) => inventory,
+        None => store.refresh(app).await?,
+    };
+    Ok(inventory
+        .installed
+        .into_iter()
+        .filter(|entry| entry.info.is_some())
+        .map(|entry| entry.name)
+        .collect())
+}
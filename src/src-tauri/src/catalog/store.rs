@@ -0,0 +1,78 @@
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::ShellExt;
+
+use crate::catalog::inventory::{disk_state, reconcile};
+use crate::catalog::list::{parse_list, ListedModel};
+use crate::catalog::{load, locate, CatalogError, MODELS_EVENT};
+use crate::config;
+use crate::server::FLM_PROGRAM;
+use crate::tray::refresh_tray_menu;
+use crate::types::ModelInventory;
+
+/// Last reconciled model lists, shared by the commands and the tray
+#[derive(Default)]
+pub struct ModelStore {
+    /// Unset until the first refresh succeeds
+    inventory: Mutex<Option<ModelInventory>>,
+}
+
+impl ModelStore {
+    pub fn inventory(&self) -> Option<ModelInventory> {
+        self.lock().clone()
+    }
+
+    /// Read the catalog, `flm list` and the model directory again
+    pub async fn refresh(&self, app: &AppHandle) -> Result<ModelInventory, CatalogError> {
+        let catalog = load(&locate(&config::load(app).flm_path)?)?;
+        for skipped in &catalog.skipped {
+            // Left out of the lists, the other models stay available
+            eprintln!("[catalog] {}", skipped);
+        }
+        let listed = list_installed(app).await;
+        let model_path = catalog.model_path.as_deref();
+        let inventory = reconcile(&catalog, listed.as_deref(), |info| {
+            disk_state(model_path, info)
+        });
+
+        let previous = self.lock().replace(inventory.clone());
+        if previous.as_ref() != Some(&inventory) {
+            // Pushed so the views follow pulls and removals made outside the app
+            let _ = app.emit(MODELS_EVENT, &inventory);
+        }
+        refresh_tray_menu(app);
+        Ok(inventory)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<ModelInventory>> {
+        self.inventory.lock().expect("model store lock poisoned")
+    }
+}
+
+/// Fill the store once at startup, the tray lists no model until then
+pub fn spawn_refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { refresh_in_background(&app).await });
+}
+
+/// Refresh nobody waits for, errors surface when the frontend loads the models
+/// through `model_inventory`
+pub async fn refresh_in_background(app: &AppHandle) {
+    let _ = app.state::<ModelStore>().refresh(app).await;
+}
+
+/// Models reported as installed by the CLI, `None` when it cannot run
+async fn list_installed(app: &AppHandle) -> Option<Vec<ListedModel>> {
+    let output = app
+        .shell()
+        .command(FLM_PROGRAM)
+        .args(["list", "--quiet", "--filter", "installed"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_list(&String::from_utf8_lossy(&output.stdout)))
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::catalog::inventory::is_partial;
+use crate::types::{FileIssue, FileProblem, ModelInfo, VerifyReport};
+
+/// Share of the catalog size the files must reach, catalog sizes are rounded
+const MIN_SIZE_PERCENT: u64 = 90;
+
+/// Check the files of a model in `dir` against its catalog entry.
+/// Checksums are only compared for the files the catalog publishes one for.
+pub fn verify(dir: &Path, info: &ModelInfo) -> VerifyReport {
+    let expected: BTreeSet<&str> = info
+        .files
+        .iter()
+        .chain(info.checksums.keys())
+        .map(String::as_str)
+        .collect();
+    let mut issues = Vec::new();
+    let mut actual_bytes = 0;
+    let mut checksums_checked = 0;
+
+    for &file in &expected {
+        let path = dir.join(file);
+        let problem = match fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() && metadata.len() > 0 => {
+                actual_bytes += metadata.len();
+                match info.checksums.get(file) {
+                    Some(checksum) => {
+                        checksums_checked += 1;
+                        // An unreadable file is as unusable as a corrupted one
+                        let matches = sha256(&path).is_ok_and(|hash| &hash == checksum);
+                        (!matches).then_some(FileProblem::ChecksumMismatch)
+                    }
+                    None => None,
+                }
+            }
+            Ok(metadata) if metadata.is_file() => Some(FileProblem::Truncated),
+            _ => Some(FileProblem::Missing),
+        };
+        if let Some(problem) = problem {
+            issues.push(FileIssue {
+                file: file.to_string(),
+                problem,
+            });
+        }
+    }
+
+    let mut found_any = false;
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !path.is_file() || expected.contains(name.as_str()) {
+            continue;
+        }
+        found_any = true;
+        let problem = if is_partial(&path) {
+            FileProblem::Partial
+        } else if expected.is_empty() {
+            // Without a file list, every file counts towards the model size
+            actual_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            continue;
+        } else {
+            FileProblem::Extra
+        };
+        issues.push(FileIssue {
+            file: name,
+            problem,
+        });
+    }
+    if expected.is_empty() && !found_any {
+        issues.push(FileIssue {
+            file: String::new(),
+            problem: FileProblem::Missing,
+        });
+    }
+    issues.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let undersized = info.size_bytes > 0 && actual_bytes * 100 < info.size_bytes * MIN_SIZE_PERCENT;
+    let healthy = !undersized
+        && issues
+            .iter()
+            .all(|issue| issue.problem == FileProblem::Extra);
+    VerifyReport {
+        model: info.name.clone(),
+        dir: dir.to_path_buf(),
+        expected_bytes: info.size_bytes,
+        actual_bytes,
+        undersized,
+        checksums_checked,
+        issues,
+        healthy,
+    }
+}
+
+/// Short English description of the problems found, for logs and notifications
+pub fn summary(report: &VerifyReport) -> String {
+    if report.healthy {
+        return format!("{}: no problem found", report.model);
+    }
+    let mut problems: Vec<String> = report
+        .issues
+        .iter()
+        .filter(|issue| issue.problem != FileProblem::Extra)
+        .map(|issue| {
+            let file = if issue.file.is_empty() {
+                "model directory"
+            } else {
+                issue.file.as_str()
+            };
+            let problem = match issue.problem {
+                FileProblem::Missing => "missing",
+                FileProblem::Truncated => "empty",
+                FileProblem::ChecksumMismatch => "checksum mismatch",
+                FileProblem::Partial => "interrupted download",
+                FileProblem::Extra => "extra",
+            };
+            format!("{} {}", file, problem)
+        })
+        .collect();
+    if report.undersized {
+        problems.push(format!(
+            "{:.1} of {:.1} GB on disk",
+            report.actual_bytes as f64 / 1e9,
+            report.expected_bytes as f64 / 1e9
+        ));
+    }
+    format!("{}: {}", report.model, problems.join(", "))
+}
+
+fn sha256(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::types::ModelCapabilities;
+
+    /// SHA-256 of `hello`
+    const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    /// Model directory removed when the test ends
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("flm-verify-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn write(&self, file: &str, content: &str) {
+            fs::write(self.0.join(file), content).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn model(size_bytes: u64, files: &[&str], checksums: &[(&str, &str)]) -> ModelInfo {
+        ModelInfo {
+            name: String::from("tiny:1m"),
+            family: String::from("tiny"),
+            tag: String::from("1m"),
+            description: Some(String::from("Tiny-1M")),
+            dir_name: Some(String::from("Tiny-1M")),
+            url: None,
+            size_bytes,
+            modified_at: None,
+            context_length: None,
+            parameter_size: None,
+            quantization: None,
+            format: None,
+            capabilities: ModelCapabilities::default(),
+            files: files.iter().map(|file| file.to_string()).collect(),
+            checksums: checksums
+                .iter()
+                .map(|(file, hash)| (file.to_string(), hash.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    }
+
+    fn problems(report: &VerifyReport) -> Vec<(&str, FileProblem)> {
+        report
+            .issues
+            .iter()
+            .map(|issue| (issue.file.as_str(), issue.problem))
+            .collect()
+    }
+
+    #[test]
+    fn complete_model_is_healthy() {
+        let dir = TempDir::new("complete");
+        dir.write("a.bin", "hello");
+        dir.write("b.bin", "world");
+        let report = verify(
+            &dir.0,
+            &model(10, &["a.bin", "b.bin"], &[("a.bin", HELLO_SHA256)]),
+        );
+        assert!(report.healthy, "{:?}", report);
+        assert_eq!(report.actual_bytes, 10);
+        assert_eq!(report.checksums_checked, 1);
+    }
+
+    #[test]
+    fn reports_missing_empty_and_extra_files() {
+        let dir = TempDir::new("missing");
+        dir.write("a.bin", "");
+        dir.write("notes.txt", "hello");
+        dir.write("c.bin.part", "he");
+        let report = verify(&dir.0, &model(0, &["a.bin", "b.bin"], &[]));
+        assert!(!report.healthy);
+        assert_eq!(
+            problems(&report),
+            [
+                ("a.bin", FileProblem::Truncated),
+                ("b.bin", FileProblem::Missing),
+                ("c.bin.part", FileProblem::Partial),
+                ("notes.txt", FileProblem::Extra),
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_files_alone_keep_the_model_healthy() {
+        let dir = TempDir::new("extra");
+        dir.write("a.bin", "hello");
+        dir.write("notes.txt", "hello");
+        let report = verify(&dir.0, &model(0, &["a.bin"], &[]));
+        assert!(report.healthy);
+        assert_eq!(problems(&report), [("notes.txt", FileProblem::Extra)]);
+    }
+
+    #[test]
+    fn detects_corrupted_files() {
+        let dir = TempDir::new("corrupted");
+        dir.write("a.bin", "hellO");
+        let report = verify(&dir.0, &model(0, &["a.bin"], &[("a.bin", HELLO_SHA256)]));
+        assert_eq!(
+            problems(&report),
+            [("a.bin", FileProblem::ChecksumMismatch)]
+        );
+        assert_eq!(summary(&report), "tiny:1m: a.bin checksum mismatch");
+    }
+
+    #[test]
+    fn files_well_below_the_catalog_size_are_undersized() {
+        let dir = TempDir::new("undersized");
+        dir.write("a.bin", "hello");
+        // Within the rounding of the catalog size
+        assert!(verify(&dir.0, &model(5, &[], &[])).healthy);
+        let report = verify(&dir.0, &model(100, &[], &[]));
+        assert!(report.undersized);
+        assert!(!report.healthy);
+        assert!(report.issues.is_empty());
+    }
+
+    #[test]
+    fn missing_directory_without_file_list() {
+        let dir = TempDir::new("gone");
+        let report = verify(&dir.0.join("Tiny-1M"), &model(0, &[], &[]));
+        assert_eq!(problems(&report), [("", FileProblem::Missing)]);
+        assert_eq!(summary(&report), "tiny:1m: model directory missing");
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+
+use crate::catalog::changes::{classify, Debouncer};
+use crate::catalog::store::refresh_in_background;
+use crate::catalog::{load, locate};
+use crate::config;
+
+/// Wait when no change is pending, only bounds the sleep of the loop
+const IDLE_WAIT: Duration = Duration::from_secs(3600);
+
+/// Watch `model_list.json` and `model_path` for the lifetime of the app, refreshing the
+/// model lists once changes settle. Catches `flm pull`/`flm remove` run in a terminal and
+/// catalogs replaced by an FLM upgrade. The FLM location is read once, at startup.
+pub fn spawn(app: &AppHandle) {
+    let Ok(catalog) = locate(&config::load(app).flm_path) else {
+        return;
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+    let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        if let Ok(event) = result {
+            // Reads, hashing during a verification for instance, change nothing
+            if !matches!(event.kind, EventKind::Access(_)) {
+                let _ = tx.send(event);
+            }
+        }
+    });
+    let Ok(mut watcher) = watcher else {
+        return;
+    };
+    // The parent directory, an upgrade may replace the file instead of writing to it
+    if let Some(dir) = catalog.parent() {
+        let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+    }
+    let catalog_dir = catalog.parent().map(Path::to_path_buf);
+    let mut models = ModelWatch::new(model_path(&catalog));
+    models.watch(&mut watcher, catalog_dir.as_deref());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut debouncer = Debouncer::default();
+        loop {
+            let wait = debouncer
+                .deadline()
+                .map_or(IDLE_WAIT, |at| at.saturating_duration_since(Instant::now()));
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(event)) => {
+                    for path in &event.paths {
+                        if let Some(change) = classify(path, &catalog, models.path.as_deref()) {
+                            debouncer.record(change, Instant::now());
+                        }
+                    }
+                }
+                Ok(None) => break,
+                Err(_) => {}
+            }
+
+            if let Some(catalog_changed) = debouncer.take_due(Instant::now()) {
+                if catalog_changed {
+                    // The model directory is declared by the catalog
+                    let path = model_path(&catalog);
+                    if path != models.path {
+                        models.unwatch(&mut watcher, catalog_dir.as_deref());
+                        models = ModelWatch::new(path);
+                    }
+                }
+                if !models.watched {
+                    models.watch(&mut watcher, catalog_dir.as_deref());
+                }
+                refresh_in_background(&app).await;
+            }
+        }
+    });
+}
+
+fn model_path(catalog: &Path) -> Option<PathBuf> {
+    load(catalog).ok()?.model_path
+}
+
+/// Watch of the model directory. It is missing until the first pull, its parent is
+/// watched meanwhile to see it appear.
+struct ModelWatch {
+    path: Option<PathBuf>,
+    watched: bool,
+    parent_watched: bool,
+}
+
+impl ModelWatch {
+    fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            watched: false,
+            parent_watched: false,
+        }
+    }
+
+    fn watch(&mut self, watcher: &mut RecommendedWatcher, catalog_dir: Option<&Path>) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        self.watched = watcher.watch(path, RecursiveMode::Recursive).is_ok();
+        let parent = path.parent().filter(|parent| Some(*parent) != catalog_dir);
+        match parent {
+            Some(parent) if self.watched && self.parent_watched => {
+                let _ = watcher.unwatch(parent);
+                self.parent_watched = false;
+            }
+            Some(parent) if !self.watched && !self.parent_watched => {
+                self.parent_watched = watcher.watch(parent, RecursiveMode::NonRecursive).is_ok();
+            }
+            _ => {}
+        }
+    }
+
+    fn unwatch(&self, watcher: &mut RecommendedWatcher, catalog_dir: Option<&Path>) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        if self.watched {
+            let _ = watcher.unwatch(path);
+        }
+        if let Some(parent) = path.parent().filter(|parent| Some(*parent) != catalog_dir) {
+            if self.parent_watched {
+                let _ = watcher.unwatch(parent);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use tauri::{AppHandle, State};
+
+use crate::catalog::memory::{self, SystemMemory};
+use crate::catalog::{self, repair, Catalog, CatalogError, ModelStore};
+use crate::config;
+use crate::tray::{refresh_tray_menu, TrayState};
+use crate::types::{MemoryEstimate, ModelInventory, ServerOptions, VerifyReport};
+
+/// Models of the FLM catalog, installed or not
+#[tauri::command]
+pub fn get_model_catalog(app: AppHandle) -> Result<Catalog, CatalogError> {
+    let path = catalog::locate(&config::load(&app).flm_path)?;
+    catalog::load(&path)
+}
+
+/// Installed and available models, reconciled with the disk.
+/// Returns the last lists unless `refresh` is set, after a pull or a removal for instance.
+#[tauri::command]
+pub async fn model_inventory(
+    app: AppHandle,
+    store: State<'_, ModelStore>,
+    refresh: Option<bool>,
+) -> Result<ModelInventory, CatalogError> {
+    match store.inventory() {
+        Some(inventory) if !refresh.unwrap_or(false) => Ok(inventory),
+        _ => store.refresh(&app).await,
+    }
+}
+
+/// Check the files of an installed model against the catalog
+#[tauri::command]
+pub async fn verify_model(app: AppHandle, model: String) -> Result<VerifyReport, CatalogError> {
+    repair::verify_model(&app, &model).await
+}
+
+/// Verify models, all installed ones by default, and queue a new pull of the damaged ones
+#[tauri::command]
+pub async fn repair_models(
+    app: AppHandle,
+    models: Option<Vec<String>>,
+) -> Result<Vec<VerifyReport>, CatalogError> {
+    repair::repair_models(&app, models.unwrap_or_default()).await
+}
+
+/// Memory a model needs with the given options, compared with the shared memory
+#[tauri::command]
+pub fn estimate_memory(
+    app: AppHandle,
+    model: String,
+    options: ServerOptions,
+) -> Result<MemoryEstimate, CatalogError> {
+    let catalog = catalog::load(&catalog::locate(&config::load(&app).flm_path)?)?;
+    memory::estimate(&catalog, &model, &options, SystemMemory::current())
+}
+
+#[tauri::command]
+pub fn get_hide_unfit_models(tray: State<'_, TrayState>) -> bool {
+    tray.hide_unfit_models()
+}
+
+/// Hide the catalog models that won't fit from the tray, or only flag them
+#[tauri::command]
+pub fn set_hide_unfit_models(
+    app: AppHandle,
+    tray: State<'_, TrayState>,
+    hide: bool,
+) -> Result<(), CatalogError> {
+    config::save_key(&app, "hideUnfitModels", &hide)
+        .map_err(|e| CatalogError::Io(e.to_string()))?;
+    tray.set_hide_unfit_models(hide);
+    refresh_tray_menu(&app);
+    Ok(())
+}
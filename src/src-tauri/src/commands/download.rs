@@ -0,0 +1,38 @@
+use tauri::{AppHandle, State};
+
+use crate::download::{DownloadError, DownloadManager};
+use crate::types::DownloadJob;
+
+/// Downloads in the order they were requested, the finished ones included
+#[tauri::command]
+pub fn list_downloads(downloads: State<'_, DownloadManager>) -> Vec<DownloadJob> {
+    downloads.jobs()
+}
+
+/// Add a model to the download queue, progress is reported through `download-changed`
+#[tauri::command]
+pub fn queue_download(
+    app: AppHandle,
+    downloads: State<'_, DownloadManager>,
+    model: String,
+) -> Result<DownloadJob, DownloadError> {
+    downloads.enqueue(&app, &model)
+}
+
+#[tauri::command]
+pub fn cancel_download(
+    app: AppHandle,
+    downloads: State<'_, DownloadManager>,
+    model: String,
+) -> Result<DownloadJob, DownloadError> {
+    downloads.cancel(&app, &model)
+}
+
+#[tauri::command]
+pub fn retry_download(
+    app: AppHandle,
+    downloads: State<'_, DownloadManager>,
+    model: String,
+) -> Result<DownloadJob, DownloadError> {
+    downloads.retry(&app, &model)
+}
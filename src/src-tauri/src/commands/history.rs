@@ -0,0 +1,17 @@
+use tauri::State;
+
+use crate::server::SessionHistory;
+use crate::types::SessionRecord;
+
+/// Sessions returned when the caller sets no limit
+const DEFAULT_HISTORY_LIMIT: usize = 50;
+
+/// Server sessions newest first, with the diagnosis of the failed ones
+#[tauri::command]
+pub fn server_history(
+    history: State<'_, SessionHistory>,
+    instance: Option<String>,
+    limit: Option<usize>,
+) -> Vec<SessionRecord> {
+    history.list(instance.as_deref(), limit.unwrap_or(DEFAULT_HISTORY_LIMIT))
+}
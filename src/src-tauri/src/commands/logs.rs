@@ -0,0 +1,35 @@
+use tauri::State;
+
+use crate::server::{LogStore, ServerError};
+use crate::types::{LogPage, LogSession};
+
+/// Recorded server sessions, newest first
+#[tauri::command]
+pub fn list_log_sessions(store: State<'_, LogStore>, instance: Option<String>) -> Vec<LogSession> {
+    store.sessions(instance.as_deref())
+}
+
+/// Page through the lines of a session
+#[tauri::command]
+pub fn read_server_log(
+    store: State<'_, LogStore>,
+    session: String,
+    offset: usize,
+    limit: usize,
+) -> Result<LogPage, ServerError> {
+    store
+        .read(&session, offset, limit)
+        .map_err(|e| ServerError::Io(e.to_string()))
+}
+
+/// Last lines of a session
+#[tauri::command]
+pub fn tail_server_log(
+    store: State<'_, LogStore>,
+    session: String,
+    count: usize,
+) -> Result<LogPage, ServerError> {
+    store
+        .tail(&session, count)
+        .map_err(|e| ServerError::Io(e.to_string()))
+}
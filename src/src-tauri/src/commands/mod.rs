@@ -1,3 +1,15 @@
+pub mod catalog;
+pub mod download;
+pub mod history;
+pub mod logs;
+pub mod schedule;
+pub mod server;
 pub mod tray;
 
+pub use catalog::*;
+pub use download::*;
+pub use history::*;
+pub use logs::*;
+pub use schedule::*;
+pub use server::*;
 pub use tray::*;
@@ -0,0 +1,27 @@
+use tauri::{AppHandle, State};
+
+use crate::server::{Scheduler, ServerError};
+use crate::types::{Schedule, ScheduledTransition};
+
+#[tauri::command]
+pub fn get_schedule(scheduler: State<'_, Scheduler>) -> Schedule {
+    scheduler.state().schedule()
+}
+
+#[tauri::command]
+pub fn set_schedule(
+    app: AppHandle,
+    scheduler: State<'_, Scheduler>,
+    schedule: Schedule,
+) -> Result<(), ServerError> {
+    scheduler.set_schedule(&app, schedule)
+}
+
+/// Next scheduled starts and stops, in order
+#[tauri::command]
+pub fn upcoming_schedule(
+    scheduler: State<'_, Scheduler>,
+    count: Option<usize>,
+) -> Vec<ScheduledTransition> {
+    scheduler.state().upcoming(count.unwrap_or(5))
+}
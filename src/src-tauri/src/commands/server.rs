@@ -0,0 +1,155 @@
+use tauri::{AppHandle, State};
+
+use crate::config;
+use crate::server::{ServerError, ServerSupervisor};
+use crate::types::{
+    IdlePolicy, InstanceConfig, OrphanAction, PortSelection, RestartPolicy, ServerOptions,
+    ServerState, StartOnLaunch,
+};
+
+/// Start an instance, the main one when `instance` is omitted
+#[tauri::command]
+pub fn start_server(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    model: String,
+    options: ServerOptions,
+    instance: Option<String>,
+) -> Result<ServerState, ServerError> {
+    supervisor
+        .instance_or_main(instance.as_deref())?
+        .start(&app, model, options)
+}
+
+#[tauri::command]
+pub async fn stop_server(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    instance: Option<String>,
+) -> Result<(), ServerError> {
+    supervisor
+        .instance_or_main(instance.as_deref())?
+        .stop(&app)
+        .await
+}
+
+#[tauri::command]
+pub fn server_status(
+    supervisor: State<'_, ServerSupervisor>,
+    instance: Option<String>,
+) -> Result<ServerState, ServerError> {
+    Ok(supervisor.instance_or_main(instance.as_deref())?.state())
+}
+
+/// Remember the model and options the tray should use for its Start action
+#[tauri::command]
+pub fn set_server_launch_config(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    model: String,
+    options: ServerOptions,
+    instance: Option<String>,
+) -> Result<(), ServerError> {
+    supervisor.set_launch_config(&app, instance.as_deref(), model, options)
+}
+
+/// Change the model or options of a live server, restarting it once if needed
+#[tauri::command]
+pub fn reconfigure_server(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    model: String,
+    options: ServerOptions,
+    instance: Option<String>,
+) -> Result<(), ServerError> {
+    supervisor
+        .instance_or_main(instance.as_deref())?
+        .reconfigure(&app, model, options);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_server_instances(supervisor: State<'_, ServerSupervisor>) -> Vec<ServerState> {
+    supervisor.states()
+}
+
+#[tauri::command]
+pub fn add_server_instance(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    instance: InstanceConfig,
+) -> Result<ServerState, ServerError> {
+    supervisor.add_instance(&app, instance)
+}
+
+#[tauri::command]
+pub fn remove_server_instance(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    instance: String,
+) -> Result<(), ServerError> {
+    supervisor.remove_instance(&app, &instance)
+}
+
+#[tauri::command]
+pub fn get_restart_policy(supervisor: State<'_, ServerSupervisor>) -> RestartPolicy {
+    supervisor.restart_policy()
+}
+
+#[tauri::command]
+pub fn set_restart_policy(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    policy: RestartPolicy,
+) -> Result<(), ServerError> {
+    supervisor.set_restart_policy(&app, policy)
+}
+
+#[tauri::command]
+pub fn get_idle_policy(supervisor: State<'_, ServerSupervisor>) -> IdlePolicy {
+    supervisor.idle_policy()
+}
+
+#[tauri::command]
+pub fn set_idle_policy(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    policy: IdlePolicy,
+) -> Result<(), ServerError> {
+    supervisor.set_idle_policy(&app, policy)
+}
+
+#[tauri::command]
+pub fn get_port_selection(supervisor: State<'_, ServerSupervisor>) -> PortSelection {
+    supervisor.port_selection()
+}
+
+#[tauri::command]
+pub fn set_port_selection(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    selection: PortSelection,
+) -> Result<(), ServerError> {
+    supervisor.set_port_selection(&app, selection)
+}
+
+/// Read at startup only, changes apply on the next launch
+#[tauri::command]
+pub fn get_start_on_launch(app: AppHandle) -> StartOnLaunch {
+    config::load(&app).start_on_launch
+}
+
+#[tauri::command]
+pub fn set_start_on_launch(app: AppHandle, settings: StartOnLaunch) -> Result<(), ServerError> {
+    config::save_key(&app, "startOnLaunch", &settings).map_err(|e| ServerError::Io(e.to_string()))
+}
+
+/// Adopt, terminate or avoid the server found running at startup
+#[tauri::command]
+pub async fn resolve_orphan_server(
+    app: AppHandle,
+    supervisor: State<'_, ServerSupervisor>,
+    action: OrphanAction,
+) -> Result<ServerState, ServerError> {
+    supervisor.resolve_orphan(&app, action).await
+}
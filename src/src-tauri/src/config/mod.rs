@@ -0,0 +1,50 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::types::AppConfig;
+
+/// Same file as the frontend `ConfigService` (`BaseDirectory.AppConfig`)
+const CONFIG_FILENAME: &str = "config.json";
+
+fn config_path(app: &AppHandle) -> io::Result<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(CONFIG_FILENAME))
+        .map_err(io::Error::other)
+}
+
+fn read_object(app: &AppHandle) -> io::Result<Map<String, Value>> {
+    let content = match fs::read_to_string(config_path(app)?) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(e),
+    };
+    match serde_json::from_str(&content)? {
+        Value::Object(map) => Ok(map),
+        _ => Ok(Map::new()),
+    }
+}
+
+/// Load the app config, falling back to defaults when the file is missing or invalid
+pub fn load(app: &AppHandle) -> AppConfig {
+    read_object(app)
+        .and_then(|map| Ok(serde_json::from_value(Value::Object(map))?))
+        .unwrap_or_default()
+}
+
+/// Write a single top-level key, keeping the keys owned by the frontend untouched
+pub fn save_key<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> io::Result<()> {
+    let mut map = read_object(app)?;
+    map.insert(key.to_string(), serde_json::to_value(value)?);
+
+    let path = config_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&Value::Object(map))?)
+}
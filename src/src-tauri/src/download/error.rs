@@ -0,0 +1,76 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::types::RemovableModel;
+
+/// Errors returned by the download queue to commands and tray actions
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum DownloadError {
+    /// The model is already waiting or downloading
+    AlreadyQueued(String),
+    /// No pending download of the model to cancel
+    NotQueued(String),
+    /// No failed or cancelled download of the model to retry
+    NotRetryable(String),
+    InvalidModel(String),
+    /// The volume holding the models lacks room for the model and the safety margin
+    #[serde(rename_all = "camelCase")]
+    InsufficientSpace {
+        model: String,
+        required_bytes: u64,
+        available_bytes: u64,
+        /// Installed models whose removal would free enough space, largest first
+        removable: Vec<RemovableModel>,
+    },
+}
+
+impl fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyQueued(model) => write!(f, "{} is already being downloaded", model),
+            Self::NotQueued(model) => write!(f, "No download of {} in progress", model),
+            Self::NotRetryable(model) => write!(f, "No failed download of {} to retry", model),
+            Self::InvalidModel(model) => write!(f, "Invalid model name '{}'", model),
+            Self::InsufficientSpace {
+                model,
+                required_bytes,
+                available_bytes,
+                removable,
+            } => {
+                write!(
+                    f,
+                    "Not enough disk space for {}: {} free, {} required",
+                    model,
+                    format_size(*available_bytes),
+                    format_size(*required_bytes)
+                )?;
+                if !removable.is_empty() {
+                    let names: Vec<&str> = removable.iter().map(|m| m.name.as_str()).collect();
+                    let freed: u64 = removable.iter().map(|m| m.size_bytes).sum();
+                    write!(
+                        f,
+                        ". Removing {} would free {}",
+                        names.join(", "),
+                        format_size(freed)
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+/// `3.4 GB`, or `640 MB` below a gigabyte
+pub fn format_size(bytes: u64) -> String {
+    const GB: f64 = 1e9;
+    const MB: f64 = 1e6;
+    if bytes as f64 >= GB {
+        format!("{:.1} GB", bytes as f64 / GB)
+    } else {
+        format!("{:.0} MB", bytes as f64 / MB)
+    }
+}
@@ -0,0 +1,10 @@
+pub mod error;
+pub mod progress;
+pub mod queue;
+pub mod space;
+
+pub use error::DownloadError;
+pub use queue::DownloadManager;
+
+/// Event emitted with a `DownloadJob` payload whenever a download changes
+pub const DOWNLOAD_EVENT: &str = "download-changed";
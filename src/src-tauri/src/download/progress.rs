@@ -0,0 +1,180 @@
+/// Progress of one `flm pull`, fed with its output line by line.
+/// Understands `Downloading 2/3: <file>`, `Downloading: 45.2%`, `Overall progress: 60%`,
+/// progress bars with a percentage, and speeds such as `12.5 MB/s`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PullProgress {
+    pub current_file: Option<u32>,
+    pub total_files: Option<u32>,
+    pub bytes_per_sec: Option<u64>,
+    /// Overall progress reached before the current file
+    base: Option<f32>,
+    file_percent: f32,
+}
+
+impl PullProgress {
+    /// Returns whether the line reported progress, other lines may explain a failure
+    pub fn update(&mut self, line: &str) -> bool {
+        let lower = line.to_lowercase();
+        let mut matched = false;
+
+        if let Some((current, total)) = file_count(&lower) {
+            self.current_file = Some(current);
+            self.total_files = Some(total);
+            self.base = Some((current - 1) as f32 / total as f32 * 100.0);
+            self.file_percent = 0.0;
+            matched = true;
+        }
+        if let Some(percent) = percent(&lower) {
+            if lower.contains("overall") || lower.contains("total") {
+                self.base = Some(percent);
+                self.file_percent = 0.0;
+            } else {
+                self.base.get_or_insert(0.0);
+                self.file_percent = percent;
+            }
+            matched = true;
+        }
+        if let Some(speed) = speed(&lower) {
+            self.bytes_per_sec = Some(speed);
+            matched = true;
+        }
+        matched
+    }
+
+    /// Overall percentage, `None` until the output reports any
+    pub fn percent(&self) -> Option<f32> {
+        let files = self.total_files.unwrap_or(1).max(1) as f32;
+        self.base
+            .map(|base| (base + self.file_percent / files).clamp(0.0, 100.0))
+    }
+}
+
+/// `2/3` in a line about downloading files
+fn file_count(lower: &str) -> Option<(u32, u32)> {
+    if !lower.contains("download") && !lower.contains("file") {
+        return None;
+    }
+    lower
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c: char| !c.is_ascii_digit()))
+        .filter_map(|token| token.split_once('/'))
+        .find_map(|(current, total)| {
+            let current: u32 = current.parse().ok()?;
+            let total: u32 = total.parse().ok()?;
+            (current >= 1 && current <= total).then_some((current, total))
+        })
+}
+
+/// First `NN.N%` of the line
+fn percent(lower: &str) -> Option<f32> {
+    let end = lower.find('%')?;
+    number_before(&lower[..end])
+        .parse::<f32>()
+        .ok()
+        .filter(|percent| (0.0..=100.0).contains(percent))
+}
+
+/// `12.5MB/s`, `800 KiB/s` or `3 B/s`, in bytes per second
+fn speed(lower: &str) -> Option<u64> {
+    lower.match_indices("/s").find_map(|(index, _)| {
+        let after = lower[index + 2..].chars().next();
+        if after.is_some_and(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let before = &lower[..index];
+        let number = before.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        let multiplier = unit_multiplier(&before[number.len()..])?;
+        let value: f64 = number_before(number.trim_end()).parse().ok()?;
+        Some((value * multiplier) as u64)
+    })
+}
+
+/// Digits and dots ending `text`
+fn number_before(text: &str) -> &str {
+    let rest = text.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    &text[rest.len()..]
+}
+
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    let multiplier = match unit {
+        "b" => 1.0,
+        "k" | "kb" => 1e3,
+        "m" | "mb" => 1e6,
+        "g" | "gb" => 1e9,
+        "kib" => 1024.0,
+        "mib" => 1024.0 * 1024.0,
+        "gib" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(multiplier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(lines: &[&str]) -> PullProgress {
+        let mut progress = PullProgress::default();
+        for line in lines {
+            progress.update(line);
+        }
+        progress
+    }
+
+    #[test]
+    fn combines_file_count_and_file_percent() {
+        let progress = feed(&[
+            "[FLM] Downloading 2/4: model.q4nx",
+            "[FLM] Downloading: 50.0%",
+        ]);
+        assert_eq!(progress.current_file, Some(2));
+        assert_eq!(progress.total_files, Some(4));
+        assert_eq!(progress.percent(), Some(37.5));
+    }
+
+    #[test]
+    fn overall_progress_replaces_the_estimate() {
+        let progress = feed(&[
+            "Downloading 1/2: config.json",
+            "Downloading: 100%",
+            "Overall progress: 50%",
+        ]);
+        assert_eq!(progress.percent(), Some(50.0));
+
+        let progress = feed(&[
+            "Downloading 1/2: config.json",
+            "Overall progress: 50%",
+            "Downloading: 40%",
+        ]);
+        assert_eq!(progress.percent(), Some(70.0));
+    }
+
+    #[test]
+    fn reads_progress_bars_and_speeds() {
+        let mut progress = PullProgress::default();
+        assert!(progress.update("model.q4nx:  45%|████▌     | 1.52G/3.40G [00:12<00:15, 126MB/s]"));
+        assert_eq!(progress.percent(), Some(45.0));
+        assert_eq!(progress.bytes_per_sec, Some(126_000_000));
+
+        progress.update("Downloading: 46% (2.5 MiB/s)");
+        assert_eq!(progress.bytes_per_sec, Some(2_621_440));
+        progress.update("Speed: 800 KB/s");
+        assert_eq!(progress.bytes_per_sec, Some(800_000));
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        let mut progress = PullProgress::default();
+        assert!(!progress.update("[FLM] Model llama3.2:1b is already up to date"));
+        assert!(!progress.update("Error: connection reset by peer"));
+        assert!(!progress.update("Processing 12 it/s"));
+        assert_eq!(progress.percent(), None);
+        assert_eq!(progress, PullProgress::default());
+    }
+
+    #[test]
+    fn skips_impossible_percentages() {
+        let progress = feed(&["Downloading: 40%", "Downloading: 250%"]);
+        assert_eq!(progress.percent(), Some(40.0));
+    }
+}
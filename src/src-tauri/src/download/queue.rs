@@ -0,0 +1,552 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::Notify;
+
+use crate::catalog::{self, ModelStore};
+use crate::config;
+use crate::download::progress::PullProgress;
+use crate::download::space::{self, SystemFsStats};
+use crate::download::{DownloadError, DOWNLOAD_EVENT};
+use crate::server::history::now_millis;
+use crate::server::FLM_PROGRAM;
+use crate::tray::{refresh_download_progress, refresh_tray_menu};
+use crate::types::{DownloadJob, DownloadStatus};
+
+const QUEUE_FILENAME: &str = "downloads.json";
+/// Pulls of one request before it is marked as failed
+const MAX_ATTEMPTS: u32 = 4;
+/// Delay before the first retry, doubled on each attempt
+const RETRY_INITIAL_DELAY: Duration = Duration::from_secs(5);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(120);
+/// Progress events are sent at most this often, status changes always are
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+/// Finished downloads kept for the UI, oldest are dropped first
+const MAX_FINISHED: usize = 20;
+/// Output lines kept to explain a failed pull
+const MAX_ERROR_LINES: usize = 3;
+
+#[derive(Default)]
+struct Inner {
+    path: Option<PathBuf>,
+    /// Downloads in the order they were requested, finished ones included
+    jobs: Vec<DownloadJob>,
+    /// Process of the running pull with its model
+    child: Option<(String, CommandChild)>,
+}
+
+impl Inner {
+    fn job_mut(&mut self, model: &str) -> Option<&mut DownloadJob> {
+        self.jobs.iter_mut().find(|job| job.model == model)
+    }
+}
+
+/// How a pull ended
+enum Outcome {
+    Done,
+    Failed(String),
+    /// Failed in a way a retry cannot fix, e.g. a full disk
+    Refused(String),
+}
+
+enum Next {
+    Start(DownloadJob),
+    /// Retries are waiting, the earliest is due after this delay
+    Wait(Duration),
+    Idle,
+}
+
+/// Persisted FIFO queue of `flm pull`, run one at a time
+#[derive(Default)]
+pub struct DownloadManager {
+    inner: Mutex<Inner>,
+    /// Wakes the worker when a download is queued
+    wake: Notify,
+}
+
+impl DownloadManager {
+    /// Load the queue left by the previous run, interrupted pulls start over
+    pub fn init(&self, app: &AppHandle) {
+        let Ok(path) = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(QUEUE_FILENAME))
+        else {
+            return;
+        };
+        let mut jobs: Vec<DownloadJob> = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        for job in jobs
+            .iter_mut()
+            .filter(|job| job.status == DownloadStatus::Downloading)
+        {
+            job.status = DownloadStatus::Queued;
+            job.bytes_per_sec = None;
+        }
+
+        let mut inner = self.lock();
+        inner.jobs = jobs;
+        inner.path = Some(path);
+    }
+
+    pub fn jobs(&self) -> Vec<DownloadJob> {
+        self.lock().jobs.clone()
+    }
+
+    /// Queued, running and retrying downloads, in queue order
+    pub fn pending(&self) -> Vec<DownloadJob> {
+        self.lock()
+            .jobs
+            .iter()
+            .filter(|job| job.status.is_pending())
+            .cloned()
+            .collect()
+    }
+
+    fn is_pending(&self, model: &str) -> bool {
+        self.lock()
+            .jobs
+            .iter()
+            .any(|job| job.model == model && job.status.is_pending())
+    }
+
+    /// Add a model at the end of the queue, replacing its finished download if any
+    pub fn enqueue(&self, app: &AppHandle, model: &str) -> Result<DownloadJob, DownloadError> {
+        self.push(app, model, false)
+    }
+
+    /// Queue a pull that downloads the files of an installed model again
+    pub fn repair(&self, app: &AppHandle, model: &str) -> Result<DownloadJob, DownloadError> {
+        self.push(app, model, true)
+    }
+
+    fn push(
+        &self,
+        app: &AppHandle,
+        model: &str,
+        force: bool,
+    ) -> Result<DownloadJob, DownloadError> {
+        let model = model.trim();
+        if !is_model_name(model) {
+            return Err(DownloadError::InvalidModel(model.to_string()));
+        }
+
+        if self.is_pending(model) {
+            return Err(DownloadError::AlreadyQueued(model.to_string()));
+        }
+        if !force {
+            // A repair rewrites files already counted as used space
+            self.preflight(app, model, true)?;
+        }
+
+        let job = {
+            let mut inner = self.lock();
+            if let Some(index) = inner.jobs.iter().position(|job| job.model == model) {
+                if inner.jobs[index].status.is_pending() {
+                    return Err(DownloadError::AlreadyQueued(model.to_string()));
+                }
+                inner.jobs.remove(index);
+            }
+            let job = DownloadJob {
+                force,
+                ..DownloadJob::new(model, now_millis())
+            };
+            inner.jobs.push(job.clone());
+            let _ = save(&inner);
+            job
+        };
+        emit(app, &job);
+        self.wake.notify_one();
+        Ok(job)
+    }
+
+    /// Drop a pending download, killing its pull when it is running
+    pub fn cancel(&self, app: &AppHandle, model: &str) -> Result<DownloadJob, DownloadError> {
+        let (job, child) = {
+            let mut inner = self.lock();
+            let job = inner
+                .job_mut(model)
+                .filter(|job| job.status.is_pending())
+                .ok_or_else(|| DownloadError::NotQueued(model.to_string()))?;
+            job.status = DownloadStatus::Cancelled;
+            job.finished_at = Some(now_millis());
+            job.retry_at = None;
+            job.bytes_per_sec = None;
+            let job = job.clone();
+
+            let running = inner
+                .child
+                .as_ref()
+                .is_some_and(|(running, _)| running == model);
+            let child = if running { inner.child.take() } else { None };
+            trim_finished(&mut inner.jobs);
+            let _ = save(&inner);
+            (job, child)
+        };
+
+        if let Some((_, child)) = child {
+            let _ = child.kill();
+        }
+        emit(app, &job);
+        Ok(job)
+    }
+
+    /// Queue a failed or cancelled download again, with a fresh retry budget
+    pub fn retry(&self, app: &AppHandle, model: &str) -> Result<DownloadJob, DownloadError> {
+        let force = self
+            .lock()
+            .jobs
+            .iter()
+            .find(|job| {
+                job.model == model
+                    && matches!(
+                        job.status,
+                        DownloadStatus::Failed | DownloadStatus::Cancelled
+                    )
+            })
+            .map(|job| job.force)
+            .ok_or_else(|| DownloadError::NotRetryable(model.to_string()))?;
+        self.push(app, model, force)
+    }
+
+    /// Check that the model fits on the volume holding the models.
+    /// When `queued`, the downloads ahead in the queue keep the room they still need.
+    /// Skipped when the catalog, the model size or the volume is unknown.
+    fn preflight(&self, app: &AppHandle, model: &str, queued: bool) -> Result<(), DownloadError> {
+        let Ok(catalog) =
+            catalog::locate(&config::load(app).flm_path).and_then(|path| catalog::load(&path))
+        else {
+            return Ok(());
+        };
+        let size_of = |name: &str| {
+            catalog
+                .models
+                .iter()
+                .find(|info| info.name == name)
+                .map(|info| info.size_bytes)
+        };
+        let (Some(model_path), Some(info)) = (
+            catalog.model_path.as_deref(),
+            catalog.models.iter().find(|info| info.name == model),
+        ) else {
+            return Ok(());
+        };
+
+        let reserved = if queued {
+            self.pending()
+                .iter()
+                .filter(|job| job.model != model)
+                .filter_map(|job| {
+                    let left = 1.0 - f64::from(job.percent.unwrap_or(0.0)) / 100.0;
+                    size_of(&job.model).map(|size| (size as f64 * left) as u64)
+                })
+                .sum()
+        } else {
+            0
+        };
+        let installed = app
+            .state::<ModelStore>()
+            .inventory()
+            .map(|inventory| inventory.installed)
+            .unwrap_or_default();
+        space::check(&SystemFsStats, model_path, info, reserved, &installed)
+    }
+
+    /// Kill the running pull without touching the queue, it restarts on the next launch
+    pub fn abort(&self) {
+        if let Some((_, child)) = self.lock().child.take() {
+            let _ = child.kill();
+        }
+    }
+
+    /// Mark the first due download as started
+    fn next(&self, now: u64) -> Next {
+        let mut inner = self.lock();
+        let due = inner.jobs.iter_mut().find(|job| match job.status {
+            DownloadStatus::Queued => true,
+            DownloadStatus::Retrying => job.retry_at.is_none_or(|at| at <= now),
+            _ => false,
+        });
+        if let Some(job) = due {
+            job.status = DownloadStatus::Downloading;
+            job.attempts += 1;
+            job.started_at = Some(now);
+            job.retry_at = None;
+            job.percent = None;
+            job.bytes_per_sec = None;
+            job.current_file = None;
+            job.total_files = None;
+            let job = job.clone();
+            let _ = save(&inner);
+            return Next::Start(job);
+        }
+
+        inner
+            .jobs
+            .iter()
+            .filter_map(|job| job.retry_at)
+            .min()
+            .map_or(Next::Idle, |at| {
+                Next::Wait(Duration::from_millis(at.saturating_sub(now)))
+            })
+    }
+
+    /// Keep the process of a started pull, unless it was cancelled meanwhile
+    fn attach(&self, model: &str, child: CommandChild) -> Result<(), CommandChild> {
+        let mut inner = self.lock();
+        let running = inner
+            .jobs
+            .iter()
+            .any(|job| job.model == model && job.status == DownloadStatus::Downloading);
+        if !running {
+            return Err(child);
+        }
+        inner.child = Some((model.to_string(), child));
+        Ok(())
+    }
+
+    fn set_progress(&self, app: &AppHandle, model: &str, progress: &PullProgress) {
+        let job = {
+            let mut inner = self.lock();
+            let Some(job) = inner
+                .job_mut(model)
+                .filter(|job| job.status == DownloadStatus::Downloading)
+            else {
+                return;
+            };
+            job.percent = progress.percent();
+            job.bytes_per_sec = progress.bytes_per_sec;
+            job.current_file = progress.current_file;
+            job.total_files = progress.total_files;
+            job.clone()
+        };
+        let _ = app.emit(DOWNLOAD_EVENT, &job);
+        refresh_download_progress(app);
+    }
+
+    /// Record the end of a pull, scheduling a retry when attempts are left
+    fn finish(&self, app: &AppHandle, model: &str, outcome: Outcome) {
+        let job = {
+            let mut inner = self.lock();
+            if inner
+                .child
+                .as_ref()
+                .is_some_and(|(running, _)| running == model)
+            {
+                inner.child = None;
+            }
+            let now = now_millis();
+            let Some(job) = inner
+                .job_mut(model)
+                .filter(|job| job.status == DownloadStatus::Downloading)
+            else {
+                // Cancelled, the status is already set
+                return;
+            };
+
+            job.bytes_per_sec = None;
+            match outcome {
+                Outcome::Done => {
+                    job.status = DownloadStatus::Completed;
+                    job.percent = Some(100.0);
+                    job.error = None;
+                    job.finished_at = Some(now);
+                }
+                Outcome::Failed(error) if job.attempts < MAX_ATTEMPTS => {
+                    job.status = DownloadStatus::Retrying;
+                    job.error = Some(error);
+                    job.retry_at = Some(now + retry_delay(job.attempts).as_millis() as u64);
+                }
+                Outcome::Failed(error) | Outcome::Refused(error) => {
+                    job.status = DownloadStatus::Failed;
+                    job.error = Some(error);
+                    job.finished_at = Some(now);
+                }
+            }
+            let job = job.clone();
+            trim_finished(&mut inner.jobs);
+            let _ = save(&inner);
+            job
+        };
+
+        emit(app, &job);
+        if job.status == DownloadStatus::Completed {
+            catalog::store::spawn_refresh(app);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("download queue lock poisoned")
+    }
+}
+
+/// Run the queued downloads one after the other for the lifetime of the app
+pub fn spawn(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let manager = app.state::<DownloadManager>();
+        loop {
+            match manager.next(now_millis()) {
+                Next::Start(job) => {
+                    emit(&app, &job);
+                    // Earlier downloads may have used the space checked when it was queued
+                    let preflight = if job.force {
+                        Ok(())
+                    } else {
+                        manager.preflight(&app, &job.model, false)
+                    };
+                    let outcome = match preflight {
+                        Err(e) => Outcome::Refused(e.to_string()),
+                        Ok(()) => match pull(&app, &manager, &job.model, job.force).await {
+                            Ok(()) => Outcome::Done,
+                            Err(e) => Outcome::Failed(e),
+                        },
+                    };
+                    manager.finish(&app, &job.model, outcome);
+                }
+                Next::Wait(delay) => {
+                    let _ = tokio::time::timeout(delay, manager.wake.notified()).await;
+                }
+                Next::Idle => manager.wake.notified().await,
+            }
+        }
+    });
+}
+
+/// Run `flm pull` until it exits, reporting its progress.
+/// `force` downloads the files again even when the model looks installed.
+async fn pull(
+    app: &AppHandle,
+    manager: &DownloadManager,
+    model: &str,
+    force: bool,
+) -> Result<(), String> {
+    let mut args = vec!["pull", model];
+    if force {
+        args.push("--force");
+    }
+    let (mut rx, child) = app
+        .shell()
+        .command(FLM_PROGRAM)
+        .args(args)
+        // Progress bars redraw with `\r`, line buffering would hold them back
+        .set_raw_out(true)
+        .spawn()
+        .map_err(|e| format!("Failed to start flm: {}", e))?;
+    if let Err(child) = manager.attach(model, child) {
+        let _ = child.kill();
+        return Ok(());
+    }
+
+    let mut progress = PullProgress::default();
+    let mut errors: VecDeque<String> = VecDeque::new();
+    let mut pending = String::new();
+    let mut last_emit: Option<Instant> = None;
+    let mut exit_code = None;
+
+    while let Some(event) = rx.recv().await {
+        match event {
+            CommandEvent::Stdout(bytes) | CommandEvent::Stderr(bytes) => {
+                pending.push_str(&String::from_utf8_lossy(&bytes));
+                // The last segment may be the start of a line
+                let Some(end) = pending.rfind(['\r', '\n']) else {
+                    continue;
+                };
+                let complete: String = pending.drain(..=end).collect();
+                let mut changed = false;
+                for line in complete.split(['\r', '\n']).map(str::trim) {
+                    if line.is_empty() {
+                        continue;
+                    }
+                    if progress.update(line) {
+                        changed = true;
+                    } else {
+                        keep_error_line(&mut errors, line);
+                    }
+                }
+                if changed && last_emit.is_none_or(|at| at.elapsed() >= PROGRESS_INTERVAL) {
+                    manager.set_progress(app, model, &progress);
+                    last_emit = Some(Instant::now());
+                }
+            }
+            CommandEvent::Error(error) => keep_error_line(&mut errors, &error),
+            CommandEvent::Terminated(payload) => {
+                exit_code = payload.code;
+                break;
+            }
+            _ => {}
+        }
+    }
+    if !pending.trim().is_empty() {
+        keep_error_line(&mut errors, pending.trim());
+    }
+
+    match exit_code {
+        Some(0) => Ok(()),
+        code if errors.is_empty() => Err(format!(
+            "flm pull exited with code {}",
+            code.map_or_else(|| String::from("unknown"), |code| code.to_string())
+        )),
+        _ => Err(Vec::from(errors).join("\n")),
+    }
+}
+
+fn keep_error_line(errors: &mut VecDeque<String>, line: &str) {
+    if errors.len() == MAX_ERROR_LINES {
+        errors.pop_front();
+    }
+    errors.push_back(line.to_string());
+}
+
+/// Exponential backoff for the given attempt (1-based)
+fn retry_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    RETRY_INITIAL_DELAY
+        .saturating_mul(factor)
+        .min(RETRY_MAX_DELAY)
+}
+
+/// `family:tag`, also keeps arguments out of the `flm pull` command line
+fn is_model_name(model: &str) -> bool {
+    !model.is_empty()
+        && !model.starts_with('-')
+        && model
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '.' | '-' | '_'))
+}
+
+fn trim_finished(jobs: &mut Vec<DownloadJob>) {
+    let mut finished = jobs.iter().filter(|job| !job.status.is_pending()).count();
+    jobs.retain(|job| {
+        if finished > MAX_FINISHED && !job.status.is_pending() {
+            finished -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+/// Broadcast a status change, the tray menu is rebuilt to add or drop the download
+fn emit(app: &AppHandle, job: &DownloadJob) {
+    let _ = app.emit(DOWNLOAD_EVENT, job);
+    refresh_tray_menu(app);
+}
+
+fn save(inner: &Inner) -> io::Result<()> {
+    let Some(path) = inner.path.as_ref() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string(&inner.jobs)?)
+}
@@ -0,0 +1,268 @@
+use std::path::Path;
+
+use crate::download::DownloadError;
+use crate::types::{InventoryEntry, ModelInfo, RemovableModel};
+
+/// Extra room kept free on top of the model, a tenth of its size but at least this much
+const MIN_SAFETY_MARGIN: u64 = 512 * 1024 * 1024;
+
+/// Free space of the volume holding a path, behind a trait so tests can fake the disks
+pub trait FsStats {
+    /// `None` when the volume cannot be found, the check is then skipped
+    fn available_space(&self, path: &Path) -> Option<u64>;
+}
+
+/// Disks of the machine as reported by the OS
+pub struct SystemFsStats;
+
+impl FsStats for SystemFsStats {
+    fn available_space(&self, path: &Path) -> Option<u64> {
+        // The model directory may not exist before the first pull
+        let existing = path.ancestors().find(|dir| dir.exists())?;
+        let path = existing.canonicalize().ok()?;
+        let disks = sysinfo::Disks::new_with_refreshed_list();
+        disks
+            .list()
+            .iter()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
+            .map(|disk| disk.available_space())
+    }
+}
+
+/// Bytes needed to download a model of the given size
+pub fn required_bytes(size_bytes: u64) -> u64 {
+    size_bytes + (size_bytes / 10).max(MIN_SAFETY_MARGIN)
+}
+
+/// Refuse a pull that would not fit next to the downloads already queued (`reserved` bytes).
+/// The error suggests installed models to remove, the largest first, until the gap is covered.
+pub fn check(
+    stats: &impl FsStats,
+    model_path: &Path,
+    model: &ModelInfo,
+    reserved: u64,
+    installed: &[InventoryEntry],
+) -> Result<(), DownloadError> {
+    if model.size_bytes == 0 {
+        // Unknown size, nothing to compare
+        return Ok(());
+    }
+    let Some(available) = stats.available_space(model_path) else {
+        return Ok(());
+    };
+    let required = required_bytes(model.size_bytes).saturating_add(reserved);
+    if available >= required {
+        return Ok(());
+    }
+
+    Err(DownloadError::InsufficientSpace {
+        model: model.name.clone(),
+        required_bytes: required,
+        available_bytes: available,
+        removable: removable_models(installed, &model.name, required - available),
+    })
+}
+
+/// Fewest installed models covering `missing` bytes, all of them when they cannot
+fn removable_models(
+    installed: &[InventoryEntry],
+    exclude: &str,
+    missing: u64,
+) -> Vec<RemovableModel> {
+    let mut candidates: Vec<RemovableModel> = installed
+        .iter()
+        .filter(|entry| entry.name != exclude)
+        .filter_map(|entry| {
+            let info = entry.info.as_ref()?;
+            (info.size_bytes > 0).then(|| RemovableModel {
+                name: entry.name.clone(),
+                size_bytes: info.size_bytes,
+            })
+        })
+        .collect();
+    candidates.sort_by_key(|model| std::cmp::Reverse(model.size_bytes));
+
+    let mut freed = 0;
+    candidates
+        .into_iter()
+        .take_while(|model| {
+            let needed = freed < missing;
+            freed += model.size_bytes;
+            needed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::types::{DiskState, ModelCapabilities};
+
+    const GB: u64 = 1_000_000_000;
+
+    /// Volumes mounted at fixed paths with a fixed amount of free space
+    struct FakeFsStats(Vec<(PathBuf, u64)>);
+
+    impl FsStats for FakeFsStats {
+        fn available_space(&self, path: &Path) -> Option<u64> {
+            self.0
+                .iter()
+                .filter(|(mount, _)| path.starts_with(mount))
+                .max_by_key(|(mount, _)| mount.as_os_str().len())
+                .map(|(_, free)| *free)
+        }
+    }
+
+    fn model(name: &str, size_bytes: u64) -> ModelInfo {
+        let (family, tag) = name.split_once(':').unwrap();
+        ModelInfo {
+            name: name.to_string(),
+            family: family.to_string(),
+            tag: tag.to_string(),
+            description: None,
+            dir_name: None,
+            url: None,
+            size_bytes,
+            modified_at: None,
+            context_length: None,
+            parameter_size: None,
+            quantization: None,
+            format: None,
+            capabilities: ModelCapabilities::default(),
+            files: Vec::new(),
+            checksums: Default::default(),
+        }
+    }
+
+    fn installed(name: &str, size_bytes: u64) -> InventoryEntry {
+        InventoryEntry {
+            name: name.to_string(),
+            info: Some(model(name, size_bytes)),
+            disk: DiskState::Complete,
+            issue: None,
+        }
+    }
+
+    fn stats() -> FakeFsStats {
+        FakeFsStats(vec![
+            (PathBuf::from("/"), 100 * GB),
+            (PathBuf::from("/models"), 5 * GB),
+        ])
+    }
+
+    #[test]
+    fn accepts_a_model_that_fits_with_its_margin() {
+        let result = check(
+            &stats(),
+            Path::new("/models/flm"),
+            &model("llama3.2:1b", 2 * GB),
+            0,
+            &[],
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn refuses_with_free_and_required_bytes() {
+        let installed = [
+            installed("qwen3:8b", 5 * GB),
+            installed("llama3.2:1b", GB),
+            installed("gemma3:4b", 3 * GB),
+        ];
+        let error = check(
+            &stats(),
+            Path::new("/models/flm"),
+            &model("gpt-oss:20b", 6 * GB),
+            0,
+            &installed,
+        )
+        .unwrap_err();
+
+        let DownloadError::InsufficientSpace {
+            model,
+            required_bytes,
+            available_bytes,
+            removable,
+        } = &error
+        else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(model, "gpt-oss:20b");
+        assert_eq!(*available_bytes, 5 * GB);
+        assert_eq!(*required_bytes, 6 * GB + 600_000_000);
+        // The largest model alone covers the 1.6 GB gap
+        assert_eq!(
+            removable,
+            &[RemovableModel {
+                name: String::from("qwen3:8b"),
+                size_bytes: 5 * GB,
+            }]
+        );
+        assert_eq!(
+            error.to_string(),
+            "Not enough disk space for gpt-oss:20b: 5.0 GB free, 6.6 GB required. \
+             Removing qwen3:8b would free 5.0 GB"
+        );
+    }
+
+    #[test]
+    fn small_models_keep_the_minimum_margin() {
+        assert_eq!(required_bytes(GB), GB + MIN_SAFETY_MARGIN);
+        let result = check(
+            &FakeFsStats(vec![(PathBuf::from("/"), GB + 100_000_000)]),
+            Path::new("/models"),
+            &model("embed-gemma:300m", GB),
+            0,
+            &[],
+        );
+        assert!(matches!(
+            result,
+            Err(DownloadError::InsufficientSpace { .. })
+        ));
+    }
+
+    #[test]
+    fn counts_downloads_already_queued() {
+        let target = model("gemma3:4b", 3 * GB);
+        assert!(check(&stats(), Path::new("/models"), &target, 0, &[]).is_ok());
+        assert!(check(&stats(), Path::new("/models"), &target, 2 * GB, &[]).is_err());
+    }
+
+    #[test]
+    fn suggests_several_models_and_skips_the_target() {
+        let installed = [
+            installed("gemma3:4b", 3 * GB),
+            installed("llama3.2:1b", GB),
+            installed("qwen3:8b", 2 * GB),
+            installed("phi4-mini:3.8b", 2 * GB),
+        ];
+        let Err(DownloadError::InsufficientSpace { removable, .. }) = check(
+            &FakeFsStats(vec![(PathBuf::from("/"), GB)]),
+            Path::new("/models"),
+            &model("gemma3:4b", 3 * GB),
+            0,
+            &installed,
+        ) else {
+            panic!("expected an insufficient space error");
+        };
+        let names: Vec<&str> = removable.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["qwen3:8b", "phi4-mini:3.8b"]);
+    }
+
+    #[test]
+    fn skips_the_check_without_size_or_volume() {
+        let empty = FakeFsStats(Vec::new());
+        assert!(check(
+            &empty,
+            Path::new("/models"),
+            &model("a:b", 100 * GB),
+            0,
+            &[]
+        )
+        .is_ok());
+        assert!(check(&stats(), Path::new("/models"), &model("a:b", 0), 0, &[]).is_ok());
+    }
+}
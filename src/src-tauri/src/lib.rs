@@ -1,4 +1,8 @@
+mod catalog;
 mod commands;
+mod config;
+mod download;
+mod server;
 mod tray;
 mod types;
 
@@ -26,17 +30,94 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_dialog::init())
+        .manage(server::ServerSupervisor::default())
+        .manage(tray::TrayState::default())
+        .manage(tray::removal::PendingRemovals::default())
+        .manage(server::ShutdownState::default())
+        .manage(server::LogStore::default())
+        .manage(server::SessionHistory::default())
+        .manage(catalog::ModelStore::default())
+        .manage(download::DownloadManager::default())
+        .manage(server::Scheduler::default())
         .setup(|app| {
+            if let Ok(log_dir) = app.path().app_log_dir() {
+                app.state::<server::LogStore>().init(log_dir);
+            }
+            app.state::<server::SessionHistory>().init(app.handle());
+            app.state::<download::DownloadManager>().init(app.handle());
+            let config = config::load(app.handle());
+            app.state::<server::ServerSupervisor>().init_config(&config);
+            app.state::<server::Scheduler>().init_config(&config);
+            app.state::<tray::TrayState>().init_config(&config);
+
             tray::init_tray(app)?;
+            // A previous session may have left `flm serve` running. Looked for in the
+            // background, the schedule and the start on launch wait for the outcome.
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let orphan = server::orphan::detect(&config.server_options).await;
+                handle
+                    .state::<server::ServerSupervisor>()
+                    .set_orphan(&handle, orphan);
+                server::scheduler::spawn(&handle);
+                server::launch::spawn(&handle, &config);
+            });
+            catalog::store::spawn_refresh(app.handle());
+            download::queue::spawn(app.handle());
+            catalog::watcher::spawn(app.handle());
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![commands::update_tray_menu])
+        .invoke_handler(tauri::generate_handler![
+            commands::update_tray_menu,
+            commands::start_server,
+            commands::stop_server,
+            commands::server_status,
+            commands::set_server_launch_config,
+            commands::reconfigure_server,
+            commands::list_server_instances,
+            commands::add_server_instance,
+            commands::remove_server_instance,
+            commands::get_restart_policy,
+            commands::set_restart_policy,
+            commands::get_port_selection,
+            commands::set_port_selection,
+            commands::get_idle_policy,
+            commands::set_idle_policy,
+            commands::get_start_on_launch,
+            commands::set_start_on_launch,
+            commands::resolve_orphan_server,
+            commands::get_model_catalog,
+            commands::model_inventory,
+            commands::verify_model,
+            commands::repair_models,
+            commands::estimate_memory,
+            commands::get_hide_unfit_models,
+            commands::set_hide_unfit_models,
+            commands::list_downloads,
+            commands::queue_download,
+            commands::cancel_download,
+            commands::retry_download,
+            commands::list_log_sessions,
+            commands::server_history,
+            commands::read_server_log,
+            commands::tail_server_log,
+            commands::get_schedule,
+            commands::set_schedule,
+            commands::upcoming_schedule
+        ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let _ = window.hide();
                 api.prevent_close();
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            tauri::RunEvent::ExitRequested { code, api, .. } => {
+                server::shutdown::on_exit_requested(app, code, &api);
+            }
+            tauri::RunEvent::Exit => server::shutdown::on_exit(app),
+            _ => {}
+        });
 }
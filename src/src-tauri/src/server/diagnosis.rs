@@ -0,0 +1,218 @@
+use crate::types::Diagnosis;
+
+/// Output phrases of each diagnosis, checked in this order on lowercase lines.
+/// Phrases name their subject: hints such as "run `flm pull`" and generic
+/// "in use" messages appear in healthy output too.
+const PATTERNS: [(Diagnosis, &[&str]); 5] = [
+    (
+        Diagnosis::PortInUse,
+        &[
+            "address already in use",
+            "only one usage of each socket address",
+            "port is already in use",
+            "eaddrinuse",
+            "os error 10048",
+        ],
+    ),
+    (
+        Diagnosis::ModelNotInstalled,
+        &[
+            "model not installed",
+            "model is not installed",
+            "model not found",
+            "no such model",
+            "unknown model",
+            "model files are missing",
+        ],
+    ),
+    (
+        Diagnosis::NpuBusy,
+        &[
+            "npu is busy",
+            "npu busy",
+            "device or resource busy",
+            "npu device is busy",
+            "npu is in use",
+        ],
+    ),
+    (
+        Diagnosis::NpuUnavailable,
+        &[
+            "npu not found",
+            "no npu device",
+            "npu driver",
+            "failed to open device",
+        ],
+    ),
+    (
+        Diagnosis::OutOfMemory,
+        &[
+            "out of memory",
+            "bad_alloc",
+            "insufficient memory",
+            "cannot allocate memory",
+        ],
+    ),
+];
+
+const CRASH_PATTERNS: [&str; 4] = [
+    "segmentation fault",
+    "access violation",
+    "terminate called",
+    "panicked",
+];
+
+/// Windows status codes of a process that crashed: access violation, stack overflow,
+/// stack buffer overrun, heap corruption
+const CRASH_CODES: [u32; 4] = [0xC000_0005, 0xC000_00FD, 0xC000_0409, 0xC000_0374];
+
+/// Whether a line points to a known cause, kept even when its severity is only informational
+pub fn is_relevant(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    PATTERNS
+        .iter()
+        .flat_map(|(_, patterns)| patterns.iter())
+        .chain(CRASH_PATTERNS.iter())
+        .any(|pattern| lower.contains(pattern))
+}
+
+/// Most likely cause of a failed session, the latest matching line wins
+pub fn diagnose(exit_code: Option<i32>, lines: &[String]) -> Diagnosis {
+    for line in lines.iter().rev() {
+        let lower = line.to_lowercase();
+        if let Some((diagnosis, _)) = PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|p| lower.contains(p)))
+        {
+            return *diagnosis;
+        }
+    }
+
+    let crashed_output = lines.iter().any(|line| {
+        let lower = line.to_lowercase();
+        CRASH_PATTERNS.iter().any(|p| lower.contains(p))
+    });
+    // No exit code for adopted servers, nothing tells a crash from a kill
+    let crashed_code = exit_code.is_some_and(|code| CRASH_CODES.contains(&(code as u32)));
+    if crashed_output || crashed_code {
+        Diagnosis::Crashed
+    } else {
+        Diagnosis::Unknown
+    }
+}
+
+impl Diagnosis {
+    /// English summary for the log, the UI has its own translations
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Diagnosis::PortInUse => "port in use",
+            Diagnosis::ModelNotInstalled => "model not installed",
+            Diagnosis::NpuBusy => "NPU busy",
+            Diagnosis::NpuUnavailable => "NPU unavailable",
+            Diagnosis::OutOfMemory => "out of memory",
+            Diagnosis::Crashed => "crashed",
+            Diagnosis::Unknown => "unknown error",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    /// Last output lines of failed sessions, with the expected diagnosis
+    fn samples() -> Vec<(Option<i32>, Vec<&'static str>, Diagnosis)> {
+        vec![
+            (
+                Some(1),
+                vec!["[ERROR] bind: Address already in use"],
+                Diagnosis::PortInUse,
+            ),
+            (
+                Some(1),
+                vec!["Only one usage of each socket address (protocol/network address/port) is normally permitted. (os error 10048)"],
+                Diagnosis::PortInUse,
+            ),
+            (
+                Some(1),
+                vec!["[ERROR] Model is not installed: qwen3:8b, run `flm pull qwen3:8b`"],
+                Diagnosis::ModelNotInstalled,
+            ),
+            (
+                Some(1),
+                vec!["Unknown model 'qwen9:1b'"],
+                Diagnosis::ModelNotInstalled,
+            ),
+            (
+                Some(1),
+                vec!["[ERROR] NPU is busy, close the other application"],
+                Diagnosis::NpuBusy,
+            ),
+            (
+                Some(1),
+                vec!["[ERROR] NPU driver not installed or too old"],
+                Diagnosis::NpuUnavailable,
+            ),
+            (
+                Some(1),
+                vec!["std::bad_alloc"],
+                Diagnosis::OutOfMemory,
+            ),
+            (
+                Some(139),
+                vec!["Segmentation fault (core dumped)"],
+                Diagnosis::Crashed,
+            ),
+            (
+                Some(0xC000_0005_u32 as i32),
+                vec![],
+                Diagnosis::Crashed,
+            ),
+            (Some(1), vec!["[ERROR] something odd"], Diagnosis::Unknown),
+            // Adopted servers have no exit code
+            (None, vec![], Diagnosis::Unknown),
+        ]
+    }
+
+    #[test]
+    fn diagnoses_known_failures() {
+        for (exit_code, output, expected) in samples() {
+            assert_eq!(
+                diagnose(exit_code, &lines(&output)),
+                expected,
+                "{:?} {:?}",
+                exit_code,
+                output
+            );
+        }
+    }
+
+    #[test]
+    fn latest_matching_line_wins() {
+        let output = lines(&[
+            "[ERROR] NPU is busy",
+            "Retrying...",
+            "[ERROR] out of memory",
+            "[INFO] shutting down",
+        ]);
+        assert_eq!(diagnose(Some(1), &output), Diagnosis::OutOfMemory);
+    }
+
+    #[test]
+    fn hints_and_generic_messages_are_not_causes() {
+        for line in [
+            "Tip: run `flm pull <model>` to download more models",
+            "The file is in use by another process",
+            "Whisper not installed, ASR disabled",
+        ] {
+            assert!(!is_relevant(line), "{}", line);
+            assert_eq!(diagnose(Some(1), &lines(&[line])), Diagnosis::Unknown);
+        }
+        assert!(is_relevant("Address already in use"));
+        assert!(is_relevant("thread 'main' panicked at src/main.rs"));
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::types::NpuResource;
+
+/// Errors returned by the server supervisor to commands and tray actions
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum ServerError {
+    AlreadyRunning,
+    NotRunning,
+    Spawn(String),
+    Io(String),
+    Orphan(String),
+    /// The port is held by another process and no free port could be selected
+    PortInUse {
+        port: u16,
+        pid: Option<u32>,
+        process: Option<String>,
+    },
+    UnknownInstance(String),
+    InvalidInstance(String),
+    /// Another instance already holds this NPU resource
+    NpuConflict {
+        instance: String,
+        resource: NpuResource,
+    },
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyRunning => write!(f, "Server is already running"),
+            Self::NotRunning => write!(f, "Server is not running"),
+            Self::Spawn(e) => write!(f, "Failed to start flm: {}", e),
+            Self::Io(e) => write!(f, "Server I/O error: {}", e),
+            Self::Orphan(e) => write!(f, "Existing server: {}", e),
+            Self::PortInUse { port, pid, process } => {
+                write!(f, "Port {} is already in use", port)?;
+                match (process, pid) {
+                    (Some(process), Some(pid)) => write!(f, " by {} (PID: {})", process, pid),
+                    (None, Some(pid)) => write!(f, " by PID {}", pid),
+                    _ => Ok(()),
+                }
+            }
+            Self::UnknownInstance(id) => write!(f, "Unknown server instance '{}'", id),
+            Self::InvalidInstance(e) => write!(f, "Invalid server instance: {}", e),
+            Self::NpuConflict { instance, resource } => {
+                let resource = match resource {
+                    NpuResource::Llm => "the language model",
+                    NpuResource::Asr => "speech recognition",
+                    NpuResource::Embeddings => "embeddings",
+                };
+                write!(
+                    f,
+                    "The NPU is already serving {} for instance '{}'",
+                    resource, instance
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServerError {}
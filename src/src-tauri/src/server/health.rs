@@ -0,0 +1,157 @@
+use std::time::{Duration, Instant};
+
+use tauri_plugin_http::reqwest::Client;
+
+use crate::types::ServerHealth;
+
+/// Delay between probes while the model is loading
+pub const STARTUP_PROBE_INTERVAL: Duration = Duration::from_millis(500);
+/// Delay between probes once the server is ready
+pub const HEALTH_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const SLOW_RESPONSE: Duration = Duration::from_secs(1);
+const UNREACHABLE_AFTER: u32 = 3;
+
+/// Result of a single request to the OpenAI-compatible endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// 2xx answered in time
+    Healthy,
+    /// Answered, but slowly or with an error status
+    Impaired,
+    /// Connection refused, reset or timed out
+    Failed,
+}
+
+pub fn probe_client() -> Client {
+    Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// Query `/v1/models` on the given host and port
+pub async fn probe(client: &Client, host: &str, port: u16) -> ProbeOutcome {
+    let url = format!("http://{}:{}/v1/models", probe_host(host), port);
+    let started = Instant::now();
+
+    match client.get(&url).send().await {
+        Ok(response) if response.status().is_success() => {
+            if started.elapsed() > SLOW_RESPONSE {
+                ProbeOutcome::Impaired
+            } else {
+                ProbeOutcome::Healthy
+            }
+        }
+        Ok(_) => ProbeOutcome::Impaired,
+        Err(_) => ProbeOutcome::Failed,
+    }
+}
+
+/// Wildcard bind addresses are not connectable, probe the loopback instead
+fn probe_host(host: &str) -> &str {
+    match host {
+        "0.0.0.0" | "" => "127.0.0.1",
+        "::" | "[::]" => "[::1]",
+        _ => host,
+    }
+}
+
+/// Turns probe outcomes into a health state, tolerating isolated failures
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    consecutive_failures: u32,
+}
+
+impl HealthTracker {
+    pub fn record(&mut self, outcome: ProbeOutcome) -> ServerHealth {
+        match outcome {
+            ProbeOutcome::Healthy => {
+                self.consecutive_failures = 0;
+                ServerHealth::Ready
+            }
+            ProbeOutcome::Impaired => {
+                self.consecutive_failures = 0;
+                ServerHealth::Degraded
+            }
+            ProbeOutcome::Failed => {
+                self.consecutive_failures += 1;
+                if self.consecutive_failures >= UNREACHABLE_AFTER {
+                    ServerHealth::Unreachable
+                } else {
+                    ServerHealth::Degraded
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Minimal stand-in for `flm serve`: answers one request with the given status line
+    fn serve_once(status_line: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            if let Ok((mut stream, _)) = listener.accept() {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                let body = r#"{"object":"list","data":[]}"#;
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status_line,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        port
+    }
+
+    fn free_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    #[tokio::test]
+    async fn probe_reports_healthy_on_success() {
+        let port = serve_once("200 OK");
+        let outcome = probe(&probe_client(), "127.0.0.1", port).await;
+        assert_eq!(outcome, ProbeOutcome::Healthy);
+    }
+
+    #[tokio::test]
+    async fn probe_reports_impaired_on_error_status() {
+        let port = serve_once("503 Service Unavailable");
+        let outcome = probe(&probe_client(), "0.0.0.0", port).await;
+        assert_eq!(outcome, ProbeOutcome::Impaired);
+    }
+
+    #[tokio::test]
+    async fn probe_reports_failed_without_listener() {
+        let port = free_port();
+        let outcome = probe(&probe_client(), "127.0.0.1", port).await;
+        assert_eq!(outcome, ProbeOutcome::Failed);
+    }
+
+    #[test]
+    fn tracker_becomes_unreachable_after_repeated_failures() {
+        let mut tracker = HealthTracker::default();
+        assert_eq!(tracker.record(ProbeOutcome::Failed), ServerHealth::Degraded);
+        assert_eq!(tracker.record(ProbeOutcome::Failed), ServerHealth::Degraded);
+        assert_eq!(
+            tracker.record(ProbeOutcome::Failed),
+            ServerHealth::Unreachable
+        );
+        assert_eq!(tracker.record(ProbeOutcome::Healthy), ServerHealth::Ready);
+        assert_eq!(tracker.record(ProbeOutcome::Failed), ServerHealth::Degraded);
+    }
+}
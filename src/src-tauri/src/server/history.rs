@@ -0,0 +1,166 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+
+use crate::server::diagnosis;
+use crate::types::{ServerOptions, SessionRecord, StopCause};
+
+const HISTORY_FILENAME: &str = "server-history.json";
+/// Finished sessions kept on disk, oldest are dropped first
+const MAX_RECORDS: usize = 200;
+/// Error lines kept with each session
+const MAX_ERROR_LINES: usize = 20;
+
+#[derive(Default)]
+struct Inner {
+    path: Option<PathBuf>,
+    /// Finished sessions, oldest first
+    records: VecDeque<SessionRecord>,
+    /// Running session of each instance
+    open: HashMap<String, SessionRecord>,
+}
+
+/// Persisted record of every server session, with a diagnosis of the failed ones
+#[derive(Default)]
+pub struct SessionHistory {
+    inner: Mutex<Inner>,
+}
+
+impl SessionHistory {
+    /// Load the sessions recorded by previous runs
+    pub fn init(&self, app: &AppHandle) {
+        let Ok(path) = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(HISTORY_FILENAME))
+        else {
+            return;
+        };
+        let records = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let mut inner = self.lock();
+        inner.records = records;
+        inner.path = Some(path);
+    }
+
+    /// Open a session for a freshly spawned process
+    pub fn begin(
+        &self,
+        instance: &str,
+        log_session: Option<String>,
+        model: &str,
+        options: &ServerOptions,
+    ) {
+        self.lock().open.insert(
+            instance.to_string(),
+            SessionRecord {
+                instance: instance.to_string(),
+                log_session,
+                model: model.to_string(),
+                options: options.clone(),
+                started_at: now_millis(),
+                ready_at: None,
+                stopped_at: None,
+                exit_code: None,
+                stopped_by: None,
+                last_errors: Vec::new(),
+                diagnosis: None,
+            },
+        );
+    }
+
+    pub fn mark_ready(&self, instance: &str) {
+        if let Some(record) = self.lock().open.get_mut(instance) {
+            record.ready_at.get_or_insert_with(now_millis);
+        }
+    }
+
+    /// Keep a warning or error line of the running session
+    pub fn note_error(&self, instance: &str, line: &str) {
+        if let Some(record) = self.lock().open.get_mut(instance) {
+            if record.last_errors.len() == MAX_ERROR_LINES {
+                record.last_errors.remove(0);
+            }
+            record.last_errors.push(line.to_string());
+        }
+    }
+
+    /// Close the running session of the instance and persist it
+    pub fn finish(
+        &self,
+        instance: &str,
+        exit_code: Option<i32>,
+        cause: StopCause,
+    ) -> Option<SessionRecord> {
+        let mut inner = self.lock();
+        let mut record = inner.open.remove(instance)?;
+        record.stopped_at = Some(now_millis());
+        record.exit_code = exit_code;
+        record.stopped_by = Some(cause);
+        if record.failed() {
+            record.diagnosis = Some(diagnosis::diagnose(exit_code, &record.last_errors));
+        }
+
+        inner.records.push_back(record.clone());
+        while inner.records.len() > MAX_RECORDS {
+            inner.records.pop_front();
+        }
+        // The history is informative, losing it is not worth failing the stop
+        let _ = save(&inner);
+        Some(record)
+    }
+
+    /// Sessions newest first, the running ones included, optionally restricted to one instance
+    pub fn list(&self, instance: Option<&str>, limit: usize) -> Vec<SessionRecord> {
+        let inner = self.lock();
+        let mut running: Vec<SessionRecord> = inner.open.values().cloned().collect();
+        running.sort_by_key(|record| Reverse(record.started_at));
+        running
+            .into_iter()
+            .chain(inner.records.iter().rev().cloned())
+            .filter(|record| instance.is_none_or(|instance| record.instance == instance))
+            .take(limit)
+            .collect()
+    }
+
+    /// Latest finished session of the instance when it failed
+    pub fn last_failure(&self, instance: &str) -> Option<SessionRecord> {
+        self.lock()
+            .records
+            .iter()
+            .rev()
+            .find(|record| record.instance == instance)
+            .filter(|record| record.failed())
+            .cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("session history lock poisoned")
+    }
+}
+
+fn save(inner: &Inner) -> io::Result<()> {
+    let Some(path) = inner.path.as_ref() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_string(&inner.records)?)
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,82 @@
+use std::io;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crate::server::port;
+use crate::types::FlmEvent;
+
+/// How often the wake listener checks for a connection
+pub const WAKE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Time allowed for a woken server to load its model before the first request is dropped
+pub const WAKE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// Output lines showing that a client is using the server
+pub fn is_activity(event: &FlmEvent) -> bool {
+    matches!(
+        event,
+        FlmEvent::Request { .. } | FlmEvent::Throughput { .. }
+    )
+}
+
+/// Hold the port of a stopped server to notice the next request
+pub fn bind_wake_listener(host: &str, port: u16) -> io::Result<TcpListener> {
+    let addr = port::resolve(host, port)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unresolvable host"))?;
+    let listener = TcpListener::bind(addr)?;
+    // Polled from an async task, and dropped synchronously when the server starts
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// Pipe the connection that woke the server to it, in both directions
+pub fn forward(inbound: TcpStream, host: &str, port: u16) -> io::Result<()> {
+    let addr = port::resolve(host, port)
+        .map(port::connect_addr)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unresolvable host"))?;
+    // Accepted sockets inherit the listener mode on Windows
+    inbound.set_nonblocking(false)?;
+    let outbound = TcpStream::connect(addr)?;
+
+    let (mut client_read, mut server_write) = (inbound.try_clone()?, outbound.try_clone()?);
+    let (mut server_read, mut client_write) = (outbound, inbound);
+    thread::spawn(move || {
+        let _ = io::copy(&mut client_read, &mut server_write);
+        let _ = server_write.shutdown(Shutdown::Write);
+    });
+    thread::spawn(move || {
+        let _ = io::copy(&mut server_read, &mut client_write);
+        let _ = client_write.shutdown(Shutdown::Write);
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::output::parse_line;
+
+    #[test]
+    fn only_client_traffic_counts_as_activity() {
+        let active = [
+            "[FLM]  POST /v1/chat/completions",
+            "127.0.0.1 - \"GET /api/tags HTTP/1.1\" 200",
+            "Prefill speed: 812.40 tokens/s",
+            "[stats] generated 128 tokens (41.2 tok/s)",
+        ];
+        let idle = [
+            "[FLM]  Loading model: llama3.2:1b",
+            "[FLM]  Model loaded: llama3.2:1b",
+            "[FLM]  Starting server on port 52625...",
+            "[FLM]  Warning: context length exceeds model maximum, truncating",
+            "Error: failed to allocate NPU buffer",
+            "some unrelated output",
+        ];
+        for line in active {
+            assert!(is_activity(&parse_line(line)), "{line}");
+        }
+        for line in idle {
+            assert!(!is_activity(&parse_line(line)), "{line}");
+        }
+    }
+}
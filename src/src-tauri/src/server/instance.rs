@@ -0,0 +1,1101 @@
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tauri::async_runtime::Receiver;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tauri_plugin_shell::ShellExt;
+use tokio::sync::watch;
+
+use crate::server::health::{self, HealthTracker, ProbeOutcome};
+use crate::server::restart::{RestartDecision, RestartTracker};
+use crate::server::{
+    diagnosis, history, idle, orphan, output, port, stop, LogStore, ServerError, ServerSupervisor,
+    SessionHistory, FLM_PROGRAM, MAIN_INSTANCE, SERVER_LOG_EVENT, SERVER_OUTPUT_EVENT,
+    SERVER_PORT_EVENT, SERVER_RECONFIGURE_EVENT, SERVER_STATE_EVENT,
+};
+use crate::tray::refresh_tray_menu;
+use crate::types::{
+    FailureReason, FlmEvent, OrphanAction, OrphanServer, OutputSeverity, PendingRestart,
+    ReconfigureEvent, ReconfigurePhase, ServerHealth, ServerLog, ServerOptions, ServerOutput,
+    ServerState, ServerStatus, StopCause,
+};
+
+const EXIT_COMMAND: &[u8] = b"exit\r\n";
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time allowed for a killed process to report its termination
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+const ADOPTED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Changes arriving within this delay are applied with a single restart
+const RECONFIGURE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Model and options used when the server is started without explicit parameters (tray)
+#[derive(Debug, Clone, Default)]
+pub struct LaunchConfig {
+    pub model: String,
+    pub options: ServerOptions,
+}
+
+#[derive(Default)]
+struct Inner {
+    child: Option<CommandChild>,
+    /// PID of a server found at startup and adopted, mutually exclusive with `child`
+    adopted: Option<u32>,
+    session: u64,
+    launch: LaunchConfig,
+    started_at: Option<Instant>,
+    /// Set when a stop is requested, the exit is then expected
+    stop_cause: Option<StopCause>,
+    restarts: RestartTracker,
+    /// Latest launch parameters waiting to be applied by the reconfiguration task
+    pending: Option<LaunchConfig>,
+    reconfiguring: bool,
+    /// Last request seen in the server output
+    last_activity: Option<Instant>,
+    /// Port held after an idle stop to wake the server on the next request
+    wake: Option<TcpListener>,
+}
+
+impl Inner {
+    /// A process is alive or about to be
+    fn is_busy(&self, status: ServerStatus) -> bool {
+        self.child.is_some()
+            || self.adopted.is_some()
+            || matches!(
+                status,
+                ServerStatus::Starting | ServerStatus::Running | ServerStatus::Stopping
+            )
+    }
+}
+
+/// One `flm serve` process with its own model, options, logs and lifecycle
+pub struct ServerInstance {
+    id: String,
+    inner: Mutex<Inner>,
+    state: watch::Sender<ServerState>,
+}
+
+impl ServerInstance {
+    pub fn new(id: &str, launch: LaunchConfig) -> Self {
+        Self {
+            id: id.to_string(),
+            inner: Mutex::new(Inner {
+                launch,
+                ..Default::default()
+            }),
+            state: watch::Sender::new(ServerState {
+                id: id.to_string(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn state(&self) -> ServerState {
+        self.state.borrow().clone()
+    }
+
+    pub fn launch_config(&self) -> LaunchConfig {
+        self.lock().launch.clone()
+    }
+
+    pub fn set_launch_config(&self, model: String, options: ServerOptions) {
+        self.lock().launch = LaunchConfig { model, options };
+    }
+
+    pub fn reset_restarts(&self) {
+        self.lock().restarts.reset();
+    }
+
+    /// Flag a stopped instance as failed, for errors raised before a process exists
+    pub fn mark_failed(&self, app: &AppHandle, reason: FailureReason) {
+        self.update_state(app, |state| {
+            state.status = ServerStatus::Failed;
+            state.failure = Some(reason);
+        });
+    }
+
+    /// Apply new launch parameters, restarting a live server once if its command line changes.
+    /// Rapid successive calls are coalesced and applied one batch at a time.
+    pub fn reconfigure(self: &Arc<Self>, app: &AppHandle, model: String, options: ServerOptions) {
+        let launch = LaunchConfig { model, options };
+        let status = self.state.borrow().status;
+
+        if !matches!(status, ServerStatus::Starting | ServerStatus::Running) {
+            // Nothing to restart, the next start picks the parameters up
+            self.lock().launch = launch.clone();
+            if status == ServerStatus::Restarting {
+                self.update_state(app, |state| {
+                    state.model = launch.model;
+                    state.options = launch.options;
+                });
+            }
+            self.emit_reconfigure(app, ReconfigurePhase::Skipped);
+            return;
+        }
+
+        let schedule = {
+            let mut inner = self.lock();
+            inner.pending = Some(launch);
+            !std::mem::replace(&mut inner.reconfiguring, true)
+        };
+        self.emit_reconfigure(app, ReconfigurePhase::Queued);
+        if schedule {
+            tauri::async_runtime::spawn(process_reconfigure(app.clone(), self.clone()));
+        }
+    }
+
+    fn take_pending(&self) -> Option<LaunchConfig> {
+        let mut inner = self.lock();
+        let pending = inner.pending.take();
+        if pending.is_none() {
+            inner.reconfiguring = false;
+        }
+        pending
+    }
+
+    /// One stop/start cycle, skipped when the command line would be the same
+    async fn apply_launch(self: &Arc<Self>, app: &AppHandle, launch: LaunchConfig) {
+        let state = self.state();
+        self.lock().launch = launch.clone();
+
+        let unchanged =
+            serve_args(&state.model, &state.options) == serve_args(&launch.model, &launch.options);
+        if unchanged || !matches!(state.status, ServerStatus::Starting | ServerStatus::Running) {
+            self.emit_reconfigure(app, ReconfigurePhase::Skipped);
+            return;
+        }
+
+        self.log(
+            app,
+            String::from("[SYSTEM] Options changed, restarting server..."),
+        );
+        self.emit_reconfigure(app, ReconfigurePhase::Restarting);
+
+        if let Err(e) = self.stop_for(app, StopCause::Reconfigure).await {
+            self.emit_reconfigure(app, ReconfigurePhase::Failed(e.to_string()));
+            return;
+        }
+        match self.start(app, launch.model, launch.options) {
+            Ok(_) => self.emit_reconfigure(app, ReconfigurePhase::Applied),
+            Err(e) => self.emit_reconfigure(app, ReconfigurePhase::Failed(e.to_string())),
+        }
+    }
+
+    fn emit_reconfigure(&self, app: &AppHandle, phase: ReconfigurePhase) {
+        let _ = app.emit(
+            SERVER_RECONFIGURE_EVENT,
+            ReconfigureEvent {
+                instance: self.id.clone(),
+                phase,
+            },
+        );
+    }
+
+    /// Spawn `flm serve` with the given model and options (manual start)
+    pub fn start(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        model: String,
+        options: ServerOptions,
+    ) -> Result<ServerState, ServerError> {
+        self.lock().restarts.reset();
+        self.spawn(app, model, options)
+    }
+
+    fn spawn(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        model: String,
+        options: ServerOptions,
+    ) -> Result<ServerState, ServerError> {
+        {
+            let mut inner = self.lock();
+            if inner.is_busy(self.state.borrow().status) {
+                return Err(ServerError::AlreadyRunning);
+            }
+            // Release the port held to wake the server
+            inner.wake = None;
+        }
+        if let Err(e) = app
+            .state::<ServerSupervisor>()
+            .check_npu(&self.id, &model, &options)
+        {
+            self.log(app, format!("[ERROR] {}", e));
+            return Err(e);
+        }
+        let options = self.ensure_port(app, options)?;
+
+        let mut inner = self.lock();
+        if inner.is_busy(self.state.borrow().status) {
+            return Err(ServerError::AlreadyRunning);
+        }
+
+        // Each launch gets its own log file, refused starts leave none behind
+        let log_session = app.state::<LogStore>().begin_session(&self.id);
+        let args = serve_args(&model, &options);
+        self.log(
+            app,
+            format!("[SYSTEM] Executing: {} {}", FLM_PROGRAM, args.join(" ")),
+        );
+
+        let (rx, child) = match app.shell().command(FLM_PROGRAM).args(&args).spawn() {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                self.log(app, format!("[ERROR] Failed to spawn server: {}", e));
+                app.state::<LogStore>().end_session(&self.id);
+                return Err(ServerError::Spawn(e.to_string()));
+            }
+        };
+
+        let pid = child.pid();
+        inner.session += 1;
+        let session = inner.session;
+        inner.child = Some(child);
+        inner.started_at = Some(Instant::now());
+        inner.last_activity = inner.started_at;
+        inner.stop_cause = None;
+        inner.launch = LaunchConfig {
+            model: model.clone(),
+            options: options.clone(),
+        };
+        drop(inner);
+        app.state::<SessionHistory>()
+            .begin(&self.id, log_session, &model, &options);
+
+        self.update_state(app, |state| {
+            *state = ServerState {
+                id: self.id.clone(),
+                status: ServerStatus::Starting,
+                health: Some(ServerHealth::Starting),
+                model,
+                options,
+                pid: Some(pid),
+                started_at: Some(history::now_millis()),
+                ..Default::default()
+            };
+        });
+        self.log(
+            app,
+            format!("[SYSTEM] Server process started (PID: {})", pid),
+        );
+
+        tauri::async_runtime::spawn(monitor(app.clone(), self.clone(), session, rx));
+        tauri::async_runtime::spawn(watch_health(app.clone(), self.clone(), session));
+
+        Ok(self.state())
+    }
+
+    /// Check the port before spawning, moving to a free one when the user opted in
+    fn ensure_port(
+        &self,
+        app: &AppHandle,
+        mut options: ServerOptions,
+    ) -> Result<ServerOptions, ServerError> {
+        let host = options.host().to_string();
+        let requested = options.port();
+        if !port::is_port_in_use(&host, requested) {
+            return Ok(options);
+        }
+
+        let selection = app.state::<ServerSupervisor>().port_selection();
+        if selection.auto_select {
+            if let Some(free_port) = port::find_free_port(&host, selection.candidates(requested)) {
+                self.log(
+                    app,
+                    format!(
+                        "[SYSTEM] Port {} is in use, using port {} instead",
+                        requested, free_port
+                    ),
+                );
+                if self.id == MAIN_INSTANCE {
+                    // The UI owns the main instance options
+                    let _ = app.emit(SERVER_PORT_EVENT, free_port);
+                }
+                options.port = Some(free_port);
+                return Ok(options);
+            }
+        }
+
+        let owner = port::owner(requested);
+        let error = ServerError::PortInUse {
+            port: requested,
+            pid: owner.pid,
+            process: owner.name,
+        };
+        self.log(app, format!("[ERROR] {}", error));
+        Err(error)
+    }
+
+    /// Record the server found at startup, until the user decides what to do with it
+    pub fn set_orphan(&self, app: &AppHandle, orphan: Option<OrphanServer>) {
+        if let Some(found) = &orphan {
+            let owner = match (&found.process_name, found.pid) {
+                (Some(name), Some(pid)) => format!("{} (PID: {})", name, pid),
+                (None, Some(pid)) => format!("PID: {}", pid),
+                _ => String::from("unknown process"),
+            };
+            self.log(
+                app,
+                format!(
+                    "[SYSTEM] Existing server found on {}:{}, held by {}",
+                    found.host, found.port, owner
+                ),
+            );
+        }
+        self.update_state(app, |state| state.orphan = orphan);
+    }
+
+    /// Adopt, terminate or avoid the server found at startup
+    pub async fn resolve_orphan(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        action: OrphanAction,
+    ) -> Result<ServerState, ServerError> {
+        let Some(found) = self.state.borrow().orphan.clone() else {
+            return Err(ServerError::Orphan(String::from(
+                "no existing server was detected",
+            )));
+        };
+
+        match action {
+            OrphanAction::Adopt => self.adopt(app, found)?,
+            OrphanAction::Terminate => {
+                let pid = manageable_pid(&found)?;
+                self.log(
+                    app,
+                    format!("[SYSTEM] Terminating existing server (PID: {})...", pid),
+                );
+                let terminated =
+                    tauri::async_runtime::spawn_blocking(move || orphan::terminate(pid))
+                        .await
+                        .unwrap_or(false);
+                if !terminated {
+                    return Err(ServerError::Io(format!(
+                        "failed to terminate process {}",
+                        pid
+                    )));
+                }
+                self.update_state(app, |state| state.orphan = None);
+            }
+            OrphanAction::ChangePort => {
+                let options = self.launch_config().options;
+                let candidates = app
+                    .state::<ServerSupervisor>()
+                    .port_selection()
+                    .candidates(options.port());
+                let free_port = port::find_free_port(options.host(), candidates)
+                    .ok_or_else(|| ServerError::Orphan(String::from("no free port found")))?;
+                self.lock().launch.options.port = Some(free_port);
+                self.log(
+                    app,
+                    format!("[SYSTEM] Server port changed to {}", free_port),
+                );
+                let _ = app.emit(SERVER_PORT_EVENT, free_port);
+                self.update_state(app, |state| state.orphan = None);
+            }
+        }
+
+        Ok(self.state())
+    }
+
+    /// Monitor a server we did not spawn: health through HTTP, liveness through its PID
+    fn adopt(self: &Arc<Self>, app: &AppHandle, found: OrphanServer) -> Result<(), ServerError> {
+        let pid = manageable_pid(&found)?;
+
+        let mut inner = self.lock();
+        if inner.is_busy(self.state.borrow().status) {
+            return Err(ServerError::AlreadyRunning);
+        }
+
+        inner.session += 1;
+        let session = inner.session;
+        inner.adopted = Some(pid);
+        inner.started_at = Some(Instant::now());
+        inner.stop_cause = None;
+        inner.restarts.reset();
+
+        let mut options = inner.launch.options.clone();
+        options.host = Some(found.host.clone());
+        options.port = Some(found.port);
+        let model = found.model.unwrap_or_default();
+        drop(inner);
+
+        app.state::<LogStore>().begin_session(&self.id);
+        self.log(
+            app,
+            format!("[SYSTEM] Adopted existing server (PID: {})", pid),
+        );
+        self.update_state(app, |state| {
+            *state = ServerState {
+                id: self.id.clone(),
+                status: ServerStatus::Running,
+                health: Some(ServerHealth::Starting),
+                model,
+                options,
+                pid: Some(pid),
+                adopted: true,
+                started_at: Some(history::now_millis()),
+                ..Default::default()
+            };
+        });
+
+        tauri::async_runtime::spawn(watch_adopted(app.clone(), self.clone(), session, pid));
+        tauri::async_runtime::spawn(watch_health(app.clone(), self.clone(), session));
+
+        Ok(())
+    }
+
+    /// Stop requested by the user
+    pub async fn stop(self: &Arc<Self>, app: &AppHandle) -> Result<(), ServerError> {
+        self.stop_for(app, StopCause::User).await
+    }
+
+    /// Send `exit` on stdin, then kill the process if it is still alive after the timeout.
+    /// Returns once the instance is `Stopped`. Also cancels a pending automatic restart.
+    pub async fn stop_for(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        cause: StopCause,
+    ) -> Result<(), ServerError> {
+        let adopted = {
+            let mut inner = self.lock();
+            match inner.adopted {
+                Some(pid) => {
+                    inner.stop_cause = Some(cause);
+                    Some((pid, inner.session))
+                }
+                None => None,
+            }
+        };
+        if let Some((pid, session)) = adopted {
+            return self.stop_adopted(app, session, pid).await;
+        }
+
+        let write_result = {
+            let mut inner = self.lock();
+            let Some(child) = inner.child.as_mut() else {
+                let status = self.state.borrow().status;
+                if !matches!(status, ServerStatus::Restarting | ServerStatus::Failed) {
+                    return Err(ServerError::NotRunning);
+                }
+                // Invalidate the scheduled restart
+                inner.session += 1;
+                drop(inner);
+                self.log(app, "[SYSTEM] Automatic restart cancelled".into());
+                self.update_state(app, |state| {
+                    state.status = ServerStatus::Stopped;
+                    state.restart = None;
+                    state.failure = None;
+                    state.stopped_by = Some(cause);
+                });
+                return Ok(());
+            };
+            self.log(app, "[SYSTEM] Sending 'exit' command to server...".into());
+            let result = child.write(EXIT_COMMAND);
+            inner.stop_cause = Some(cause);
+            result
+        };
+
+        self.update_state(app, |state| state.status = ServerStatus::Stopping);
+
+        let grace = match write_result {
+            Ok(()) => {
+                self.log(
+                    app,
+                    "[SYSTEM] Exit command sent. Waiting for graceful shutdown...".into(),
+                );
+                STOP_TIMEOUT
+            }
+            Err(e) => {
+                self.log(
+                    app,
+                    format!(
+                        "[ERROR] Failed to write exit command: {}. Forcing kill...",
+                        e
+                    ),
+                );
+                Duration::ZERO
+            }
+        };
+
+        let exit = stop::wait_for_exit(self.state.subscribe(), grace, KILL_TIMEOUT, || {
+            if !grace.is_zero() {
+                self.log(
+                    app,
+                    "[SYSTEM] Server did not exit gracefully, forcing kill...".into(),
+                );
+            }
+            self.kill()
+        })
+        .await;
+        exit.map(|_| ())
+    }
+
+    /// The adopted server has no stdin, terminate it through its PID
+    async fn stop_adopted(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        session: u64,
+        pid: u32,
+    ) -> Result<(), ServerError> {
+        self.log(
+            app,
+            format!("[SYSTEM] Terminating adopted server (PID: {})...", pid),
+        );
+        self.update_state(app, |state| state.status = ServerStatus::Stopping);
+
+        let terminated = tauri::async_runtime::spawn_blocking(move || orphan::terminate(pid))
+            .await
+            .unwrap_or(false);
+        if !terminated {
+            self.lock().stop_cause = None;
+            self.update_state(app, |state| state.status = ServerStatus::Running);
+            return Err(ServerError::Io(format!(
+                "failed to terminate process {}",
+                pid
+            )));
+        }
+
+        self.on_adopted_exit(app, session);
+        Ok(())
+    }
+
+    /// Whether a `flm serve` process is currently alive
+    pub fn has_process(&self) -> bool {
+        let inner = self.lock();
+        inner.child.is_some() || inner.adopted.is_some()
+    }
+
+    /// Let go of the process without stopping it (quit but keep the server running)
+    pub fn detach(&self) {
+        let mut inner = self.lock();
+        let child = inner.child.take();
+        if child.is_some() || inner.adopted.take().is_some() {
+            // Ignore its termination
+            inner.session += 1;
+        }
+        // Keep its stdin open until the companion exits
+        std::mem::forget(child);
+    }
+
+    /// Blocking variant of `stop` for the final exit, when the event loop no longer runs
+    pub fn shutdown_blocking(&self) {
+        let adopted = self.lock().adopted.take();
+        if let Some(pid) = adopted {
+            orphan::terminate(pid);
+            return;
+        }
+
+        {
+            let mut inner = self.lock();
+            let Some(child) = inner.child.as_mut() else {
+                return;
+            };
+            let _ = child.write(EXIT_COMMAND);
+            inner.stop_cause = Some(StopCause::Shutdown);
+        }
+
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while Instant::now() < deadline {
+            if !self.has_process() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        let _ = self.kill();
+    }
+
+    fn kill(&self) -> Result<(), ServerError> {
+        if let Some(child) = self.lock().child.take() {
+            child.kill().map_err(|e| ServerError::Io(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn handle_event(self: &Arc<Self>, app: &AppHandle, session: u64, event: CommandEvent) {
+        match event {
+            CommandEvent::Stdout(bytes) | CommandEvent::Stderr(bytes) => {
+                self.handle_output(app, session, decode_line(&bytes));
+            }
+            CommandEvent::Error(error) => {
+                self.log(app, format!("[ERROR] {}", error));
+            }
+            CommandEvent::Terminated(payload) => {
+                self.on_terminated(app, session, payload.code);
+            }
+            _ => {}
+        }
+    }
+
+    /// Log a line of server output under its severity and broadcast what it means
+    fn handle_output(&self, app: &AppHandle, session: u64, line: String) {
+        let event = output::parse_line(&line);
+        let severity = event.severity();
+        let tag = match severity {
+            OutputSeverity::Info => "[FLM]",
+            OutputSeverity::Warning => "[FLM WARN]",
+            OutputSeverity::Error | OutputSeverity::Fatal => "[FLM ERR]",
+        };
+        self.log(app, format!("{} {}", tag, line));
+        if severity != OutputSeverity::Info || diagnosis::is_relevant(&line) {
+            app.state::<SessionHistory>().note_error(&self.id, &line);
+        }
+
+        if idle::is_activity(&event) {
+            self.lock().last_activity = Some(Instant::now());
+        }
+
+        if let FlmEvent::LoadProgress { percent } = event {
+            let state = self.state.borrow().clone();
+            if self.is_current(session)
+                && state.status == ServerStatus::Starting
+                && state.load_percent != Some(percent)
+            {
+                self.update_state(app, |state| state.load_percent = Some(percent));
+            }
+        }
+
+        let _ = app.emit(
+            SERVER_OUTPUT_EVENT,
+            ServerOutput {
+                instance: self.id.clone(),
+                severity,
+                event,
+            },
+        );
+    }
+
+    /// Stop a spawned server that has not seen a request within the idle timeout
+    async fn check_idle(self: &Arc<Self>, app: &AppHandle, session: u64) {
+        let policy = app.state::<ServerSupervisor>().idle_policy();
+        let Some(timeout) = policy.timeout() else {
+            return;
+        };
+        {
+            let inner = self.lock();
+            // An adopted server has no output to watch
+            let idle = inner.session == session
+                && inner.child.is_some()
+                && inner
+                    .last_activity
+                    .is_some_and(|at| at.elapsed() >= timeout);
+            if !idle || self.state.borrow().status != ServerStatus::Running {
+                return;
+            }
+        }
+
+        self.log(
+            app,
+            format!(
+                "[SYSTEM] No request for {} minutes, stopping server",
+                policy.timeout_minutes
+            ),
+        );
+        if let Err(e) = self.stop_for(app, StopCause::Idle).await {
+            self.log(app, format!("[ERROR] {}", e));
+            return;
+        }
+        if policy.wake_on_request {
+            self.arm_wake(app);
+        }
+    }
+
+    /// Listen on the port of the stopped server, the next connection starts it again
+    fn arm_wake(self: &Arc<Self>, app: &AppHandle) {
+        let options = self.state().options;
+        match idle::bind_wake_listener(options.host(), options.port()) {
+            Ok(listener) => {
+                self.lock().wake = Some(listener);
+                self.log(
+                    app,
+                    format!(
+                        "[SYSTEM] Waiting for a request on port {} to wake the server",
+                        options.port()
+                    ),
+                );
+                tauri::async_runtime::spawn(watch_wake(app.clone(), self.clone()));
+            }
+            Err(e) => self.log(
+                app,
+                format!("[ERROR] Cannot listen for wake requests: {}", e),
+            ),
+        }
+    }
+
+    /// Start the server for the connection that hit its port, then hand the connection over
+    async fn wake(self: &Arc<Self>, app: &AppHandle, inbound: TcpStream) {
+        self.log(app, "[SYSTEM] Request received, waking server...".into());
+        let launch = self.launch_config();
+        if let Err(e) = self.start(app, launch.model, launch.options) {
+            self.log(app, format!("[ERROR] {}", e));
+            return;
+        }
+
+        let mut rx = self.state.subscribe();
+        let settled = rx.wait_for(|state| {
+            state.health == Some(ServerHealth::Ready)
+                || matches!(state.status, ServerStatus::Stopped | ServerStatus::Failed)
+        });
+        let ready = matches!(
+            tokio::time::timeout(idle::WAKE_TIMEOUT, settled).await,
+            Ok(Ok(ref state)) if state.health == Some(ServerHealth::Ready)
+        );
+        if !ready {
+            return;
+        }
+        let options = self.state().options;
+        if let Err(e) = idle::forward(inbound, options.host(), options.port()) {
+            self.log(app, format!("[ERROR] Cannot forward wake request: {}", e));
+        }
+    }
+
+    fn is_current(&self, session: u64) -> bool {
+        self.lock().session == session
+    }
+
+    /// Apply a probe outcome: first success marks the server ready, later ones update its health
+    fn apply_probe(
+        &self,
+        app: &AppHandle,
+        session: u64,
+        outcome: ProbeOutcome,
+        tracker: &mut HealthTracker,
+    ) {
+        if !self.is_current(session) {
+            return;
+        }
+
+        let current = self.state();
+        match current.status {
+            ServerStatus::Starting if outcome == ProbeOutcome::Healthy => {
+                tracker.record(outcome);
+                app.state::<SessionHistory>().mark_ready(&self.id);
+                self.update_state(app, |state| {
+                    state.status = ServerStatus::Running;
+                    state.health = Some(ServerHealth::Ready);
+                });
+            }
+            ServerStatus::Running => {
+                let health = tracker.record(outcome);
+                if current.health != Some(health) {
+                    self.log(app, format!("[SYSTEM] Server health: {:?}", health));
+                    self.update_state(app, |state| state.health = Some(health));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_adopted_exit(self: &Arc<Self>, app: &AppHandle, session: u64) {
+        {
+            let mut inner = self.lock();
+            if inner.session != session || inner.adopted.take().is_none() {
+                return;
+            }
+        }
+        // Exit code of a process we did not spawn is unknown
+        self.on_terminated(app, session, None);
+    }
+
+    fn on_terminated(self: &Arc<Self>, app: &AppHandle, session: u64, code: Option<i32>) {
+        let policy = app.state::<ServerSupervisor>().restart_policy();
+        let (decision, max_retries, cause) = {
+            let mut guard = self.lock();
+            let inner = &mut *guard;
+            if inner.session != session {
+                return;
+            }
+            inner.child = None;
+            let cause = inner.stop_cause.take();
+            let uptime = inner
+                .started_at
+                .take()
+                .map(|started| started.elapsed())
+                .unwrap_or_default();
+            let decision = if cause.is_some() {
+                RestartDecision::Stay
+            } else {
+                inner
+                    .restarts
+                    .on_exit(&policy, code, uptime, Instant::now())
+            };
+            (decision, policy.max_retries, cause)
+        };
+
+        let code_text = code.map_or_else(|| String::from("unknown"), |c| c.to_string());
+        self.log(
+            app,
+            format!("[SYSTEM] Server stopped with code {}", code_text),
+        );
+        let record = app.state::<SessionHistory>().finish(
+            &self.id,
+            code,
+            cause.unwrap_or(StopCause::Exited),
+        );
+        if let Some(diagnosis) = record.and_then(|record| record.diagnosis) {
+            self.log(
+                app,
+                format!("[ERROR] Likely cause: {}", diagnosis.describe()),
+            );
+        }
+
+        match decision {
+            RestartDecision::Stay => {
+                self.update_state(app, |state| {
+                    state.status = ServerStatus::Stopped;
+                    state.health = None;
+                    state.pid = None;
+                    state.exit_code = code;
+                    state.adopted = false;
+                    state.idle = cause == Some(StopCause::Idle);
+                    state.stopped_by = Some(cause.unwrap_or(StopCause::Exited));
+                });
+            }
+            RestartDecision::Restart { attempt, delay } => {
+                self.log(
+                    app,
+                    format!(
+                        "[SYSTEM] Restarting server in {}s (attempt {}/{})",
+                        delay.as_secs(),
+                        attempt,
+                        max_retries
+                    ),
+                );
+                self.update_state(app, |state| {
+                    state.status = ServerStatus::Restarting;
+                    state.health = None;
+                    state.pid = None;
+                    state.exit_code = code;
+                    state.adopted = false;
+                    state.restart = Some(PendingRestart {
+                        attempt,
+                        max_retries,
+                        delay_secs: delay.as_secs(),
+                    });
+                });
+                tauri::async_runtime::spawn(restart_after(
+                    app.clone(),
+                    self.clone(),
+                    session,
+                    delay,
+                ));
+            }
+            RestartDecision::GiveUp(reason) => {
+                self.log(
+                    app,
+                    format!("[ERROR] Giving up on automatic restart: {:?}", reason),
+                );
+                self.update_state(app, |state| {
+                    state.status = ServerStatus::Failed;
+                    state.health = None;
+                    state.pid = None;
+                    state.exit_code = code;
+                    state.adopted = false;
+                    state.restart = None;
+                    state.failure = Some(reason);
+                });
+            }
+        }
+        // A restart opens a new file
+        app.state::<LogStore>().end_session(&self.id);
+    }
+
+    fn log(&self, app: &AppHandle, line: String) {
+        emit_log(app, &self.id, line);
+    }
+
+    fn update_state(&self, app: &AppHandle, update: impl FnOnce(&mut ServerState)) {
+        self.state.send_modify(update);
+        let _ = app.emit(SERVER_STATE_EVENT, self.state());
+        refresh_tray_menu(app);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("server instance lock poisoned")
+    }
+}
+
+/// Apply queued launch parameters until the queue is empty
+async fn process_reconfigure(app: AppHandle, instance: Arc<ServerInstance>) {
+    loop {
+        // Let rapid changes coalesce into a single restart
+        tokio::time::sleep(RECONFIGURE_DEBOUNCE).await;
+        let Some(launch) = instance.take_pending() else {
+            break;
+        };
+        instance.apply_launch(&app, launch).await;
+    }
+}
+
+async fn monitor(
+    app: AppHandle,
+    instance: Arc<ServerInstance>,
+    session: u64,
+    mut rx: Receiver<CommandEvent>,
+) {
+    while let Some(event) = rx.recv().await {
+        instance.handle_event(&app, session, event);
+    }
+}
+
+/// Restart the server once the backoff delay has elapsed, unless it was started or stopped meanwhile
+async fn restart_after(
+    app: AppHandle,
+    instance: Arc<ServerInstance>,
+    session: u64,
+    delay: Duration,
+) {
+    tokio::time::sleep(delay).await;
+
+    let state = instance.state();
+    if !instance.is_current(session) || state.status != ServerStatus::Restarting {
+        return;
+    }
+
+    if let Err(e) = instance.spawn(&app, state.model, state.options) {
+        instance.log(&app, format!("[ERROR] Failed to restart server: {}", e));
+        instance.update_state(&app, |state| {
+            state.status = ServerStatus::Stopped;
+            state.restart = None;
+            state.stopped_by = Some(StopCause::Exited);
+        });
+    }
+}
+
+/// The adopted process is not our child, poll for its exit
+async fn watch_adopted(app: AppHandle, instance: Arc<ServerInstance>, session: u64, pid: u32) {
+    loop {
+        tokio::time::sleep(ADOPTED_POLL_INTERVAL).await;
+
+        if !instance.is_current(session) {
+            break;
+        }
+        if !orphan::is_alive(pid) {
+            instance.on_adopted_exit(&app, session);
+            break;
+        }
+    }
+}
+
+/// Poll the HTTP endpoint until the session ends
+async fn watch_health(app: AppHandle, instance: Arc<ServerInstance>, session: u64) {
+    let client = health::probe_client();
+    let mut tracker = HealthTracker::default();
+
+    loop {
+        let state = instance.state();
+        if !instance.is_current(session)
+            || !matches!(state.status, ServerStatus::Starting | ServerStatus::Running)
+        {
+            break;
+        }
+
+        let outcome = health::probe(&client, state.options.host(), state.options.port()).await;
+        instance.apply_probe(&app, session, outcome, &mut tracker);
+        instance.check_idle(&app, session).await;
+
+        let interval = if state.status == ServerStatus::Starting {
+            health::STARTUP_PROBE_INTERVAL
+        } else {
+            health::HEALTH_PROBE_INTERVAL
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Poll the wake listener until a client connects or the server is started otherwise
+async fn watch_wake(app: AppHandle, instance: Arc<ServerInstance>) {
+    loop {
+        tokio::time::sleep(idle::WAKE_POLL_INTERVAL).await;
+        let accepted = {
+            let mut inner = instance.lock();
+            let Some(listener) = inner.wake.as_ref() else {
+                return;
+            };
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    // Free the port for the server
+                    inner.wake = None;
+                    stream
+                }
+                Err(_) => continue,
+            }
+        };
+        instance.wake(&app, accepted).await;
+        return;
+    }
+}
+
+/// PID of an orphan that can be adopted or terminated
+fn manageable_pid(found: &OrphanServer) -> Result<u32, ServerError> {
+    found
+        .pid
+        .filter(|_| found.is_manageable())
+        .ok_or_else(|| ServerError::Orphan(format!("port {} is not held by flm", found.port)))
+}
+
+/// Record a line of output for the given instance and forward it to the UI
+pub fn emit_log(app: &AppHandle, instance: &str, line: String) {
+    app.state::<LogStore>().append(instance, &line);
+    let _ = app.emit(
+        SERVER_LOG_EVENT,
+        ServerLog {
+            instance: instance.to_string(),
+            line,
+        },
+    );
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).trim_end().to_string()
+}
+
+/// Build the `flm serve` arguments, same rules as the former frontend implementation
+fn serve_args(model: &str, options: &ServerOptions) -> Vec<String> {
+    let mut args = vec![String::from("serve")];
+    if !model.is_empty() {
+        args.push(model.to_string());
+    }
+
+    let flag = |value: bool| String::from(if value { "1" } else { "0" });
+
+    if let Some(pmode) = options.pmode.as_ref().filter(|p| !p.is_empty()) {
+        args.extend([String::from("--pmode"), pmode.clone()]);
+    }
+    if let Some(ctx_len) = options.ctx_len.filter(|v| *v > 0) {
+        args.extend([String::from("--ctx-len"), ctx_len.to_string()]);
+    }
+    if let Some(port) = options.port.filter(|v| *v > 0) {
+        args.extend([String::from("--port"), port.to_string()]);
+    }
+    if let Some(host) = options.host.as_ref().filter(|h| !h.is_empty()) {
+        args.extend([String::from("--host"), host.clone()]);
+    }
+    if let Some(socket) = options.socket.filter(|v| *v > 0) {
+        args.extend([String::from("--socket"), socket.to_string()]);
+    }
+    if let Some(q_len) = options.q_len.filter(|v| *v > 0) {
+        args.extend([String::from("--q-len"), q_len.to_string()]);
+    }
+
+    // Boolean flags that take 0 or 1
+    if let Some(asr) = options.asr {
+        args.extend([String::from("--asr"), flag(asr)]);
+    }
+    if let Some(embed) = options.embed {
+        args.extend([String::from("--embed"), flag(embed)]);
+    }
+    if let Some(cors) = options.cors {
+        args.extend([String::from("--cors"), flag(cors)]);
+    }
+    if let Some(preemption) = options.preemption {
+        args.extend([String::from("--preemption"), flag(preemption)]);
+    }
+
+    args
+}
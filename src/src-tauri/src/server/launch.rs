@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::server::{emit_log, presets, ServerSupervisor, MAIN_INSTANCE};
+use crate::tray::TrayState;
+use crate::types::{AppConfig, FailureReason, ServerStatus};
+
+/// Start the main instance once the startup delay has elapsed, when enabled in the config.
+/// Runs from the `setup` hook, so it does not depend on the window being shown.
+pub fn spawn(app: &AppHandle, config: &AppConfig) {
+    let settings = config.start_on_launch.clone();
+    if !settings.enabled {
+        return;
+    }
+    let selection = settings
+        .preset
+        .clone()
+        .filter(|preset| !preset.is_empty())
+        .unwrap_or_else(|| config.last_selected_model.clone());
+    let options = config.server_options.clone();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(settings.delay_secs)).await;
+
+        let instance = app.state::<ServerSupervisor>().main();
+        let state = instance.state();
+        // Something else got there first: an orphan awaiting a decision, a schedule window, the user
+        if state.orphan.is_some()
+            || !matches!(state.status, ServerStatus::Stopped | ServerStatus::Failed)
+        {
+            emit_log(
+                &app,
+                MAIN_INSTANCE,
+                String::from("[SYSTEM] Start on launch skipped, the server is already handled"),
+            );
+            return;
+        }
+
+        let Some(launch) = presets::resolve(&selection, &options) else {
+            fail(&app, format!("unknown preset {}", selection));
+            return;
+        };
+        emit_log(
+            &app,
+            MAIN_INSTANCE,
+            format!(
+                "[SYSTEM] Starting server on launch with {}",
+                if selection.is_empty() {
+                    "no model"
+                } else {
+                    &selection
+                }
+            ),
+        );
+        if let Err(e) = instance.start(&app, launch.model, launch.options) {
+            fail(&app, e.to_string());
+        }
+    });
+}
+
+/// Report the failure in the tray and through a notification, the window may be hidden
+fn fail(app: &AppHandle, reason: String) {
+    emit_log(
+        app,
+        MAIN_INSTANCE,
+        format!("[ERROR] Start on launch failed: {}", reason),
+    );
+    app.state::<ServerSupervisor>()
+        .main()
+        .mark_failed(app, FailureReason::LaunchFailed);
+
+    let title = app
+        .state::<TrayState>()
+        .params()
+        .map(|params| params.texts.launch_failed)
+        .unwrap_or_else(|| String::from("Server start on launch failed"));
+    let _ = app
+        .notification()
+        .builder()
+        .title(title)
+        .body(reason)
+        .show();
+}
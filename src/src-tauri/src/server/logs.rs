@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::server::history::now_millis;
+use crate::types::{LogPage, LogSession};
+
+/// Subdirectory of the app log dir holding the server sessions
+const LOG_SUBDIR: &str = "server";
+const LOG_EXTENSION: &str = "log";
+/// Suffix of the previous part of a session that outgrew `MAX_FILE_BYTES`
+const ROTATED_SUFFIX: &str = ".1";
+/// Size of a session file before it is rotated, only the last two parts are kept
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Total size of the log directory, oldest sessions are removed first
+const MAX_TOTAL_BYTES: u64 = 50 * 1024 * 1024;
+const MAX_AGE: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+/// Upper bound of a single page request
+const MAX_PAGE_LINES: usize = 5000;
+
+struct OpenSession {
+    id: String,
+    writer: LineWriter<File>,
+    bytes: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    dir: Option<PathBuf>,
+    open: HashMap<String, OpenSession>,
+}
+
+/// Writes each server session to its own file under the app log dir
+#[derive(Default)]
+pub struct LogStore {
+    inner: Mutex<Inner>,
+}
+
+impl LogStore {
+    /// Use the server subdirectory of the app log dir and apply the retention rules
+    pub fn init(&self, log_dir: PathBuf) {
+        let dir = log_dir.join(LOG_SUBDIR);
+        if fs::create_dir_all(&dir).is_err() {
+            return;
+        }
+        prune(&dir, &[]);
+        self.lock().dir = Some(dir);
+    }
+
+    /// Start a new session file for the instance, closing the previous one
+    pub fn begin_session(&self, instance: &str) -> Option<String> {
+        let mut inner = self.lock();
+        inner.open.remove(instance);
+        let dir = inner.dir.clone()?;
+
+        let active: Vec<String> = inner.open.values().map(|s| s.id.clone()).collect();
+        prune(&dir, &active);
+
+        let mut id = format!("{}_{}", instance, now_millis());
+        // Two sessions of the same instance within a millisecond
+        while session_path(&dir, &id).exists() {
+            id.push('_');
+        }
+        let file = File::create(session_path(&dir, &id)).ok()?;
+        inner.open.insert(
+            instance.to_string(),
+            OpenSession {
+                id: id.clone(),
+                writer: LineWriter::new(file),
+                bytes: 0,
+            },
+        );
+        Some(id)
+    }
+
+    /// Close the session file of the instance once its process has exited
+    pub fn end_session(&self, instance: &str) {
+        self.lock().open.remove(instance);
+    }
+
+    /// Append a line to the current session of the instance.
+    /// Lines outside of a session only reach the live console.
+    pub fn append(&self, instance: &str, line: &str) {
+        let mut inner = self.lock();
+        let Some(dir) = inner.dir.clone() else {
+            return;
+        };
+        let Some(session) = inner.open.get_mut(instance) else {
+            return;
+        };
+        if session.bytes >= MAX_FILE_BYTES {
+            match rotate(&dir, &session.id) {
+                Ok(file) => {
+                    session.writer = LineWriter::new(file);
+                    session.bytes = 0;
+                }
+                Err(_) => return,
+            }
+        }
+        if writeln!(session.writer, "{}", line).is_ok() {
+            session.bytes += line.len() as u64 + 1;
+        }
+    }
+
+    /// Sessions on disk, newest first, optionally restricted to one instance
+    pub fn sessions(&self, instance: Option<&str>) -> Vec<LogSession> {
+        let inner = self.lock();
+        let Some(dir) = inner.dir.as_ref() else {
+            return Vec::new();
+        };
+        let mut sessions: Vec<LogSession> = list_files(dir)
+            .into_iter()
+            .filter(|file| !file.rotated)
+            .filter_map(|file| {
+                let (owner, started_at) = parse_id(&file.id)?;
+                if instance.is_some_and(|instance| instance != owner) {
+                    return None;
+                }
+                let rotated = file_size(&rotated_path(dir, &file.id));
+                Some(LogSession {
+                    active: inner.open.values().any(|s| s.id == file.id),
+                    instance: owner.to_string(),
+                    started_at,
+                    size: file.size + rotated,
+                    id: file.id,
+                })
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at).then(b.id.cmp(&a.id)));
+        sessions
+    }
+
+    pub fn latest_session(&self, instance: &str) -> Option<LogSession> {
+        self.sessions(Some(instance)).into_iter().next()
+    }
+
+    /// Lines `offset..offset + limit` of a session
+    pub fn read(&self, session: &str, offset: usize, limit: usize) -> io::Result<LogPage> {
+        let lines = self.read_all(session)?;
+        let total = lines.len();
+        let offset = offset.min(total);
+        let end = offset.saturating_add(limit.min(MAX_PAGE_LINES)).min(total);
+        Ok(LogPage {
+            session: session.to_string(),
+            offset,
+            total,
+            lines: lines[offset..end].to_vec(),
+        })
+    }
+
+    /// Last `count` lines of a session; poll with `read` from `total` to follow it
+    pub fn tail(&self, session: &str, count: usize) -> io::Result<LogPage> {
+        let lines = self.read_all(session)?;
+        let total = lines.len();
+        let offset = total.saturating_sub(count.min(MAX_PAGE_LINES));
+        Ok(LogPage {
+            session: session.to_string(),
+            offset,
+            total,
+            lines: lines[offset..].to_vec(),
+        })
+    }
+
+    fn read_all(&self, session: &str) -> io::Result<Vec<String>> {
+        if parse_id(session).is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid log session: {}", session),
+            ));
+        }
+        let dir =
+            self.lock().dir.clone().ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "log directory unavailable")
+            })?;
+
+        let mut lines = match File::open(rotated_path(&dir, session)) {
+            Ok(file) => read_lines(file)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        lines.extend(read_lines(File::open(session_path(&dir, session))?)?);
+        Ok(lines)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("log store lock poisoned")
+    }
+}
+
+struct LogFile {
+    id: String,
+    rotated: bool,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn list_files(dir: &Path) -> Vec<LogFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != LOG_EXTENSION {
+                return None;
+            }
+            let stem = path.file_stem()?.to_str()?;
+            let (id, rotated) = match stem.strip_suffix(ROTATED_SUFFIX) {
+                Some(id) => (id, true),
+                None => (stem, false),
+            };
+            let metadata = entry.metadata().ok()?;
+            Some(LogFile {
+                id: id.to_string(),
+                rotated,
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+            })
+        })
+        .collect()
+}
+
+/// Remove files older than `MAX_AGE`, then the oldest until the directory fits `MAX_TOTAL_BYTES`.
+/// Files of active sessions are kept.
+fn prune(dir: &Path, active: &[String]) {
+    let mut files = list_files(dir);
+    files.retain(|file| !active.contains(&file.id));
+    files.sort_by_key(|file| file.modified);
+
+    let mut total: u64 = list_files(dir).iter().map(|file| file.size).sum();
+    let now = SystemTime::now();
+    for file in files {
+        let expired = now
+            .duration_since(file.modified)
+            .is_ok_and(|age| age > MAX_AGE);
+        if !expired && total <= MAX_TOTAL_BYTES {
+            continue;
+        }
+        let path = if file.rotated {
+            rotated_path(dir, &file.id)
+        } else {
+            session_path(dir, &file.id)
+        };
+        if fs::remove_file(path).is_ok() {
+            total = total.saturating_sub(file.size);
+        }
+    }
+}
+
+/// Move the full session file aside and reopen an empty one
+fn rotate(dir: &Path, id: &str) -> io::Result<File> {
+    fs::rename(session_path(dir, id), rotated_path(dir, id))?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(session_path(dir, id))
+}
+
+fn read_lines(file: File) -> io::Result<Vec<String>> {
+    BufReader::new(file).lines().collect()
+}
+
+fn session_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", id, LOG_EXTENSION))
+}
+
+fn rotated_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}{}.{}", id, ROTATED_SUFFIX, LOG_EXTENSION))
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Session ids are `<instance>_<unix millis>`, possibly followed by underscores
+fn parse_id(id: &str) -> Option<(&str, u64)> {
+    let (instance, started_at) = id.trim_end_matches('_').rsplit_once('_')?;
+    let valid = !instance.is_empty()
+        && instance
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    valid.then_some((instance, started_at.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Log directory removed when the test ends
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("flm-logs-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn store(&self) -> LogStore {
+            let store = LogStore::default();
+            store.init(self.0.clone());
+            store
+        }
+
+        fn server_dir(&self) -> PathBuf {
+            self.0.join(LOG_SUBDIR)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_lines(store: &LogStore, count: usize) -> String {
+        let id = store.begin_session("main").unwrap();
+        for i in 0..count {
+            store.append("main", &format!("line {}", i));
+        }
+        id
+    }
+
+    #[test]
+    fn sessions_close_when_ended() {
+        let dir = TempDir::new("sessions");
+        let store = dir.store();
+        let id = write_lines(&store, 2);
+        assert!(store.latest_session("main").unwrap().active);
+
+        store.end_session("main");
+        let session = store.latest_session("main").unwrap();
+        assert_eq!(session.id, id);
+        assert!(!session.active);
+        assert_eq!(session.instance, "main");
+    }
+
+    #[test]
+    fn lines_outside_a_session_are_not_written() {
+        let dir = TempDir::new("outside");
+        let store = dir.store();
+        store.append("main", "[ERROR] download failed");
+        assert!(store.sessions(None).is_empty());
+
+        let id = write_lines(&store, 1);
+        store.end_session("main");
+        store.append("main", "[SYSTEM] schedule disabled");
+        assert_eq!(store.sessions(None).len(), 1);
+        assert_eq!(store.tail(&id, 10).unwrap().lines, ["line 0"]);
+    }
+
+    #[test]
+    fn full_files_are_rotated() {
+        let dir = TempDir::new("rotate");
+        let store = dir.store();
+        let id = store.begin_session("main").unwrap();
+        let big = "x".repeat(MAX_FILE_BYTES as usize);
+        store.append("main", &big);
+        store.append("main", "after rotation");
+
+        assert!(rotated_path(&dir.server_dir(), &id).exists());
+        let page = store.read(&id, 0, 10).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.lines[1], "after rotation");
+        let session = store.latest_session("main").unwrap();
+        assert_eq!(
+            session.size,
+            MAX_FILE_BYTES + 1 + "after rotation".len() as u64 + 1
+        );
+    }
+
+    #[test]
+    fn prune_removes_expired_then_oldest_files() {
+        let dir = TempDir::new("prune");
+        let server_dir = dir.server_dir();
+        fs::create_dir_all(&server_dir).unwrap();
+        let now = SystemTime::now();
+        let create = |id: &str, size: u64, age: Duration| {
+            let file = File::create(session_path(&server_dir, id)).unwrap();
+            file.set_len(size).unwrap();
+            file.set_modified(now - age).unwrap();
+        };
+        let day = Duration::from_secs(24 * 60 * 60);
+        create("main_1", 10, 20 * day);
+        create("main_2", 30 * 1024 * 1024, 3 * day);
+        create("main_3", 30 * 1024 * 1024, 2 * day);
+        create("main_4", 10, 30 * day);
+
+        prune(&server_dir, &[String::from("main_4")]);
+        let mut left: Vec<String> = list_files(&server_dir)
+            .into_iter()
+            .map(|file| file.id)
+            .collect();
+        left.sort();
+        // Expired, then the oldest until under the limit; the active session stays
+        assert_eq!(left, ["main_3", "main_4"]);
+    }
+
+    #[test]
+    fn parses_session_ids() {
+        assert_eq!(
+            parse_id("main_1700000000000"),
+            Some(("main", 1700000000000))
+        );
+        assert_eq!(parse_id("gpu_2_1700"), Some(("gpu_2", 1700)));
+        // Collision suffix
+        assert_eq!(parse_id("main_1700__"), Some(("main", 1700)));
+        assert_eq!(parse_id("main"), None);
+        assert_eq!(parse_id("_1700"), None);
+        assert_eq!(parse_id("../etc_1700"), None);
+        assert_eq!(parse_id("main_abc"), None);
+    }
+
+    #[test]
+    fn pages_stay_within_bounds() {
+        let dir = TempDir::new("pages");
+        let store = dir.store();
+        let id = write_lines(&store, 10);
+
+        let page = store.read(&id, 8, 5).unwrap();
+        assert_eq!((page.offset, page.total), (8, 10));
+        assert_eq!(page.lines, ["line 8", "line 9"]);
+        let past_end = store.read(&id, 20, 5).unwrap();
+        assert_eq!(past_end.offset, 10);
+        assert!(past_end.lines.is_empty());
+
+        let tail = store.tail(&id, 3).unwrap();
+        assert_eq!(tail.offset, 7);
+        assert_eq!(tail.lines, ["line 7", "line 8", "line 9"]);
+        assert_eq!(store.tail(&id, 100).unwrap().lines.len(), 10);
+
+        assert!(store.read("../secret", 0, 10).is_err());
+    }
+}
@@ -0,0 +1,40 @@
+pub mod diagnosis;
+pub mod error;
+pub mod health;
+pub mod history;
+pub mod idle;
+pub mod instance;
+pub mod launch;
+pub mod logs;
+pub mod npu;
+pub mod orphan;
+pub mod output;
+pub mod port;
+pub mod presets;
+pub mod restart;
+pub mod schedule;
+pub mod scheduler;
+pub mod shutdown;
+pub mod stop;
+pub mod supervisor;
+
+pub use error::ServerError;
+pub use history::SessionHistory;
+pub use instance::{emit_log, LaunchConfig, ServerInstance};
+pub use logs::LogStore;
+pub use scheduler::Scheduler;
+pub use shutdown::ShutdownState;
+pub use supervisor::{ServerSupervisor, MAIN_INSTANCE};
+
+/// Command name of the FLM CLI, as declared in the shell scope of the capabilities
+pub const FLM_PROGRAM: &str = "flm";
+/// Event emitted with a `ServerState` payload whenever the server state changes
+pub const SERVER_STATE_EVENT: &str = "server-state-changed";
+/// Event emitted with a `ServerLog` payload for each line of server output
+pub const SERVER_LOG_EVENT: &str = "server-log";
+/// Event emitted with a `ServerOutput` payload for each recognized line of server output
+pub const SERVER_OUTPUT_EVENT: &str = "server-output";
+/// Event emitted with a `ReconfigureEvent` payload at each step of a hot reconfiguration
+pub const SERVER_RECONFIGURE_EVENT: &str = "server-reconfigure";
+/// Event emitted with the new port when the supervisor moves the server to another port
+pub const SERVER_PORT_EVENT: &str = "server-port-changed";
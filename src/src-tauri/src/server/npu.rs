@@ -0,0 +1,38 @@
+use crate::types::{NpuResource, ServerOptions, ServerState, ServerStatus};
+
+/// NPU resources claimed by `flm serve` with this model and options
+pub fn resources(model: &str, options: &ServerOptions) -> Vec<NpuResource> {
+    let mut resources = Vec::new();
+    if !model.is_empty() {
+        resources.push(NpuResource::Llm);
+    }
+    if options.asr == Some(true) {
+        resources.push(NpuResource::Asr);
+    }
+    if options.embed == Some(true) {
+        resources.push(NpuResource::Embeddings);
+    }
+    resources
+}
+
+/// Whether the instance holds its resources, or is about to hold them again
+fn holds_resources(state: &ServerState) -> bool {
+    !matches!(state.status, ServerStatus::Stopped | ServerStatus::Failed)
+}
+
+/// First requested resource already held by another instance, with that instance id
+pub fn find_conflict<'a>(
+    requested: &[NpuResource],
+    others: impl IntoIterator<Item = &'a ServerState>,
+) -> Option<(String, NpuResource)> {
+    others
+        .into_iter()
+        .filter(|state| holds_resources(state))
+        .find_map(|state| {
+            let held = resources(&state.model, &state.options);
+            requested
+                .iter()
+                .find(|resource| held.contains(resource))
+                .map(|resource| (state.id.clone(), *resource))
+        })
+}
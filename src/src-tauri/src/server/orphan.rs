@@ -0,0 +1,204 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, Signal, System, UpdateKind};
+
+use crate::server::{health, port};
+use crate::types::{OrphanServer, ServerOptions, DEFAULT_SERVER_HOST, DEFAULT_SERVER_PORT};
+
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(5);
+const TERMINATE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Look for a `flm serve` left running by a previous session, or anything holding the configured port
+pub async fn detect(options: &ServerOptions) -> Option<OrphanServer> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always),
+    );
+
+    let mut orphan = if port::is_port_in_use(options.host(), options.port()) {
+        let pid = port::listener_pid(options.port());
+        match pid.and_then(|pid| system.process(Pid::from_u32(pid))) {
+            Some(process) if is_flm_serve(process) => Some(from_process(process)),
+            process => Some(OrphanServer {
+                pid,
+                process_name: process.map(|p| p.name().to_string_lossy().into_owned()),
+                is_flm: false,
+                host: options.host().to_string(),
+                port: options.port(),
+                model: None,
+                responding: false,
+            }),
+        }
+    } else {
+        // Not on our port, but the NPU can only serve one model at a time
+        system
+            .processes()
+            .values()
+            .find(|process| is_flm_serve(process))
+            .map(from_process)
+    }?;
+
+    orphan.responding = health::probe(&health::probe_client(), &orphan.host, orphan.port).await
+        != health::ProbeOutcome::Failed;
+    Some(orphan)
+}
+
+pub fn is_alive(pid: u32) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system.process(pid).is_some()
+}
+
+/// Ask the process to terminate, kill it if it is still alive after the timeout
+pub fn terminate(pid: u32) -> bool {
+    let sys_pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[sys_pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    let Some(process) = system.process(sys_pid) else {
+        return true;
+    };
+
+    // SIGTERM is not available on Windows, fall back to a kill
+    if process.kill_with(Signal::Term) != Some(true) {
+        return process.kill();
+    }
+
+    let deadline = Instant::now() + TERMINATE_TIMEOUT;
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return true;
+        }
+        thread::sleep(TERMINATE_POLL_INTERVAL);
+    }
+    process.kill()
+}
+
+fn is_flm_serve(process: &Process) -> bool {
+    let name = process.name().to_string_lossy().to_lowercase();
+    let is_flm = name == "flm" || name == "flm.exe";
+    is_flm && process.cmd().iter().any(|arg| arg == "serve")
+}
+
+fn from_process(process: &Process) -> OrphanServer {
+    let args: Vec<String> = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    let serve = ServeCommand::parse(&args);
+
+    OrphanServer {
+        pid: Some(process.pid().as_u32()),
+        process_name: Some(process.name().to_string_lossy().into_owned()),
+        is_flm: true,
+        host: serve
+            .host
+            .unwrap_or_else(|| DEFAULT_SERVER_HOST.to_string()),
+        port: serve.port.unwrap_or(DEFAULT_SERVER_PORT),
+        model: serve.model,
+        responding: false,
+    }
+}
+
+/// What can be recovered from a `flm serve` command line
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ServeCommand {
+    model: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+impl ServeCommand {
+    fn parse(args: &[String]) -> Self {
+        let mut command = Self::default();
+        let mut rest = args
+            .iter()
+            .skip_while(|arg| arg.as_str() != "serve")
+            .skip(1);
+
+        while let Some(arg) = rest.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                command.model.get_or_insert_with(|| arg.clone());
+                continue;
+            };
+            // Every `flm serve` flag takes a value, inline or as the next argument
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, rest.next().cloned()),
+            };
+            match name {
+                "host" => command.host = value,
+                "port" => command.port = value.and_then(|v| v.parse().ok()),
+                _ => {}
+            }
+        }
+
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(model: Option<&str>, host: Option<&str>, port: Option<u16>) -> ServeCommand {
+        ServeCommand {
+            model: model.map(String::from),
+            host: host.map(String::from),
+            port,
+        }
+    }
+
+    /// Command lines of running servers, with what is recovered from them
+    fn samples() -> Vec<(&'static str, ServeCommand)> {
+        vec![
+            (
+                "flm serve qwen3:8b --port=11434",
+                command(Some("qwen3:8b"), None, Some(11434)),
+            ),
+            (
+                "C:\\flm\\flm.exe serve llama3.2:1b --host 0.0.0.0 --port 52626",
+                command(Some("llama3.2:1b"), Some("0.0.0.0"), Some(52626)),
+            ),
+            (
+                "flm serve --ctx-len 8192 --port 52625 gemma3:4b",
+                command(Some("gemma3:4b"), None, Some(52625)),
+            ),
+            // Side models only
+            (
+                "flm serve --asr 1 --embed 1 --host=127.0.0.1",
+                command(None, Some("127.0.0.1"), None),
+            ),
+            (
+                "flm serve qwen3:8b --port abc",
+                command(Some("qwen3:8b"), None, None),
+            ),
+            // A trailing flag without value
+            (
+                "flm serve qwen3:8b --port",
+                command(Some("qwen3:8b"), None, None),
+            ),
+            ("flm list", command(None, None, None)),
+        ]
+    }
+
+    #[test]
+    fn parses_serve_command_lines() {
+        for (line, expected) in samples() {
+            let args: Vec<String> = line.split(' ').map(String::from).collect();
+            assert_eq!(ServeCommand::parse(&args), expected, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use crate::types::{FlmEvent, ThroughputStage};
+
+const HTTP_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+/// Phrases after which the server cannot go on
+const FATAL_PATTERNS: [&str; 8] = [
+    "fatal",
+    "panic",
+    "terminate called",
+    "segmentation fault",
+    "out of memory",
+    "failed to load",
+    "npu not found",
+    "no npu device",
+];
+const READY_PATTERNS: [&str; 5] = [
+    "listening on",
+    "server started",
+    "server is running",
+    "starting server on port",
+    "running on",
+];
+
+/// Recognize a line of `flm serve` output, stdout and stderr alike.
+/// The output format is not a contract, so matching relies on keywords rather than exact lines.
+pub fn parse_line(line: &str) -> FlmEvent {
+    let message = strip_tags(line).trim();
+    let lower = message.to_lowercase();
+    // Severity may only be carried by a tag, e.g. `[ERROR] ...`
+    let full = line.to_lowercase();
+
+    if FATAL_PATTERNS.iter().any(|p| full.contains(p)) {
+        return FlmEvent::Fatal {
+            message: message.to_string(),
+        };
+    }
+    if has_word(&full, &["error", "exception"]) {
+        return FlmEvent::Error {
+            message: message.to_string(),
+        };
+    }
+    if has_word(&full, &["warning", "warn"]) {
+        return FlmEvent::Warning {
+            message: message.to_string(),
+        };
+    }
+    if let Some(event) = parse_throughput(&lower) {
+        return event;
+    }
+    if lower.contains("load") {
+        if lower.contains("loaded") || lower.contains("complete") {
+            return FlmEvent::ModelLoaded {
+                model: value_after(message, ':'),
+            };
+        }
+        if let Some(percent) = percent(&lower) {
+            return FlmEvent::LoadProgress { percent };
+        }
+        if lower.starts_with("loading") {
+            return FlmEvent::Loading {
+                model: value_after(message, ':'),
+            };
+        }
+    }
+    if READY_PATTERNS.iter().any(|p| lower.contains(p)) {
+        return FlmEvent::Ready { port: port(&lower) };
+    }
+    if let Some(event) = parse_request(message) {
+        return event;
+    }
+    // Progress bars without a label, e.g. `[=====>    ] 45%`
+    if lower.starts_with('[') || lower.contains('|') {
+        if let Some(percent) = percent(&lower) {
+            return FlmEvent::LoadProgress { percent };
+        }
+    }
+
+    FlmEvent::Output {
+        message: message.to_string(),
+    }
+}
+
+/// Remove the bracketed tags FLM puts in front of its own messages, e.g. `[FLM]  `
+fn strip_tags(line: &str) -> &str {
+    let mut rest = line.trim_start();
+    while let Some(tag) = rest.strip_prefix('[') {
+        let Some((name, after)) = tag.split_once(']') else {
+            break;
+        };
+        // Keep progress bars, only alphabetic tags are dropped
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic() || c == ' ') {
+            break;
+        }
+        rest = after.trim_start();
+    }
+    rest
+}
+
+fn words(lower: &str) -> impl Iterator<Item = &str> {
+    lower.split(|c: char| !c.is_ascii_alphanumeric())
+}
+
+fn has_word(lower: &str, candidates: &[&str]) -> bool {
+    words(lower).any(|word| candidates.contains(&word))
+}
+
+/// Trimmed text after the first separator, if any
+fn value_after(message: &str, separator: char) -> Option<String> {
+    let (_, value) = message.split_once(separator)?;
+    let value = value.trim().trim_end_matches("...").trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Last integer percentage of the line, clamped to 100
+fn percent(lower: &str) -> Option<u8> {
+    let end = lower.rfind('%')?;
+    let digits = lower[..end].trim_end();
+    let number = &digits[digits
+        .rfind(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or(0, |i| i + 1)..];
+    let value: f32 = number.parse().ok()?;
+    Some(value.clamp(0.0, 100.0) as u8)
+}
+
+/// Port after `port` or after the last colon of an address
+fn port(lower: &str) -> Option<u16> {
+    if let Some((_, rest)) = lower.rsplit_once("port") {
+        if let Some(port) = words(rest)
+            .find(|w| !w.is_empty())
+            .and_then(|w| w.parse().ok())
+        {
+            return Some(port);
+        }
+    }
+    lower
+        .split_whitespace()
+        .filter_map(|token| token.rsplit_once(':'))
+        .find_map(|(_, port)| port.trim_end_matches(['/', '.', ',']).parse().ok())
+}
+
+/// `<number> tokens/s`, `tok/s` or `tps`, labelled prefill or decoding
+fn parse_throughput(lower: &str) -> Option<FlmEvent> {
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+    let position = tokens.iter().position(|token| {
+        let unit = token.trim_end_matches([',', '.', ')']);
+        matches!(unit, "tokens/s" | "tokens/sec" | "tok/s" | "tps" | "t/s")
+    })?;
+    let value = tokens[..position]
+        .iter()
+        .rev()
+        .find_map(|token| token.trim_start_matches('(').parse::<f32>().ok())?;
+
+    let stage = if lower.contains("prefill") || lower.contains("prompt") {
+        ThroughputStage::Prefill
+    } else {
+        ThroughputStage::Decode
+    };
+    Some(FlmEvent::Throughput {
+        stage,
+        tokens_per_second: value,
+    })
+}
+
+/// `POST /v1/chat/completions`, possibly surrounded by other text
+fn parse_request(message: &str) -> Option<FlmEvent> {
+    let tokens: Vec<&str> = message.split_whitespace().collect();
+    tokens.windows(2).find_map(|pair| {
+        let method = pair[0].trim_matches(|c: char| !c.is_ascii_alphabetic());
+        let path = pair[1].trim_matches(|c: char| c == '"' || c == ',');
+        (HTTP_METHODS.contains(&method) && path.starts_with('/')).then(|| FlmEvent::Request {
+            method: method.to_string(),
+            path: path.to_string(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::OutputSeverity;
+
+    fn output(message: &str) -> FlmEvent {
+        FlmEvent::Output {
+            message: message.to_string(),
+        }
+    }
+
+    /// Typical `flm serve` output lines, with the event each one should produce
+    fn samples() -> Vec<(&'static str, FlmEvent)> {
+        vec![
+            (
+                "[FLM]  Loading model: llama3.2:1b",
+                FlmEvent::Loading {
+                    model: Some(String::from("llama3.2:1b")),
+                },
+            ),
+            (
+                "Loading weights:  45%|████▌     | 9/20",
+                FlmEvent::LoadProgress { percent: 45 },
+            ),
+            (
+                "[=========================>    ] 87.5%",
+                FlmEvent::LoadProgress { percent: 87 },
+            ),
+            (
+                "[FLM]  Model loaded: llama3.2:1b",
+                FlmEvent::ModelLoaded {
+                    model: Some(String::from("llama3.2:1b")),
+                },
+            ),
+            ("Loading complete", FlmEvent::ModelLoaded { model: None }),
+            (
+                "[FLM]  Starting server on port 52625...",
+                FlmEvent::Ready { port: Some(52625) },
+            ),
+            (
+                "WebServer listening on 127.0.0.1:11434",
+                FlmEvent::Ready { port: Some(11434) },
+            ),
+            (
+                "[FLM]  POST /v1/chat/completions",
+                FlmEvent::Request {
+                    method: String::from("POST"),
+                    path: String::from("/v1/chat/completions"),
+                },
+            ),
+            (
+                "127.0.0.1 - \"GET /api/tags HTTP/1.1\" 200",
+                FlmEvent::Request {
+                    method: String::from("GET"),
+                    path: String::from("/api/tags"),
+                },
+            ),
+            (
+                "Prefill speed: 812.40 tokens/s",
+                FlmEvent::Throughput {
+                    stage: ThroughputStage::Prefill,
+                    tokens_per_second: 812.4,
+                },
+            ),
+            (
+                "Average decoding speed: 23.7 tokens/s",
+                FlmEvent::Throughput {
+                    stage: ThroughputStage::Decode,
+                    tokens_per_second: 23.7,
+                },
+            ),
+            (
+                "[stats] generated 128 tokens (41.2 tok/s)",
+                FlmEvent::Throughput {
+                    stage: ThroughputStage::Decode,
+                    tokens_per_second: 41.2,
+                },
+            ),
+            (
+                "[FLM]  Warning: context length exceeds model maximum, truncating",
+                FlmEvent::Warning {
+                    message: String::from(
+                        "Warning: context length exceeds model maximum, truncating",
+                    ),
+                },
+            ),
+            (
+                "[ERROR] Invalid request body",
+                FlmEvent::Error {
+                    message: String::from("Invalid request body"),
+                },
+            ),
+            (
+                "Error: model 'foo:7b' is not installed",
+                FlmEvent::Error {
+                    message: String::from("Error: model 'foo:7b' is not installed"),
+                },
+            ),
+            (
+                "terminate called after throwing an instance of 'std::runtime_error'",
+                FlmEvent::Fatal {
+                    message: String::from(
+                        "terminate called after throwing an instance of 'std::runtime_error'",
+                    ),
+                },
+            ),
+            (
+                "[FLM]  Failed to load model: out of memory",
+                FlmEvent::Fatal {
+                    message: String::from("Failed to load model: out of memory"),
+                },
+            ),
+            (
+                "Enter 'exit' to stop the server",
+                output("Enter 'exit' to stop the server"),
+            ),
+            ("", output("")),
+        ]
+    }
+
+    #[test]
+    fn parses_captured_samples() {
+        for (line, expected) in samples() {
+            assert_eq!(parse_line(line), expected, "line: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn severity_follows_event_kind() {
+        let cases = [
+            ("[FLM]  Model loaded: qwen3:4b", OutputSeverity::Info),
+            ("WARN deprecated option --q-len", OutputSeverity::Warning),
+            ("Exception in request handler", OutputSeverity::Error),
+            ("Segmentation fault (core dumped)", OutputSeverity::Fatal),
+            ("anything else", OutputSeverity::Info),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(parse_line(line).severity(), expected, "line: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn words_containing_keywords_are_not_errors() {
+        // "errors" and "warnings" as counters, not as messages
+        assert!(matches!(
+            parse_line("Request finished, 0 errors, 0 warnings"),
+            FlmEvent::Output { .. }
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::process::Command;
+use std::time::Duration;
+
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Process listening on a port, as far as it can be identified
+#[derive(Debug, Default)]
+pub struct PortOwner {
+    pub pid: Option<u32>,
+    pub name: Option<String>,
+}
+
+/// Whether something already listens on the given host and port
+pub fn is_port_in_use(host: &str, port: u16) -> bool {
+    let Some(addr) = resolve(host, port) else {
+        return false;
+    };
+    // Binding a specific address can succeed on Windows while another process holds the
+    // wildcard address, so also check whether the port accepts connections
+    TcpListener::bind(addr).is_err()
+        || TcpStream::connect_timeout(&connect_addr(addr), CONNECT_TIMEOUT).is_ok()
+}
+
+/// First free port among the candidates
+pub fn find_free_port(host: &str, candidates: impl IntoIterator<Item = u16>) -> Option<u16> {
+    candidates
+        .into_iter()
+        .find(|candidate| !is_port_in_use(host, *candidate))
+}
+
+pub fn owner(port: u16) -> PortOwner {
+    let pid = listener_pid(port);
+    let name = pid.and_then(process_name);
+    PortOwner { pid, name }
+}
+
+fn process_name(pid: u32) -> Option<String> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::nothing(),
+    );
+    system
+        .process(pid)
+        .map(|process| process.name().to_string_lossy().into_owned())
+}
+
+pub fn resolve(host: &str, port: u16) -> Option<SocketAddr> {
+    let host = if host.is_empty() { "127.0.0.1" } else { host };
+    (host, port).to_socket_addrs().ok()?.next()
+}
+
+/// Wildcard addresses are not connectable, use the loopback instead
+pub fn connect_addr(addr: SocketAddr) -> SocketAddr {
+    match addr {
+        SocketAddr::V4(v4) if v4.ip().is_unspecified() => {
+            SocketAddr::from(([127, 0, 0, 1], v4.port()))
+        }
+        SocketAddr::V6(v6) if v6.ip().is_unspecified() => {
+            SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, v6.port()))
+        }
+        _ => addr,
+    }
+}
+
+/// PID of the process listening on the given TCP port, if it can be found
+#[cfg(windows)]
+pub fn listener_pid(port: u16) -> Option<u32> {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let output = Command::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    stdout.lines().find_map(|line| {
+        // Proto, local address, foreign address, state, PID. The state column is
+        // localized, a listening socket is recognized by its unbound foreign address
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let [proto, local, foreign, _state, pid] = columns.as_slice() else {
+            return None;
+        };
+        let listening = foreign.ends_with(":0") || *foreign == "*:*";
+        if !proto.eq_ignore_ascii_case("tcp") || !listening {
+            return None;
+        }
+        let local_port = local.rsplit(':').next()?.parse::<u16>().ok()?;
+        if local_port != port {
+            return None;
+        }
+        pid.parse().ok()
+    })
+}
+
+/// PID of the process listening on the given TCP port, if it can be found
+#[cfg(not(windows))]
+pub fn listener_pid(port: u16) -> Option<u32> {
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-t"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
@@ -0,0 +1,45 @@
+use crate::server::LaunchConfig;
+use crate::types::ServerOptions;
+
+/// Prefix of the preset ids, same as the frontend `isPresetId`
+pub const PRESET_PREFIX: &str = "preset:";
+
+/// Model and options applied by a preset
+struct Preset {
+    id: &'static str,
+    model: &'static str,
+    apply: fn(&mut ServerOptions),
+}
+
+/// Mirror of `DEFAULT_SYSTEM_PRESETS` in the frontend types
+const SYSTEM_PRESETS: [Preset; 1] = [Preset {
+    id: "preset:audio-only",
+    model: "",
+    apply: |options| {
+        options.asr = Some(true);
+        options.ctx_len = Some(0);
+    },
+}];
+
+pub fn is_preset(selection: &str) -> bool {
+    selection.starts_with(PRESET_PREFIX)
+}
+
+/// Launch parameters of a UI selection, a model name or a preset id applied over `options`
+pub fn resolve(selection: &str, options: &ServerOptions) -> Option<LaunchConfig> {
+    if !is_preset(selection) {
+        return Some(LaunchConfig {
+            model: selection.to_string(),
+            options: options.clone(),
+        });
+    }
+    let preset = SYSTEM_PRESETS
+        .iter()
+        .find(|preset| preset.id == selection)?;
+    let mut options = options.clone();
+    (preset.apply)(&mut options);
+    Some(LaunchConfig {
+        model: preset.model.to_string(),
+        options,
+    })
+}
@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::types::{FailureReason, RestartMode, RestartPolicy};
+
+/// What to do after the server exited without being asked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartDecision {
+    Stay,
+    Restart { attempt: u32, delay: Duration },
+    GiveUp(FailureReason),
+}
+
+/// Counts restart attempts and recent crashes for the current run of the server
+#[derive(Debug, Default)]
+pub struct RestartTracker {
+    attempts: u32,
+    crashes: VecDeque<Instant>,
+}
+
+impl RestartTracker {
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.crashes.clear();
+    }
+
+    pub fn on_exit(
+        &mut self,
+        policy: &RestartPolicy,
+        exit_code: Option<i32>,
+        uptime: Duration,
+        now: Instant,
+    ) -> RestartDecision {
+        let failed = exit_code != Some(0);
+        let wanted = match policy.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => failed,
+            RestartMode::Always => true,
+        };
+        if !wanted {
+            self.reset();
+            return RestartDecision::Stay;
+        }
+
+        let window = policy.crash_loop_window();
+        if uptime >= window {
+            // The last run was stable, start counting again
+            self.reset();
+        }
+
+        if failed {
+            self.crashes.push_back(now);
+            while let Some(first) = self.crashes.front() {
+                if now.duration_since(*first) > window {
+                    self.crashes.pop_front();
+                } else {
+                    break;
+                }
+            }
+            if self.crashes.len() as u32 >= policy.crash_loop_threshold.max(1) {
+                return RestartDecision::GiveUp(FailureReason::CrashLoop);
+            }
+        }
+
+        if self.attempts >= policy.max_retries {
+            return RestartDecision::GiveUp(FailureReason::RetriesExhausted);
+        }
+
+        self.attempts += 1;
+        RestartDecision::Restart {
+            attempt: self.attempts,
+            delay: policy.backoff(self.attempts),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT_RUN: Duration = Duration::from_secs(5);
+
+    fn policy(mode: RestartMode) -> RestartPolicy {
+        RestartPolicy {
+            mode,
+            ..RestartPolicy::default()
+        }
+    }
+
+    fn restart(attempt: u32, delay_secs: u64) -> RestartDecision {
+        RestartDecision::Restart {
+            attempt,
+            delay: Duration::from_secs(delay_secs),
+        }
+    }
+
+    #[test]
+    fn modes_decide_which_exits_restart() {
+        let now = Instant::now();
+        let mut tracker = RestartTracker::default();
+        let never = policy(RestartMode::Never);
+        assert_eq!(
+            tracker.on_exit(&never, Some(1), SHORT_RUN, now),
+            RestartDecision::Stay
+        );
+
+        let on_failure = policy(RestartMode::OnFailure);
+        assert_eq!(
+            tracker.on_exit(&on_failure, Some(0), SHORT_RUN, now),
+            RestartDecision::Stay
+        );
+        assert_eq!(
+            tracker.on_exit(&on_failure, Some(1), SHORT_RUN, now),
+            restart(1, 2)
+        );
+
+        let mut tracker = RestartTracker::default();
+        let always = policy(RestartMode::Always);
+        assert_eq!(
+            tracker.on_exit(&always, Some(0), SHORT_RUN, now),
+            restart(1, 2)
+        );
+    }
+
+    #[test]
+    fn quick_crashes_end_in_a_crash_loop() {
+        let start = Instant::now();
+        let mut tracker = RestartTracker::default();
+        let policy = policy(RestartMode::OnFailure);
+        let at = |secs| start + Duration::from_secs(secs);
+        assert_eq!(
+            tracker.on_exit(&policy, Some(1), SHORT_RUN, at(0)),
+            restart(1, 2)
+        );
+        assert_eq!(
+            tracker.on_exit(&policy, Some(1), SHORT_RUN, at(10)),
+            restart(2, 4)
+        );
+        assert_eq!(
+            tracker.on_exit(&policy, Some(1), SHORT_RUN, at(20)),
+            RestartDecision::GiveUp(FailureReason::CrashLoop)
+        );
+    }
+
+    #[test]
+    fn crashes_outside_the_window_are_forgotten() {
+        let start = Instant::now();
+        let mut tracker = RestartTracker::default();
+        let policy = policy(RestartMode::OnFailure);
+        let at = |secs| start + Duration::from_secs(secs);
+        tracker.on_exit(&policy, Some(1), SHORT_RUN, at(0));
+        tracker.on_exit(&policy, Some(1), SHORT_RUN, at(100));
+        // The first crash left the 120 s window, only two remain
+        assert_eq!(
+            tracker.on_exit(&policy, Some(1), SHORT_RUN, at(150)),
+            restart(3, 8)
+        );
+    }
+
+    #[test]
+    fn stable_run_resets_the_counters() {
+        let start = Instant::now();
+        let mut tracker = RestartTracker::default();
+        let policy = policy(RestartMode::OnFailure);
+        tracker.on_exit(&policy, Some(1), SHORT_RUN, start);
+        tracker.on_exit(&policy, Some(1), SHORT_RUN, start + Duration::from_secs(10));
+
+        let stable = policy.crash_loop_window();
+        assert_eq!(
+            tracker.on_exit(&policy, Some(1), stable, start + Duration::from_secs(200)),
+            restart(1, 2)
+        );
+    }
+
+    #[test]
+    fn retries_run_out() {
+        let now = Instant::now();
+        let mut tracker = RestartTracker::default();
+        let policy = RestartPolicy {
+            max_retries: 2,
+            ..policy(RestartMode::Always)
+        };
+        assert_eq!(
+            tracker.on_exit(&policy, Some(0), SHORT_RUN, now),
+            restart(1, 2)
+        );
+        assert_eq!(
+            tracker.on_exit(&policy, Some(0), SHORT_RUN, now),
+            restart(2, 4)
+        );
+        assert_eq!(
+            tracker.on_exit(&policy, Some(0), SHORT_RUN, now),
+            RestartDecision::GiveUp(FailureReason::RetriesExhausted)
+        );
+
+        tracker.reset();
+        assert_eq!(
+            tracker.on_exit(&policy, Some(0), SHORT_RUN, now),
+            restart(1, 2)
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::types::{Schedule, ScheduleWindow, ScheduledTransition, TransitionKind};
+
+/// Longest wait between two evaluations, also bounds the delay after a clock change
+const MAX_WAIT: Duration = Duration::from_secs(30);
+/// Days looked ahead for upcoming transitions
+const HORIZON_DAYS: u64 = 8;
+
+/// Source of the local wall-clock time, replaced in tests
+pub trait Clock: Send + Sync {
+    fn now(&self) -> NaiveDateTime;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+}
+
+/// What the scheduler asks of the main instance at a window boundary
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleAction {
+    Start(ScheduleWindow),
+    Stop,
+}
+
+struct Inner {
+    schedule: Schedule,
+    /// Window active at the last evaluation, `None` until the first one
+    last: Option<Option<usize>>,
+}
+
+/// Weekly start/stop windows of the main instance.
+/// Only boundaries trigger actions, so a manual start or stop holds until the next one.
+pub struct ScheduleState {
+    clock: Arc<dyn Clock>,
+    inner: Mutex<Inner>,
+}
+
+impl Default for ScheduleState {
+    fn default() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+}
+
+impl ScheduleState {
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            clock,
+            inner: Mutex::new(Inner {
+                schedule: Schedule::default(),
+                last: None,
+            }),
+        }
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        self.lock().schedule.clone()
+    }
+
+    /// Replace the schedule. Once evaluated, the window running now counts as already
+    /// seen so an edit never overrides a manual start or stop, only the next boundary does.
+    pub fn set_schedule(&self, schedule: Schedule) {
+        let now = self.clock.now();
+        let mut inner = self.lock();
+        inner.last = inner.last.map(|_| active_window(&schedule, now));
+        inner.schedule = schedule;
+    }
+
+    /// Action due since the last evaluation, if a window boundary was crossed.
+    /// The first evaluation only starts the server, it never stops one started by hand.
+    pub fn tick(&self) -> Option<ScheduleAction> {
+        let now = self.clock.now();
+        let mut inner = self.lock();
+        let active = active_window(&inner.schedule, now);
+        if !inner.schedule.enabled {
+            // Enabling it inside a window then waits for the next boundary too
+            inner.last = Some(active);
+            return None;
+        }
+
+        match inner.last.replace(active) {
+            Some(previous) if previous == active => None,
+            previous => match active {
+                Some(index) => Some(ScheduleAction::Start(inner.schedule.windows[index].clone())),
+                None if previous.is_some() => Some(ScheduleAction::Stop),
+                None => None,
+            },
+        }
+    }
+
+    /// Next starts and stops, in order
+    pub fn upcoming(&self, count: usize) -> Vec<ScheduledTransition> {
+        let schedule = self.schedule();
+        if !schedule.enabled {
+            return Vec::new();
+        }
+        transitions(&schedule, self.clock.now())
+            .into_iter()
+            .take(count)
+            .collect()
+    }
+
+    /// How long to wait before the next evaluation
+    pub fn next_wait(&self) -> Duration {
+        let schedule = self.schedule();
+        let now = self.clock.now();
+        boundaries(&schedule, now)
+            .first()
+            .and_then(|at| (*at - now).to_std().ok())
+            .map_or(MAX_WAIT, |wait| {
+                wait.clamp(Duration::from_secs(1), MAX_WAIT)
+            })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().expect("schedule lock poisoned")
+    }
+}
+
+/// Start and end of a window occurrence starting on `date`
+fn occurrence(window: &ScheduleWindow, date: NaiveDate) -> Option<(NaiveDateTime, NaiveDateTime)> {
+    let weekday = date.weekday().num_days_from_monday();
+    if !window.days.iter().any(|day| day.index() == weekday) {
+        return None;
+    }
+    let start = parse_time(&window.start)?;
+    let end = parse_time(&window.end)?;
+    if start == end {
+        return None;
+    }
+    // Ends the next day when it runs past midnight
+    let end_date = if end > start {
+        date
+    } else {
+        date.checked_add_days(Days::new(1))?
+    };
+    Some((date.and_time(start), end_date.and_time(end)))
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// First window running at `now`, an occurrence may have started the day before
+fn active_window(schedule: &Schedule, now: NaiveDateTime) -> Option<usize> {
+    let today = now.date();
+    let yesterday = today.checked_sub_days(Days::new(1))?;
+    schedule.windows.iter().position(|window| {
+        [yesterday, today]
+            .into_iter()
+            .filter_map(|date| occurrence(window, date))
+            .any(|(start, end)| start <= now && now < end)
+    })
+}
+
+/// Window starts and ends after `now`, sorted and deduplicated
+fn boundaries(schedule: &Schedule, now: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let today = now.date();
+    let mut instants: Vec<NaiveDateTime> = (0..=HORIZON_DAYS)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .chain(today.checked_sub_days(Days::new(1)))
+        .flat_map(|date| {
+            schedule
+                .windows
+                .iter()
+                .filter_map(move |window| occurrence(window, date))
+        })
+        .flat_map(|(start, end)| [start, end])
+        .filter(|at| *at > now)
+        .collect();
+    instants.sort();
+    instants.dedup();
+    instants
+}
+
+/// Boundaries where the active window actually changes, adjacent windows yield a single start
+fn transitions(schedule: &Schedule, now: NaiveDateTime) -> Vec<ScheduledTransition> {
+    let mut current = active_window(schedule, now);
+    let mut result = Vec::new();
+    for at in boundaries(schedule, now) {
+        let active = active_window(schedule, at);
+        if active == current {
+            continue;
+        }
+        let (kind, window) = match active {
+            Some(index) => (TransitionKind::Start, index),
+            None => (TransitionKind::Stop, current.unwrap_or_default()),
+        };
+        let model = match kind {
+            TransitionKind::Start => {
+                let window = &schedule.windows[window];
+                let name = window
+                    .preset
+                    .clone()
+                    .unwrap_or_else(|| window.model.clone());
+                (!name.is_empty()).then_some(name)
+            }
+            TransitionKind::Stop => None,
+        };
+        result.push(ScheduledTransition {
+            kind,
+            at,
+            window,
+            model,
+        });
+        current = active;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ScheduleDay;
+
+    /// Clock moved by hand
+    struct FakeClock(Mutex<NaiveDateTime>);
+
+    impl FakeClock {
+        fn at(value: &str) -> Arc<Self> {
+            Arc::new(Self(Mutex::new(datetime(value))))
+        }
+
+        fn set(&self, value: &str) {
+            *self.0.lock().unwrap() = datetime(value);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> NaiveDateTime {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    /// 2024-01-01 is a Monday
+    fn datetime(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn window(days: &[ScheduleDay], start: &str, end: &str, model: &str) -> ScheduleWindow {
+        ScheduleWindow {
+            days: days.to_vec(),
+            start: start.to_string(),
+            end: end.to_string(),
+            model: model.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn state(clock: &Arc<FakeClock>, windows: Vec<ScheduleWindow>) -> ScheduleState {
+        let state = ScheduleState::with_clock(clock.clone());
+        state.set_schedule(Schedule {
+            enabled: true,
+            windows,
+        });
+        state
+    }
+
+    const WEEKDAYS: [ScheduleDay; 5] = [
+        ScheduleDay::Mon,
+        ScheduleDay::Tue,
+        ScheduleDay::Wed,
+        ScheduleDay::Thu,
+        ScheduleDay::Fri,
+    ];
+
+    fn started_model(action: Option<ScheduleAction>) -> Option<String> {
+        match action {
+            Some(ScheduleAction::Start(window)) => Some(window.model),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn starts_on_launch_inside_a_window_only() {
+        let clock = FakeClock::at("2024-01-01 10:00");
+        let schedule = state(
+            &clock,
+            vec![window(&WEEKDAYS, "09:00", "18:00", "qwen3:4b")],
+        );
+        assert_eq!(
+            started_model(schedule.tick()),
+            Some(String::from("qwen3:4b"))
+        );
+        assert_eq!(schedule.tick(), None);
+
+        let clock = FakeClock::at("2024-01-01 20:00");
+        let schedule = state(
+            &clock,
+            vec![window(&WEEKDAYS, "09:00", "18:00", "qwen3:4b")],
+        );
+        assert_eq!(schedule.tick(), None);
+    }
+
+    #[test]
+    fn acts_only_at_boundaries() {
+        let clock = FakeClock::at("2024-01-01 07:00");
+        let schedule = state(
+            &clock,
+            vec![window(&WEEKDAYS, "09:00", "18:00", "qwen3:4b")],
+        );
+        // A manual start before the window is left alone
+        assert_eq!(schedule.tick(), None);
+        clock.set("2024-01-01 08:59");
+        assert_eq!(schedule.tick(), None);
+
+        clock.set("2024-01-01 09:00");
+        assert!(started_model(schedule.tick()).is_some());
+        // A manual stop inside the window holds until its end
+        clock.set("2024-01-01 12:00");
+        assert_eq!(schedule.tick(), None);
+
+        clock.set("2024-01-01 18:00");
+        assert_eq!(schedule.tick(), Some(ScheduleAction::Stop));
+        clock.set("2024-01-01 23:00");
+        assert_eq!(schedule.tick(), None);
+    }
+
+    #[test]
+    fn edits_during_a_window_keep_a_manual_start() {
+        let clock = FakeClock::at("2024-01-01 10:00");
+        let schedule = state(
+            &clock,
+            vec![window(&WEEKDAYS, "09:00", "18:00", "qwen3:4b")],
+        );
+        assert!(started_model(schedule.tick()).is_some());
+
+        // Started by hand with another model, then the window is edited
+        clock.set("2024-01-01 11:00");
+        schedule.set_schedule(Schedule {
+            enabled: true,
+            windows: vec![
+                window(&[ScheduleDay::Sat], "08:00", "09:00", "gemma3:4b"),
+                window(&WEEKDAYS, "09:00", "19:00", "llama3.2:1b"),
+            ],
+        });
+        assert_eq!(schedule.tick(), None);
+
+        // Toggled off and on from the tray
+        let mut toggled = schedule.schedule();
+        toggled.enabled = false;
+        schedule.set_schedule(toggled.clone());
+        assert_eq!(schedule.tick(), None);
+        toggled.enabled = true;
+        schedule.set_schedule(toggled);
+        assert_eq!(schedule.tick(), None);
+
+        clock.set("2024-01-01 19:00");
+        assert_eq!(schedule.tick(), Some(ScheduleAction::Stop));
+        clock.set("2024-01-02 09:00");
+        assert_eq!(
+            started_model(schedule.tick()),
+            Some(String::from("llama3.2:1b"))
+        );
+    }
+
+    #[test]
+    fn overnight_window_runs_past_midnight() {
+        let windows = vec![window(&[ScheduleDay::Fri], "22:00", "02:00", "llama3.2:1b")];
+        let clock = FakeClock::at("2024-01-06 01:00");
+        let schedule = state(&clock, windows);
+        assert!(started_model(schedule.tick()).is_some());
+
+        clock.set("2024-01-06 02:00");
+        assert_eq!(schedule.tick(), Some(ScheduleAction::Stop));
+    }
+
+    #[test]
+    fn switching_windows_starts_the_next_model() {
+        let windows = vec![
+            window(&[ScheduleDay::Mon], "09:00", "12:00", "qwen3:4b"),
+            window(&[ScheduleDay::Mon], "12:00", "18:00", "gemma3:4b"),
+        ];
+        let clock = FakeClock::at("2024-01-01 11:00");
+        let schedule = state(&clock, windows);
+        assert!(schedule.tick().is_some());
+
+        clock.set("2024-01-01 12:00");
+        assert_eq!(
+            started_model(schedule.tick()),
+            Some(String::from("gemma3:4b"))
+        );
+    }
+
+    #[test]
+    fn upcoming_transitions_merge_adjacent_windows() {
+        let windows = vec![
+            window(&[ScheduleDay::Mon], "09:00", "12:00", "qwen3:4b"),
+            window(&[ScheduleDay::Mon], "12:00", "18:00", "gemma3:4b"),
+        ];
+        let clock = FakeClock::at("2024-01-01 08:00");
+        let schedule = state(&clock, windows);
+
+        let upcoming: Vec<(TransitionKind, NaiveDateTime)> = schedule
+            .upcoming(4)
+            .into_iter()
+            .map(|transition| (transition.kind, transition.at))
+            .collect();
+        assert_eq!(
+            upcoming,
+            vec![
+                (TransitionKind::Start, datetime("2024-01-01 09:00")),
+                (TransitionKind::Start, datetime("2024-01-01 12:00")),
+                (TransitionKind::Stop, datetime("2024-01-01 18:00")),
+                (TransitionKind::Start, datetime("2024-01-08 09:00")),
+            ]
+        );
+        assert_eq!(schedule.next_wait(), MAX_WAIT);
+
+        // Woken up right at the boundary
+        *clock.0.lock().unwrap() = datetime("2024-01-01 17:59") + chrono::Duration::seconds(50);
+        assert_eq!(schedule.next_wait(), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn disabled_or_invalid_schedule_does_nothing() {
+        let clock = FakeClock::at("2024-01-01 10:00");
+        let schedule = ScheduleState::with_clock(clock.clone());
+        schedule.set_schedule(Schedule {
+            enabled: false,
+            windows: vec![window(&WEEKDAYS, "09:00", "18:00", "qwen3:4b")],
+        });
+        assert_eq!(schedule.tick(), None);
+        assert!(schedule.upcoming(2).is_empty());
+
+        let schedule = state(&clock, vec![window(&WEEKDAYS, "9h", "18:00", "qwen3:4b")]);
+        assert_eq!(schedule.tick(), None);
+        assert!(schedule.upcoming(2).is_empty());
+    }
+}
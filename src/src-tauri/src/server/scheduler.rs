@@ -0,0 +1,84 @@
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::config;
+use crate::server::schedule::{ScheduleAction, ScheduleState};
+use crate::server::{emit_log, ServerError, ServerSupervisor, MAIN_INSTANCE};
+use crate::tray::refresh_tray_menu;
+use crate::types::{AppConfig, Schedule, ServerStatus, StopCause};
+
+/// Drives the main instance from the weekly schedule
+#[derive(Default)]
+pub struct Scheduler {
+    state: ScheduleState,
+    /// Wakes the task up when the schedule changes
+    changed: Notify,
+}
+
+impl Scheduler {
+    pub fn init_config(&self, config: &AppConfig) {
+        self.state.set_schedule(config.schedule.clone());
+    }
+
+    pub fn state(&self) -> &ScheduleState {
+        &self.state
+    }
+
+    /// Replace the schedule and persist it in the app config
+    pub fn set_schedule(&self, app: &AppHandle, schedule: Schedule) -> Result<(), ServerError> {
+        self.state.set_schedule(schedule.clone());
+        self.changed.notify_one();
+        refresh_tray_menu(app);
+        config::save_key(app, "schedule", &schedule).map_err(|e| ServerError::Io(e.to_string()))
+    }
+}
+
+/// Evaluate the schedule until the app exits
+pub fn spawn(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let scheduler = app.state::<Scheduler>();
+            if let Some(action) = scheduler.state.tick() {
+                apply(&app, action).await;
+                refresh_tray_menu(&app);
+            }
+            let wait = scheduler.state.next_wait();
+            let _ = tokio::time::timeout(wait, scheduler.changed.notified()).await;
+        }
+    });
+}
+
+async fn apply(app: &AppHandle, action: ScheduleAction) {
+    let instance = app.state::<ServerSupervisor>().main();
+    let status = instance.state().status;
+
+    match action {
+        ScheduleAction::Start(window) => {
+            emit_log(
+                app,
+                MAIN_INSTANCE,
+                format!("[SYSTEM] Schedule: starting {}", window.model),
+            );
+            if matches!(status, ServerStatus::Starting | ServerStatus::Running) {
+                // Restarts only when the window runs another model or options
+                instance.reconfigure(app, window.model, window.options);
+            } else if let Err(e) = instance.start(app, window.model, window.options) {
+                emit_log(app, MAIN_INSTANCE, format!("[ERROR] Schedule: {}", e));
+            }
+        }
+        ScheduleAction::Stop => {
+            if matches!(status, ServerStatus::Stopped | ServerStatus::Failed) {
+                return;
+            }
+            emit_log(
+                app,
+                MAIN_INSTANCE,
+                String::from("[SYSTEM] Schedule: stopping server"),
+            );
+            if let Err(e) = instance.stop_for(app, StopCause::Schedule).await {
+                emit_log(app, MAIN_INSTANCE, format!("[ERROR] Schedule: {}", e));
+            }
+        }
+    }
+}
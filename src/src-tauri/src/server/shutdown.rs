@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use tauri::{AppHandle, ExitRequestApi, Manager};
+
+use crate::download::DownloadManager;
+use crate::server::ServerSupervisor;
+use crate::tray::show_shutdown_progress;
+
+const IDLE: u8 = 0;
+const STOPPING: u8 = 1;
+const DONE: u8 = 2;
+
+/// Tracks the ordered shutdown triggered by the first exit request
+#[derive(Default)]
+pub struct ShutdownState {
+    phase: AtomicU8,
+}
+
+impl ShutdownState {
+    fn begin(&self) -> bool {
+        self.phase
+            .compare_exchange(IDLE, STOPPING, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    fn finish(&self) {
+        self.phase.store(DONE, Ordering::SeqCst);
+    }
+
+    fn is_done(&self) -> bool {
+        self.phase.load(Ordering::SeqCst) == DONE
+    }
+}
+
+/// Quit the companion, either stopping the servers first or leaving every instance running
+pub fn quit(app: &AppHandle, keep_server: bool) {
+    if keep_server {
+        app.state::<ServerSupervisor>().detach();
+    }
+    app.exit(0);
+}
+
+/// `RunEvent::ExitRequested`: hold the exit until the server has stopped
+pub fn on_exit_requested(app: &AppHandle, code: Option<i32>, api: &ExitRequestApi) {
+    let shutdown = app.state::<ShutdownState>();
+    if shutdown.is_done() || !app.state::<ServerSupervisor>().has_process() {
+        return;
+    }
+
+    api.prevent_exit();
+    if !shutdown.begin() {
+        return;
+    }
+
+    show_shutdown_progress(app);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // stop() already falls back to a kill after its timeout
+        app.state::<ServerSupervisor>().stop_all(&app).await;
+        app.state::<ShutdownState>().finish();
+
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.destroy();
+        }
+        app.exit(code.unwrap_or(0));
+    });
+}
+
+/// `RunEvent::Exit` (also raised on OS session end): last chance, the event loop is gone
+pub fn on_exit(app: &AppHandle) {
+    // The interrupted download stays queued and starts over on the next launch
+    app.state::<DownloadManager>().abort();
+    app.state::<ServerSupervisor>().shutdown_blocking();
+}
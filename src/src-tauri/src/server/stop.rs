@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::server::ServerError;
+use crate::types::{ServerState, ServerStatus};
+
+/// How a server sent its exit command ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Graceful,
+    /// Killed once the grace period was over
+    Killed,
+}
+
+/// Wait for a server sent its exit command to be `Stopped`, killing it after `grace`.
+/// The termination of a killed process is awaited too, for up to `kill_wait`, so a start
+/// that follows never finds the instance still `Stopping`.
+pub async fn wait_for_exit(
+    mut state: watch::Receiver<ServerState>,
+    grace: Duration,
+    kill_wait: Duration,
+    kill: impl FnOnce() -> Result<(), ServerError>,
+) -> Result<Exit, ServerError> {
+    if stopped(&mut state, grace).await {
+        return Ok(Exit::Graceful);
+    }
+    kill()?;
+    if stopped(&mut state, kill_wait).await {
+        Ok(Exit::Killed)
+    } else {
+        Err(ServerError::Io(String::from(
+            "the killed server did not report its exit",
+        )))
+    }
+}
+
+async fn stopped(state: &mut watch::Receiver<ServerState>, timeout: Duration) -> bool {
+    let stopped = state.wait_for(|state| state.status == ServerStatus::Stopped);
+    matches!(tokio::time::timeout(timeout, stopped).await, Ok(Ok(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    const GRACE: Duration = Duration::from_millis(50);
+    const KILL_WAIT: Duration = Duration::from_secs(2);
+
+    fn stopping() -> Arc<watch::Sender<ServerState>> {
+        Arc::new(watch::Sender::new(ServerState {
+            status: ServerStatus::Stopping,
+            ..ServerState::default()
+        }))
+    }
+
+    /// Stand-in for the `Terminated` event of the shell plugin, handled after a short delay
+    fn terminate_later(state: &Arc<watch::Sender<ServerState>>) {
+        let state = state.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            state.send_modify(|state| state.status = ServerStatus::Stopped);
+        });
+    }
+
+    #[tokio::test]
+    async fn graceful_exit_is_not_killed() {
+        let state = stopping();
+        terminate_later(&state);
+        let exit = wait_for_exit(state.subscribe(), KILL_WAIT, KILL_WAIT, || {
+            panic!("killed a server that exited")
+        })
+        .await;
+        assert_eq!(exit.unwrap(), Exit::Graceful);
+    }
+
+    #[tokio::test]
+    async fn forced_kill_waits_for_the_termination() {
+        let state = stopping();
+        let exit = wait_for_exit(state.subscribe(), GRACE, KILL_WAIT, || {
+            terminate_later(&state);
+            Ok(())
+        })
+        .await;
+        assert_eq!(exit.unwrap(), Exit::Killed);
+        // The start of the restart no longer finds the instance busy
+        assert_eq!(state.borrow().status, ServerStatus::Stopped);
+    }
+
+    #[tokio::test]
+    async fn kill_without_termination_is_an_error() {
+        let state = stopping();
+        let exit = wait_for_exit(state.subscribe(), GRACE, GRACE, || Ok(())).await;
+        assert!(matches!(exit, Err(ServerError::Io(_))));
+        assert_eq!(state.borrow().status, ServerStatus::Stopping);
+
+        let exit = wait_for_exit(state.subscribe(), GRACE, GRACE, || {
+            Err(ServerError::Io(String::from("access denied")))
+        })
+        .await;
+        assert!(matches!(exit, Err(ServerError::Io(message)) if message == "access denied"));
+    }
+}
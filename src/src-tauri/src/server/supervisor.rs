@@ -0,0 +1,314 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tauri::AppHandle;
+
+use crate::config;
+use crate::server::{npu, LaunchConfig, ServerError, ServerInstance};
+use crate::tray::refresh_tray_menu;
+use crate::types::{
+    AppConfig, IdlePolicy, InstanceConfig, OrphanAction, OrphanServer, PortSelection,
+    RestartPolicy, ServerOptions, ServerState, StopCause,
+};
+
+/// Id of the instance driven by the UI selection, always present
+pub const MAIN_INSTANCE: &str = "main";
+
+#[derive(Default)]
+struct Settings {
+    policy: RestartPolicy,
+    port_selection: PortSelection,
+    idle: IdlePolicy,
+}
+
+/// Registry of the `flm serve` instances, keyed by instance id
+pub struct ServerSupervisor {
+    /// Main instance first, then the additional ones in creation order
+    instances: Mutex<Vec<Arc<ServerInstance>>>,
+    settings: Mutex<Settings>,
+}
+
+impl Default for ServerSupervisor {
+    fn default() -> Self {
+        let main = ServerInstance::new(MAIN_INSTANCE, LaunchConfig::default());
+        Self {
+            instances: Mutex::new(vec![Arc::new(main)]),
+            settings: Mutex::new(Settings::default()),
+        }
+    }
+}
+
+impl ServerSupervisor {
+    /// Restore the settings and additional instances read from the config at startup
+    pub fn init_config(&self, config: &AppConfig) {
+        {
+            let mut settings = self.settings();
+            settings.policy = config.restart_policy.clone();
+            settings.port_selection = config.port_selection.clone();
+            settings.idle = config.idle_stop.clone();
+        }
+
+        let mut instances = self.instances();
+        for instance in &config.server_instances {
+            if validate_id(&instance.id).is_err()
+                || instances.iter().any(|known| known.id() == instance.id)
+            {
+                continue;
+            }
+            let launch = LaunchConfig {
+                model: instance.model.clone(),
+                options: instance.options.clone(),
+            };
+            instances.push(Arc::new(ServerInstance::new(&instance.id, launch)));
+        }
+    }
+
+    pub fn main(&self) -> Arc<ServerInstance> {
+        self.instances()[0].clone()
+    }
+
+    pub fn instance(&self, id: &str) -> Result<Arc<ServerInstance>, ServerError> {
+        self.instances()
+            .iter()
+            .find(|instance| instance.id() == id)
+            .cloned()
+            .ok_or_else(|| ServerError::UnknownInstance(id.to_string()))
+    }
+
+    /// Instance targeted by a command, the main one when none is given
+    pub fn instance_or_main(&self, id: Option<&str>) -> Result<Arc<ServerInstance>, ServerError> {
+        match id {
+            Some(id) => self.instance(id),
+            None => Ok(self.main()),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Arc<ServerInstance>> {
+        self.instances().clone()
+    }
+
+    pub fn states(&self) -> Vec<ServerState> {
+        self.instances()
+            .iter()
+            .map(|instance| instance.state())
+            .collect()
+    }
+
+    /// Register a new instance and persist it in the app config
+    pub fn add_instance(
+        &self,
+        app: &AppHandle,
+        config: InstanceConfig,
+    ) -> Result<ServerState, ServerError> {
+        validate_id(&config.id)?;
+        let instance = {
+            let mut instances = self.instances();
+            if instances.iter().any(|known| known.id() == config.id) {
+                return Err(ServerError::InvalidInstance(format!(
+                    "'{}' already exists",
+                    config.id
+                )));
+            }
+            let launch = LaunchConfig {
+                model: config.model,
+                options: config.options,
+            };
+            let instance = Arc::new(ServerInstance::new(&config.id, launch));
+            instances.push(instance.clone());
+            instance
+        };
+
+        refresh_tray_menu(app);
+        self.save_instances(app)?;
+        Ok(instance.state())
+    }
+
+    /// Remove a stopped instance, the main one cannot be removed
+    pub fn remove_instance(&self, app: &AppHandle, id: &str) -> Result<(), ServerError> {
+        if id == MAIN_INSTANCE {
+            return Err(ServerError::InvalidInstance(String::from(
+                "the main instance cannot be removed",
+            )));
+        }
+        {
+            let mut instances = self.instances();
+            let index = instances
+                .iter()
+                .position(|instance| instance.id() == id)
+                .ok_or_else(|| ServerError::UnknownInstance(id.to_string()))?;
+            if instances[index].has_process() {
+                return Err(ServerError::AlreadyRunning);
+            }
+            instances.remove(index);
+        }
+
+        refresh_tray_menu(app);
+        self.save_instances(app)
+    }
+
+    /// Change the model and options of an instance, persisted for the additional ones
+    pub fn set_launch_config(
+        &self,
+        app: &AppHandle,
+        id: Option<&str>,
+        model: String,
+        options: ServerOptions,
+    ) -> Result<(), ServerError> {
+        let instance = self.instance_or_main(id)?;
+        instance.set_launch_config(model, options);
+        if instance.id() == MAIN_INSTANCE {
+            // The main instance follows the UI selection, saved by the frontend
+            return Ok(());
+        }
+        self.save_instances(app)
+    }
+
+    fn save_instances(&self, app: &AppHandle) -> Result<(), ServerError> {
+        let instances: Vec<InstanceConfig> = self
+            .instances()
+            .iter()
+            .filter(|instance| instance.id() != MAIN_INSTANCE)
+            .map(|instance| {
+                let launch = instance.launch_config();
+                InstanceConfig {
+                    id: instance.id().to_string(),
+                    model: launch.model,
+                    options: launch.options,
+                }
+            })
+            .collect();
+        config::save_key(app, "serverInstances", &instances)
+            .map_err(|e| ServerError::Io(e.to_string()))
+    }
+
+    /// Reject a start that would claim an NPU resource already held by another instance
+    pub fn check_npu(
+        &self,
+        id: &str,
+        model: &str,
+        options: &ServerOptions,
+    ) -> Result<(), ServerError> {
+        let requested = npu::resources(model, options);
+        let others: Vec<ServerState> = self
+            .states()
+            .into_iter()
+            .filter(|state| state.id != id)
+            .collect();
+
+        match npu::find_conflict(&requested, &others) {
+            Some((instance, resource)) => Err(ServerError::NpuConflict { instance, resource }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn restart_policy(&self) -> RestartPolicy {
+        self.settings().policy.clone()
+    }
+
+    /// Change the restart policy and persist it in the app config
+    pub fn set_restart_policy(
+        &self,
+        app: &AppHandle,
+        policy: RestartPolicy,
+    ) -> Result<(), ServerError> {
+        self.settings().policy = policy.clone();
+        for instance in self.list() {
+            instance.reset_restarts();
+        }
+        refresh_tray_menu(app);
+        config::save_key(app, "restartPolicy", &policy).map_err(|e| ServerError::Io(e.to_string()))
+    }
+
+    pub fn port_selection(&self) -> PortSelection {
+        self.settings().port_selection.clone()
+    }
+
+    /// Change the port conflict handling and persist it in the app config
+    pub fn set_port_selection(
+        &self,
+        app: &AppHandle,
+        selection: PortSelection,
+    ) -> Result<(), ServerError> {
+        self.settings().port_selection = selection.clone();
+        refresh_tray_menu(app);
+        config::save_key(app, "portSelection", &selection)
+            .map_err(|e| ServerError::Io(e.to_string()))
+    }
+
+    pub fn idle_policy(&self) -> IdlePolicy {
+        self.settings().idle.clone()
+    }
+
+    /// Change the idle stop and persist it in the app config
+    pub fn set_idle_policy(&self, app: &AppHandle, policy: IdlePolicy) -> Result<(), ServerError> {
+        self.settings().idle = policy.clone();
+        refresh_tray_menu(app);
+        config::save_key(app, "idleStop", &policy).map_err(|e| ServerError::Io(e.to_string()))
+    }
+
+    /// A server found at startup is offered to the main instance
+    pub fn set_orphan(&self, app: &AppHandle, orphan: Option<OrphanServer>) {
+        self.main().set_orphan(app, orphan);
+    }
+
+    pub async fn resolve_orphan(
+        &self,
+        app: &AppHandle,
+        action: OrphanAction,
+    ) -> Result<ServerState, ServerError> {
+        self.main().resolve_orphan(app, action).await
+    }
+
+    /// Whether any instance has a live process
+    pub fn has_process(&self) -> bool {
+        self.list().iter().any(|instance| instance.has_process())
+    }
+
+    /// Stop every instance, ignoring those that are not running
+    pub async fn stop_all(&self, app: &AppHandle) {
+        for instance in self.list() {
+            if instance.has_process() {
+                let _ = instance.stop_for(app, StopCause::Shutdown).await;
+            }
+        }
+    }
+
+    /// Let go of every process without stopping them
+    pub fn detach(&self) {
+        for instance in self.list() {
+            instance.detach();
+        }
+    }
+
+    /// Blocking variant of `stop_all` for the final exit
+    pub fn shutdown_blocking(&self) {
+        for instance in self.list() {
+            instance.shutdown_blocking();
+        }
+    }
+
+    fn instances(&self) -> MutexGuard<'_, Vec<Arc<ServerInstance>>> {
+        self.instances
+            .lock()
+            .expect("server registry lock poisoned")
+    }
+
+    fn settings(&self) -> MutexGuard<'_, Settings> {
+        self.settings.lock().expect("server settings lock poisoned")
+    }
+}
+
+/// Instance ids are used in tray menu ids, keep them simple
+fn validate_id(id: &str) -> Result<(), ServerError> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ServerError::InvalidInstance(format!(
+            "'{}' must only contain letters, digits, '-' and '_'",
+            id
+        )))
+    }
+}
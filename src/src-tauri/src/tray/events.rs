@@ -1,22 +1,43 @@
 use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::catalog::memory::{estimate, SystemMemory};
+use crate::catalog::repair::{repair_models, verify_model};
+use crate::catalog::verify::summary;
+use crate::catalog::{load, locate};
+use crate::config;
+use crate::download::DownloadManager;
+use crate::server::{emit_log, shutdown, LogStore, Scheduler, ServerSupervisor, MAIN_INSTANCE};
+use crate::tray::removal::{confirm, request_removal, undo_removal};
+use crate::tray::TrayState;
+use crate::types::{
+    MemoryVerdict, MemoryWarning, OrphanAction, RestartMode, RestartPolicy, ServerOptions,
+    TrayMenuTexts, ViewLogsRequest,
+};
 
 pub fn handle_menu_event(app: &AppHandle, event_id: &str) {
     match event_id {
-        "quit" => handle_quit(app),
+        "quit" => shutdown::quit(app, false),
+        "quit_keep_server" => shutdown::quit(app, true),
         "settings" => handle_show_window(app),
-        "view_logs" => handle_view_logs(app),
-        "start_server" => {
-            let _ = app.emit("request-start-server", ());
-        }
-        "stop_server" => {
-            let _ = app.emit("request-stop-server", ());
-        }
-        "toggle_asr" => {
-            let _ = app.emit("toggle-asr", ());
-        }
-        "toggle_embed" => {
-            let _ = app.emit("toggle-embed", ());
-        }
+        "view_logs" => handle_view_logs(app, MAIN_INSTANCE),
+        "start_server" => handle_start_server(app, MAIN_INSTANCE, None),
+        "stop_server" => handle_stop_server(app, MAIN_INSTANCE),
+        "restart_policy_never" => handle_restart_mode(app, RestartMode::Never),
+        "restart_policy_on_failure" => handle_restart_mode(app, RestartMode::OnFailure),
+        "restart_policy_always" => handle_restart_mode(app, RestartMode::Always),
+        "toggle_auto_port" => handle_toggle_auto_port(app),
+        "toggle_schedule" => handle_toggle_schedule(app),
+        "repair_models" => handle_repair_models(app),
+        "orphan_adopt" => handle_resolve_orphan(app, OrphanAction::Adopt),
+        "orphan_terminate" => handle_terminate_orphan(app),
+        "orphan_change_port" => handle_resolve_orphan(app, OrphanAction::ChangePort),
+        "toggle_asr" => handle_toggle_feature(app, "toggle-asr", |options| {
+            options.asr = Some(!options.asr.unwrap_or(false));
+        }),
+        "toggle_embed" => handle_toggle_feature(app, "toggle-embed", |options| {
+            options.embed = Some(!options.embed.unwrap_or(false));
+        }),
         id if id.starts_with("model_") => {
             if let Some(model_name) = id.strip_prefix("model_") {
                 let _ = app.emit("select-model", model_name);
@@ -25,28 +46,267 @@ pub fn handle_menu_event(app: &AppHandle, event_id: &str) {
         id if id.starts_with("start_model_") => {
             if let Some(model_name) = id.strip_prefix("start_model_") {
                 let _ = app.emit("select-model", model_name);
-                let _ = app.emit("request-start-server", ());
+                handle_start_server(app, MAIN_INSTANCE, Some(model_name.to_string()));
+            }
+        }
+        id if id.starts_with("instance_start_") => {
+            if let Some(instance) = id.strip_prefix("instance_start_") {
+                handle_start_server(app, instance, None);
+            }
+        }
+        id if id.starts_with("instance_stop_") => {
+            if let Some(instance) = id.strip_prefix("instance_stop_") {
+                handle_stop_server(app, instance);
+            }
+        }
+        id if id.starts_with("instance_logs_") => {
+            if let Some(instance) = id.strip_prefix("instance_logs_") {
+                handle_view_logs(app, instance);
             }
         }
         id if id.starts_with("delete_model_") => {
             if let Some(model_name) = id.strip_prefix("delete_model_") {
-                let _ = app.emit("request-delete-model", model_name);
+                request_removal(app, model_name);
+            }
+        }
+        id if id.starts_with("undo_remove_") => {
+            if let Some(model_name) = id.strip_prefix("undo_remove_") {
+                undo_removal(app, model_name);
+            }
+        }
+        id if id.starts_with("verify_model_") => {
+            if let Some(model_name) = id.strip_prefix("verify_model_") {
+                handle_verify_model(app, model_name);
+            }
+        }
+        id if id.starts_with("cancel_download_") => {
+            if let Some(model_name) = id.strip_prefix("cancel_download_") {
+                if let Err(e) = app.state::<DownloadManager>().cancel(app, model_name) {
+                    notify_error(app, e);
+                }
             }
         }
         id if id.starts_with("download_model_") => {
             if let Some(model_name) = id.strip_prefix("download_model_") {
-                let _ = app.emit("request-download-model", model_name);
+                if let Err(e) = app.state::<DownloadManager>().enqueue(app, model_name) {
+                    notify_error(app, e);
+                }
             }
         }
         _ => {}
     }
 }
 
-fn handle_quit(app: &AppHandle) {
-    if let Some(window) = app.get_webview_window("main") {
-        let _ = window.destroy();
+/// Start an instance with its launch config, optionally overriding the model
+fn handle_start_server(app: &AppHandle, instance: &str, model: Option<String>) {
+    let instance = match app.state::<ServerSupervisor>().instance(instance) {
+        Ok(instance) => instance,
+        Err(e) => {
+            emit_log(app, instance, format!("[ERROR] {}", e));
+            return;
+        }
+    };
+    let mut launch = instance.launch_config();
+    if let Some(model) = model {
+        // Same reset as a model selection in the UI: default context, no side features
+        launch.model = model;
+        launch.options.ctx_len = None;
+        launch.options.asr = Some(false);
+        launch.options.embed = Some(false);
+    }
+
+    // Off the menu thread: the catalog is read from disk
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        // The window asks before starting a configuration that won't fit in the shared memory
+        let estimate = locate(&config::load(&app).flm_path)
+            .and_then(|path| load(&path))
+            .and_then(|catalog| {
+                estimate(
+                    &catalog,
+                    &launch.model,
+                    &launch.options,
+                    SystemMemory::current(),
+                )
+            });
+        if let Ok(estimate) = estimate {
+            if estimate.verdict == MemoryVerdict::WontFit {
+                handle_show_window(&app);
+                let _ = app.emit(
+                    "confirm-start",
+                    MemoryWarning {
+                        instance: instance.id().to_string(),
+                        model: launch.model,
+                        options: launch.options,
+                        estimate,
+                    },
+                );
+                return;
+            }
+        }
+
+        if let Err(e) = instance.start(&app, launch.model, launch.options) {
+            emit_log(
+                &app,
+                instance.id(),
+                format!("[ERROR] Failed to start server: {}", e),
+            );
+        }
+    });
+}
+
+/// Toggle a feature of the main instance, the UI is notified to keep its options in sync
+fn handle_toggle_feature(app: &AppHandle, event: &str, toggle: impl FnOnce(&mut ServerOptions)) {
+    let instance = app.state::<ServerSupervisor>().main();
+    let mut launch = instance.launch_config();
+    toggle(&mut launch.options);
+    instance.reconfigure(app, launch.model, launch.options);
+    let _ = app.emit(event, ());
+}
+
+/// Verify a model in the background, the result comes as a notification
+fn handle_verify_model(app: &AppHandle, model: &str) {
+    let app = app.clone();
+    let model = model.to_string();
+    tauri::async_runtime::spawn(async move {
+        match verify_model(&app, &model).await {
+            Ok(report) => {
+                let title = notification_title(&app, |texts| {
+                    if report.healthy {
+                        texts.verify_passed
+                    } else {
+                        texts.verify_failed
+                    }
+                });
+                notify(&app, title, summary(&report));
+            }
+            Err(e) => notify_error(&app, e),
+        }
+    });
+}
+
+fn handle_repair_models(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match repair_models(&app, Vec::new()).await {
+            Ok(reports) => {
+                let damaged: Vec<&str> = reports
+                    .iter()
+                    .filter(|report| !report.healthy)
+                    .map(|report| report.model.as_str())
+                    .collect();
+                if damaged.is_empty() {
+                    let title = notification_title(&app, |texts| texts.verify_passed);
+                    notify(&app, title, format!("{} models checked", reports.len()));
+                } else {
+                    let title = notification_title(&app, |texts| texts.repairing);
+                    notify(&app, title, damaged.join(", "));
+                }
+            }
+            Err(e) => notify_error(&app, e),
+        }
+    });
+}
+
+/// Localized title, a generic one until the frontend has sent its texts
+pub(super) fn notification_title(
+    app: &AppHandle,
+    pick: impl FnOnce(TrayMenuTexts) -> String,
+) -> String {
+    app.state::<TrayState>()
+        .params()
+        .map(|params| pick(params.texts))
+        .unwrap_or_else(|| String::from("FLM models"))
+}
+
+pub(super) fn notify(app: &AppHandle, title: String, body: String) {
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+/// Model actions have nothing to do with the server, their errors are not written to its log
+fn notify_error(app: &AppHandle, error: impl std::fmt::Display) {
+    let title = notification_title(app, |texts| texts.action_failed);
+    notify(app, title, error.to_string());
+}
+
+fn handle_toggle_schedule(app: &AppHandle) {
+    let scheduler = app.state::<Scheduler>();
+    let mut schedule = scheduler.state().schedule();
+    schedule.enabled = !schedule.enabled;
+    if let Err(e) = scheduler.set_schedule(app, schedule) {
+        emit_log(app, MAIN_INSTANCE, format!("[ERROR] {}", e));
+    }
+}
+
+fn handle_toggle_auto_port(app: &AppHandle) {
+    let supervisor = app.state::<ServerSupervisor>();
+    let mut selection = supervisor.port_selection();
+    selection.auto_select = !selection.auto_select;
+    if let Err(e) = supervisor.set_port_selection(app, selection) {
+        emit_log(
+            app,
+            MAIN_INSTANCE,
+            format!("[ERROR] Failed to save port selection: {}", e),
+        );
+    }
+}
+
+/// Killing the process disconnects its clients, ask first
+fn handle_terminate_orphan(app: &AppHandle) {
+    let message = app
+        .state::<TrayState>()
+        .params()
+        .map(|params| params.texts.confirm_terminate)
+        .unwrap_or_default();
+    confirm(
+        app,
+        |texts| texts.terminate_server.clone(),
+        message,
+        |app| handle_resolve_orphan(app, OrphanAction::Terminate),
+    );
+}
+
+fn handle_resolve_orphan(app: &AppHandle, action: OrphanAction) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let supervisor = app.state::<ServerSupervisor>();
+        if let Err(e) = supervisor.resolve_orphan(&app, action).await {
+            emit_log(&app, MAIN_INSTANCE, format!("[ERROR] {}", e));
+        }
+    });
+}
+
+fn handle_stop_server(app: &AppHandle, instance: &str) {
+    let app = app.clone();
+    let instance = instance.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = match app.state::<ServerSupervisor>().instance(&instance) {
+            Ok(server) => server.stop(&app).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            emit_log(
+                &app,
+                &instance,
+                format!("[ERROR] Failed to stop server: {}", e),
+            );
+        }
+    });
+}
+
+fn handle_restart_mode(app: &AppHandle, mode: RestartMode) {
+    let supervisor = app.state::<ServerSupervisor>();
+    let policy = RestartPolicy {
+        mode,
+        ..supervisor.restart_policy()
+    };
+    if let Err(e) = supervisor.set_restart_policy(app, policy) {
+        emit_log(
+            app,
+            MAIN_INSTANCE,
+            format!("[ERROR] Failed to save restart policy: {}", e),
+        );
     }
-    app.exit(0);
 }
 
 fn handle_show_window(app: &AppHandle) {
@@ -57,7 +317,17 @@ fn handle_show_window(app: &AppHandle) {
     }
 }
 
-fn handle_view_logs(app: &AppHandle) {
+fn handle_view_logs(app: &AppHandle, instance: &str) {
     handle_show_window(app);
-    let _ = app.emit("view-logs", ());
+    let session = app
+        .state::<LogStore>()
+        .latest_session(instance)
+        .map(|session| session.id);
+    let _ = app.emit(
+        "view-logs",
+        ViewLogsRequest {
+            instance: instance.to_string(),
+            session,
+        },
+    );
 }
@@ -1,12 +1,18 @@
 use tauri::image::Image;
 
+use crate::types::{ServerHealth, ServerState, ServerStatus};
+
 pub struct ThemeIcons {
     pub tray: Image<'static>,
+    /// Tray icon shown while models are downloading
+    pub tray_download: Image<'static>,
     pub play: Image<'static>,
     pub stop: Image<'static>,
     pub power: Image<'static>,
     pub red: Image<'static>,
     pub green: Image<'static>,
+    pub yellow: Image<'static>,
+    pub orange: Image<'static>,
     pub cog: Image<'static>,
     pub cpu: Image<'static>,
     pub file_clock: Image<'static>,
@@ -28,6 +34,8 @@ impl ThemeIcons {
         Self {
             tray: Image::from_bytes(include_bytes!("../../icons/dark/tray.png"))
                 .expect("failed to load dark/tray.png"),
+            tray_download: Image::from_bytes(include_bytes!("../../icons/dark/tray-download.png"))
+                .expect("failed to load dark/tray-download.png"),
             play: Image::from_bytes(include_bytes!("../../icons/dark/play.png"))
                 .expect("failed to load dark/play.png"),
             stop: Image::from_bytes(include_bytes!("../../icons/dark/square.png"))
@@ -38,6 +46,10 @@ impl ThemeIcons {
                 .expect("failed to load dark/red.png"),
             green: Image::from_bytes(include_bytes!("../../icons/dark/green.png"))
                 .expect("failed to load dark/green.png"),
+            yellow: Image::from_bytes(include_bytes!("../../icons/dark/yellow.png"))
+                .expect("failed to load dark/yellow.png"),
+            orange: Image::from_bytes(include_bytes!("../../icons/dark/orange.png"))
+                .expect("failed to load dark/orange.png"),
             cog: Image::from_bytes(include_bytes!("../../icons/dark/cog.png"))
                 .expect("failed to load dark/cog.png"),
             cpu: Image::from_bytes(include_bytes!("../../icons/dark/cpu.png"))
@@ -57,6 +69,8 @@ impl ThemeIcons {
         Self {
             tray: Image::from_bytes(include_bytes!("../../icons/light/tray.png"))
                 .expect("failed to load light/tray.png"),
+            tray_download: Image::from_bytes(include_bytes!("../../icons/light/tray-download.png"))
+                .expect("failed to load light/tray-download.png"),
             play: Image::from_bytes(include_bytes!("../../icons/light/play.png"))
                 .expect("failed to load light/play.png"),
             stop: Image::from_bytes(include_bytes!("../../icons/light/square.png"))
@@ -67,6 +81,10 @@ impl ThemeIcons {
                 .expect("failed to load light/red.png"),
             green: Image::from_bytes(include_bytes!("../../icons/light/green.png"))
                 .expect("failed to load light/green.png"),
+            yellow: Image::from_bytes(include_bytes!("../../icons/light/yellow.png"))
+                .expect("failed to load light/yellow.png"),
+            orange: Image::from_bytes(include_bytes!("../../icons/light/orange.png"))
+                .expect("failed to load light/orange.png"),
             cog: Image::from_bytes(include_bytes!("../../icons/light/cog.png"))
                 .expect("failed to load light/cog.png"),
             cpu: Image::from_bytes(include_bytes!("../../icons/light/cpu.png"))
@@ -82,11 +100,15 @@ impl ThemeIcons {
         }
     }
 
-    pub fn status_icon(&self, is_running: bool) -> Image<'static> {
-        if is_running {
-            self.green.clone()
-        } else {
-            self.red.clone()
+    pub fn status_icon(&self, server: &ServerState) -> Image<'static> {
+        match (server.status, server.health) {
+            (ServerStatus::Stopped | ServerStatus::Failed, _) => self.red.clone(),
+            (ServerStatus::Starting | ServerStatus::Stopping | ServerStatus::Restarting, _) => {
+                self.yellow.clone()
+            }
+            (ServerStatus::Running, Some(ServerHealth::Degraded)) => self.orange.clone(),
+            (ServerStatus::Running, Some(ServerHealth::Unreachable)) => self.red.clone(),
+            (ServerStatus::Running, _) => self.green.clone(),
         }
     }
 }
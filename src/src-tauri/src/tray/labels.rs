@@ -0,0 +1,380 @@
+use crate::types::{
+    Diagnosis, DownloadJob, DownloadStatus, FailureReason, ServerHealth, ServerState, ServerStatus,
+    TrayMenuTexts,
+};
+
+pub fn format_speed(bytes_per_sec: u64) -> String {
+    const MB: f64 = 1e6;
+    if bytes_per_sec as f64 >= MB {
+        format!("{:.1} MB/s", bytes_per_sec as f64 / MB)
+    } else {
+        format!("{} KB/s", bytes_per_sec / 1000)
+    }
+}
+
+/// `model — 45% • 12.3 MB/s`, or the reason it is waiting
+pub fn download_label(texts: &TrayMenuTexts, job: &DownloadJob) -> String {
+    let progress = match job.status {
+        DownloadStatus::Queued => texts.download_queued.clone(),
+        DownloadStatus::Retrying => texts.download_retrying.clone(),
+        _ => {
+            let percent = job
+                .percent
+                .map_or_else(|| String::from("…"), |p| format!("{:.0}%", p));
+            match job.bytes_per_sec {
+                Some(speed) => format!("{} • {}", percent, format_speed(speed)),
+                None => percent,
+            }
+        }
+    };
+    format!("{} — {}", job.model, progress)
+}
+
+/// Mean progress of the pending downloads, the queued ones counting as 0%
+pub fn aggregate_percent(downloads: &[DownloadJob]) -> f32 {
+    let total: f32 = downloads.iter().map(|job| job.percent.unwrap_or(0.0)).sum();
+    total / downloads.len().max(1) as f32
+}
+
+/// `<1m`, `14m`, `2h14m` or `3d5h`, in minutes as the tooltip is refreshed every 30 s
+pub fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        String::from("<1m")
+    }
+}
+
+/// Status word of the tooltip, health issues are left to the menu
+fn tooltip_status(texts: &TrayMenuTexts, server: &ServerState) -> String {
+    let texts = &texts.tooltip;
+    match server.status {
+        ServerStatus::Stopped if server.idle => texts.idle.clone(),
+        ServerStatus::Stopped => texts.stopped.clone(),
+        ServerStatus::Starting => match server.load_percent {
+            Some(percent) => format!("{} {}%", texts.starting, percent),
+            None => texts.starting.clone(),
+        },
+        ServerStatus::Stopping => texts.stopping.clone(),
+        ServerStatus::Restarting => texts.restarting.clone(),
+        ServerStatus::Failed => texts.failed.clone(),
+        ServerStatus::Running => texts.running.clone(),
+    }
+}
+
+/// Tooltip of the tray icon, e.g. `FLM: running • qwen3:8b • 127.0.0.1:52625 • up 2h14m`,
+/// followed by the download progress and the cause of the last failure
+pub fn tray_tooltip(
+    texts: &TrayMenuTexts,
+    server: &ServerState,
+    last_failure: Option<Diagnosis>,
+    downloads: &[DownloadJob],
+    now_millis: u64,
+) -> String {
+    let mut parts = vec![tooltip_status(texts, server)];
+    if matches!(
+        server.status,
+        ServerStatus::Starting | ServerStatus::Running
+    ) {
+        if !server.model.is_empty() {
+            parts.push(server.model.clone());
+        }
+        parts.push(endpoint_text(server));
+    }
+    if let (ServerStatus::Running, Some(started_at)) = (server.status, server.started_at) {
+        let uptime = format_uptime(now_millis.saturating_sub(started_at) / 1000);
+        parts.push(format!("{} {}", texts.uptime, uptime));
+    }
+    if !downloads.is_empty() {
+        parts.push(format!(
+            "{} {:.0}%",
+            texts.downloading.to_lowercase(),
+            aggregate_percent(downloads)
+        ));
+    }
+    // Same condition as the last failure item of the menu
+    let failed = matches!(
+        server.status,
+        ServerStatus::Stopped | ServerStatus::Restarting | ServerStatus::Failed
+    );
+    if let Some(diagnosis) = last_failure.filter(|_| failed) {
+        parts.push(format!(
+            "{}: {}",
+            texts.error,
+            texts.diagnoses.get(diagnosis)
+        ));
+    }
+    format!("FLM: {}", parts.join(" • "))
+}
+
+pub fn status_text(texts: &TrayMenuTexts, server: &ServerState) -> String {
+    match (server.status, server.health) {
+        (ServerStatus::Stopped, _) if server.idle => texts.stopped_idle.clone(),
+        (ServerStatus::Stopped, _) => texts.stopped.clone(),
+        (ServerStatus::Starting, _) => match server.load_percent {
+            Some(percent) => format!("{} {}%", texts.starting, percent),
+            None => texts.starting.clone(),
+        },
+        (ServerStatus::Stopping, _) => texts.stopping.clone(),
+        (ServerStatus::Restarting, _) => match server.restart {
+            Some(restart) => format!(
+                "{} {}/{}",
+                texts.restarting, restart.attempt, restart.max_retries
+            ),
+            None => texts.restarting.clone(),
+        },
+        (ServerStatus::Failed, _) => match server.failure {
+            Some(FailureReason::CrashLoop) => texts.crash_loop.clone(),
+            Some(FailureReason::LaunchFailed) => texts.launch_failed.clone(),
+            _ => texts.failed.clone(),
+        },
+        (ServerStatus::Running, Some(ServerHealth::Degraded)) => texts.degraded.clone(),
+        (ServerStatus::Running, Some(ServerHealth::Unreachable)) => texts.unreachable.clone(),
+        (ServerStatus::Running, _) if server.adopted => {
+            format!("{} ({})", texts.running, texts.adopted)
+        }
+        (ServerStatus::Running, _) => texts.running.clone(),
+    }
+}
+
+/// Endpoint of a live instance, the port may differ from the configured one
+pub fn endpoint_text(server: &ServerState) -> String {
+    if server.status == ServerStatus::Stopped {
+        String::from("—")
+    } else {
+        format!("{}:{}", server.options.host(), server.options.port())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    use crate::types::{DiagnosisTexts, TooltipTexts};
+
+    const NOW: u64 = 1_700_000_000_000;
+    const TRANSLATION: &str = include_str!("../../../src/locales/en/translation.json");
+
+    /// Texts sent by `useTrayMenu.ts`, from the English translation
+    fn texts() -> TrayMenuTexts {
+        let translation: Value = serde_json::from_str(TRANSLATION).unwrap();
+        let text = |key: &str| {
+            let pointer = format!("/{}", key.replace('.', "/"));
+            translation
+                .pointer(&pointer)
+                .and_then(Value::as_str)
+                .unwrap()
+                .to_string()
+        };
+        TrayMenuTexts {
+            downloading: text("tray.downloading"),
+            download_queued: text("tray.download_queued"),
+            download_retrying: text("tray.download_retrying"),
+            uptime: text("tray.uptime"),
+            error: text("tray.error"),
+            tooltip: TooltipTexts {
+                running: text("tray.tooltip_running"),
+                starting: text("tray.tooltip_starting"),
+                stopping: text("tray.tooltip_stopping"),
+                stopped: text("tray.tooltip_stopped"),
+                idle: text("tray.tooltip_idle"),
+                restarting: text("tray.tooltip_restarting"),
+                failed: text("tray.tooltip_failed"),
+            },
+            diagnoses: DiagnosisTexts {
+                port_in_use: text("diagnosis.port_in_use"),
+                ..DiagnosisTexts::default()
+            },
+            ..TrayMenuTexts::default()
+        }
+    }
+
+    fn server(status: ServerStatus, model: &str) -> ServerState {
+        ServerState {
+            status,
+            model: model.to_string(),
+            ..ServerState::default()
+        }
+    }
+
+    fn job(model: &str, status: DownloadStatus, percent: Option<f32>) -> DownloadJob {
+        DownloadJob {
+            model: model.to_string(),
+            status,
+            percent,
+            bytes_per_sec: None,
+            current_file: None,
+            total_files: None,
+            attempts: 0,
+            force: false,
+            error: None,
+            queued_at: NOW,
+            started_at: None,
+            finished_at: None,
+            retry_at: None,
+        }
+    }
+
+    #[test]
+    fn labels_each_download() {
+        let texts = texts();
+        let downloading = |percent, bytes_per_sec| DownloadJob {
+            bytes_per_sec,
+            ..job("qwen3:8b", DownloadStatus::Downloading, percent)
+        };
+        for (job, label) in [
+            (
+                job("qwen3:8b", DownloadStatus::Queued, None),
+                "qwen3:8b — queued",
+            ),
+            (
+                job("qwen3:8b", DownloadStatus::Retrying, Some(40.0)),
+                "qwen3:8b — retrying",
+            ),
+            (downloading(None, None), "qwen3:8b — …"),
+            (downloading(Some(44.6), None), "qwen3:8b — 45%"),
+            (
+                downloading(Some(45.0), Some(12_345_678)),
+                "qwen3:8b — 45% • 12.3 MB/s",
+            ),
+            (
+                downloading(Some(45.0), Some(850_000)),
+                "qwen3:8b — 45% • 850 KB/s",
+            ),
+        ] {
+            assert_eq!(download_label(&texts, &job), label);
+        }
+    }
+
+    #[test]
+    fn averages_the_download_progress() {
+        assert_eq!(aggregate_percent(&[]), 0.0);
+        let downloads = [
+            job("qwen3:8b", DownloadStatus::Downloading, Some(80.0)),
+            job("gemma3:4b", DownloadStatus::Retrying, Some(40.0)),
+            // Not started yet, counts as 0%
+            job("llama3.2:1b", DownloadStatus::Queued, None),
+        ];
+        assert_eq!(aggregate_percent(&downloads), 40.0);
+    }
+
+    #[test]
+    fn formats_uptime_in_minutes() {
+        for (secs, uptime) in [
+            (0, "<1m"),
+            (59, "<1m"),
+            (60, "1m"),
+            (3599, "59m"),
+            (3600, "1h00m"),
+            (8040, "2h14m"),
+            (86_399, "23h59m"),
+            (86_400, "1d0h"),
+            (277_200, "3d5h"),
+        ] {
+            assert_eq!(format_uptime(secs), uptime, "{} s", secs);
+        }
+    }
+
+    /// Server, last failure and downloads, with the expected tooltip
+    fn samples() -> Vec<(
+        ServerState,
+        Option<Diagnosis>,
+        Vec<DownloadJob>,
+        &'static str,
+    )> {
+        let running = ServerState {
+            started_at: Some(NOW - 8_040_000),
+            ..server(ServerStatus::Running, "qwen3:8b")
+        };
+        vec![
+            (
+                running.clone(),
+                None,
+                Vec::new(),
+                "FLM: running • qwen3:8b • 127.0.0.1:52625 • up 2h14m",
+            ),
+            (
+                running.clone(),
+                Some(Diagnosis::PortInUse),
+                vec![
+                    job("gemma3:4b", DownloadStatus::Downloading, Some(50.0)),
+                    job("llama3.2:1b", DownloadStatus::Queued, None),
+                ],
+                "FLM: running • qwen3:8b • 127.0.0.1:52625 • up 2h14m • downloading 25%",
+            ),
+            (
+                ServerState {
+                    load_percent: Some(45),
+                    started_at: Some(NOW),
+                    ..server(ServerStatus::Starting, "qwen3:8b")
+                },
+                None,
+                Vec::new(),
+                "FLM: starting 45% • qwen3:8b • 127.0.0.1:52625",
+            ),
+            (
+                ServerState {
+                    started_at: None,
+                    ..server(ServerStatus::Running, "")
+                },
+                None,
+                Vec::new(),
+                "FLM: running • 127.0.0.1:52625",
+            ),
+            (
+                server(ServerStatus::Stopped, "qwen3:8b"),
+                None,
+                Vec::new(),
+                "FLM: stopped",
+            ),
+            (
+                ServerState {
+                    idle: true,
+                    ..server(ServerStatus::Stopped, "qwen3:8b")
+                },
+                None,
+                Vec::new(),
+                "FLM: stopped (idle)",
+            ),
+            (
+                ServerState {
+                    adopted: true,
+                    health: Some(ServerHealth::Degraded),
+                    ..server(ServerStatus::Running, "qwen3:8b")
+                },
+                None,
+                Vec::new(),
+                "FLM: running • qwen3:8b • 127.0.0.1:52625",
+            ),
+            (
+                server(ServerStatus::Stopped, "qwen3:8b"),
+                Some(Diagnosis::PortInUse),
+                Vec::new(),
+                "FLM: stopped • error: port in use",
+            ),
+            (
+                server(ServerStatus::Failed, "qwen3:8b"),
+                Some(Diagnosis::PortInUse),
+                vec![job("gemma3:4b", DownloadStatus::Downloading, Some(12.4))],
+                "FLM: failed • downloading 12% • error: port in use",
+            ),
+        ]
+    }
+
+    #[test]
+    fn describes_the_server_in_the_tooltip() {
+        let texts = texts();
+        for (server, last_failure, downloads, tooltip) in samples() {
+            assert_eq!(
+                tray_tooltip(&texts, &server, last_failure, &downloads, NOW),
+                tooltip
+            );
+        }
+    }
+}
@@ -1,71 +1,286 @@
+use std::collections::BTreeMap;
+
+use chrono::Datelike;
 use tauri::menu::{CheckMenuItem, IconMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 
+use crate::catalog::memory::{self, SystemMemory};
+use crate::catalog::model_list;
+use crate::download::error::format_size;
 use crate::tray::icons::ThemeIcons;
-use crate::types::TrayMenuParams;
+use crate::tray::labels::{download_label, endpoint_text, status_text};
+use crate::tray::TraySnapshot;
+use crate::types::{
+    DownloadJob, InventoryEntry, MemoryVerdict, OrphanServer, RestartMode, RestartPolicy,
+    ScheduledTransition, ServerState, ServerStatus, TransitionKind, TrayMenuParams, TrayMenuTexts,
+};
+
+/// Labels of the existing server submenu, localized once the frontend has sent its texts
+struct OrphanLabels<'a> {
+    orphan_found: &'a str,
+    port_in_use: &'a str,
+    adopt: &'a str,
+    terminate: &'a str,
+    use_other_port: &'a str,
+}
 
-fn build_models_menu(
-    app: &AppHandle,
-    params: &TrayMenuParams,
+impl<'a> OrphanLabels<'a> {
+    const DEFAULT: OrphanLabels<'static> = OrphanLabels {
+        orphan_found: "Server already running",
+        port_in_use: "Port in use",
+        adopt: "Adopt",
+        terminate: "Terminate",
+        use_other_port: "Use another port",
+    };
+
+    fn from_texts(texts: &'a TrayMenuTexts) -> Self {
+        Self {
+            orphan_found: &texts.orphan_found,
+            port_in_use: &texts.port_in_use,
+            adopt: &texts.adopt_server,
+            terminate: &texts.terminate_server,
+            use_other_port: &texts.use_other_port,
+        }
+    }
+}
+
+/// Choices for a server found at startup: adopt it, terminate it or move to another port
+fn build_orphan_menu<M: Manager<tauri::Wry>>(
+    app: &M,
+    orphan: &OrphanServer,
+    labels: &OrphanLabels,
     icons: &ThemeIcons,
 ) -> tauri::Result<Submenu<tauri::Wry>> {
-    let texts = &params.texts;
+    let title = if orphan.is_flm {
+        format!("{} (:{})", labels.orphan_found, orphan.port)
+    } else {
+        format!("{} (:{})", labels.port_in_use, orphan.port)
+    };
+    let owner = match (&orphan.process_name, orphan.pid) {
+        (Some(name), Some(pid)) => format!("{} — PID {}", name, pid),
+        (None, Some(pid)) => format!("PID {}", pid),
+        _ => String::from("—"),
+    };
+    let owner_text = match &orphan.model {
+        Some(model) => format!("{} • {}", owner, model),
+        None => owner,
+    };
 
-    // Sous-menu Installed
-    let installed_submenu = Submenu::new(app, &texts.installed, true)?;
-    let _ = installed_submenu.set_icon(Some(icons.hard_drive.clone()));
+    let owner_i = MenuItem::with_id(app, "orphan_info", &owner_text, false, None::<&str>)?;
+    let adopt_i = MenuItem::with_id(
+        app,
+        "orphan_adopt",
+        labels.adopt,
+        orphan.is_manageable(),
+        None::<&str>,
+    )?;
+    let terminate_i = IconMenuItem::with_id(
+        app,
+        "orphan_terminate",
+        labels.terminate,
+        orphan.is_manageable(),
+        Some(icons.stop.clone()),
+        None::<&str>,
+    )?;
+    let change_port_i = MenuItem::with_id(
+        app,
+        "orphan_change_port",
+        labels.use_other_port,
+        true,
+        None::<&str>,
+    )?;
+    let separator = PredefinedMenuItem::separator(app)?;
 
-    for model_name in &params.installed_models {
-        // Sous-menu pour chaque modèle installé
-        let model_submenu = Submenu::new(app, model_name, true)?;
+    let submenu = Submenu::with_items(
+        app,
+        &title,
+        true,
+        &[&owner_i, &separator, &adopt_i, &terminate_i, &change_port_i],
+    )?;
+    let _ = submenu.set_icon(Some(icons.orange.clone()));
 
-        if params.startable_models.contains(model_name) {
-            // Action: Start Server
-            let start_item = IconMenuItem::with_id(
-                app,
-                format!("start_model_{}", model_name),
-                &texts.start_with_model,
-                true,
-                Some(icons.play.clone()),
-                None::<&str>,
-            )?;
-            let _ = model_submenu.append(&start_item);
+    Ok(submenu)
+}
+
+/// Family submenu of a model
+fn family_group(entry: &InventoryEntry) -> String {
+    let family = match &entry.info {
+        Some(info) => info.family.as_str(),
+        None => entry.name.split(':').next().unwrap_or_default(),
+    };
+    model_list::family_group(family)
+}
+
+fn group_by_family<'a>(
+    entries: impl IntoIterator<Item = &'a InventoryEntry>,
+) -> BTreeMap<String, Vec<&'a InventoryEntry>> {
+    let mut groups: BTreeMap<String, Vec<&InventoryEntry>> = BTreeMap::new();
+    for entry in entries {
+        groups.entry(family_group(entry)).or_default().push(entry);
+    }
+    groups
+}
+
+fn wont_fit(entry: &InventoryEntry, memory: SystemMemory) -> bool {
+    entry
+        .info
+        .as_ref()
+        .is_some_and(|info| memory::verdict(info, memory) == MemoryVerdict::WontFit)
+}
+
+/// `qwen3:8b (5.2 GB) · Think`, flagged when the model won't fit in memory and
+/// marked when `flm list` and the disk disagree about it
+fn model_label(texts: &TrayMenuTexts, entry: &InventoryEntry, memory: SystemMemory) -> String {
+    let mut label = entry.name.clone();
+    if let Some(info) = &entry.info {
+        if info.size_bytes > 0 {
+            label.push_str(&format!(" ({})", format_size(info.size_bytes)));
         }
+        let capabilities = &info.capabilities;
+        let badges = [
+            (capabilities.think, &texts.badge_think),
+            (capabilities.vision, &texts.badge_vlm),
+            (capabilities.embed, &texts.badge_embed),
+            (capabilities.audio, &texts.badge_asr),
+        ];
+        for (_, badge) in badges.iter().filter(|(enabled, _)| *enabled) {
+            label.push_str(&format!(" · {}", badge));
+        }
+    }
+    if wont_fit(entry, memory) {
+        label.push_str(&format!(" — {}", texts.wont_fit));
+    }
+    if entry.issue.is_some() {
+        label.push_str(" ⚠");
+    }
+    label
+}
+
+fn build_installed_model_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    entry: &InventoryEntry,
+    snapshot: &TraySnapshot,
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let memory = snapshot.memory;
+    let model_name = &entry.name;
+    let model_submenu = Submenu::new(app, model_label(texts, entry, memory), true)?;
 
-        // Action: Delete
-        let delete_item = IconMenuItem::with_id(
+    if entry.is_startable() {
+        // Action: Start Server
+        let start_item = IconMenuItem::with_id(
             app,
-            format!("delete_model_{}", model_name),
-            &texts.delete_model,
+            format!("start_model_{}", model_name),
+            &texts.start_with_model,
             true,
-            Some(icons.trash.clone()),
+            Some(icons.play.clone()),
             None::<&str>,
         )?;
-        let _ = model_submenu.append(&delete_item);
+        let _ = model_submenu.append(&start_item);
+    }
+
+    // Action: Delete, already confirmed while its removal is pending
+    let delete_item = IconMenuItem::with_id(
+        app,
+        format!("delete_model_{}", model_name),
+        &texts.delete_model,
+        !snapshot.pending_removals.contains(model_name),
+        Some(icons.trash.clone()),
+        None::<&str>,
+    )?;
+    let _ = model_submenu.append(&delete_item);
 
-        let _ = installed_submenu.append(&model_submenu);
+    if entry.info.is_some() {
+        // Action: Verify files against the catalog
+        let verify_item = IconMenuItem::with_id(
+            app,
+            format!("verify_model_{}", model_name),
+            &texts.verify_model,
+            true,
+            Some(icons.hard_drive.clone()),
+            None::<&str>,
+        )?;
+        let _ = model_submenu.append(&verify_item);
     }
 
-    // Sous-menu Catalog
-    let catalog_submenu = Submenu::new(app, &texts.catalog, true)?;
-    let _ = catalog_submenu.set_icon(Some(icons.download.clone()));
+    Ok(model_submenu)
+}
+
+fn build_catalog_model_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    entry: &InventoryEntry,
+    memory: SystemMemory,
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    // Action: Download
+    let download_item = IconMenuItem::with_id(
+        app,
+        format!("download_model_{}", entry.name),
+        &texts.download_model,
+        true,
+        Some(icons.download.clone()),
+        None::<&str>,
+    )?;
+    Submenu::with_items(
+        app,
+        model_label(texts, entry, memory),
+        true,
+        &[&download_item],
+    )
+}
 
-    for model_name in &params.available_models {
-        // Sous-menu pour chaque modèle disponible
-        let model_submenu = Submenu::new(app, model_name, true)?;
+fn build_models_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    snapshot: &TraySnapshot,
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let models = &snapshot.models;
+    let memory = snapshot.memory;
 
-        // Action: Download
-        let download_item = IconMenuItem::with_id(
+    // Sous-menu Installed, un sous-menu par famille
+    let installed_submenu = Submenu::new(app, &texts.installed, true)?;
+    let _ = installed_submenu.set_icon(Some(icons.hard_drive.clone()));
+
+    for (family, entries) in group_by_family(&models.installed) {
+        let family_submenu = Submenu::new(app, &family, true)?;
+        for entry in entries {
+            let model_submenu = build_installed_model_menu(app, texts, entry, snapshot, icons)?;
+            let _ = family_submenu.append(&model_submenu);
+        }
+        let _ = installed_submenu.append(&family_submenu);
+    }
+
+    if models.installed.iter().any(|entry| entry.info.is_some()) {
+        // Action: Verify every installed model and pull the damaged ones again
+        let _ = installed_submenu.append(&PredefinedMenuItem::separator(app)?);
+        let repair_item = MenuItem::with_id(
             app,
-            format!("download_model_{}", model_name),
-            &texts.download_model,
+            "repair_models",
+            &texts.repair_models,
             true,
-            Some(icons.download.clone()),
             None::<&str>,
         )?;
-        let _ = model_submenu.append(&download_item);
+        let _ = installed_submenu.append(&repair_item);
+    }
 
-        let _ = catalog_submenu.append(&model_submenu);
+    // Sous-menu Catalog, sans les modèles trop gros si le réglage les masque
+    let catalog_submenu = Submenu::new(app, &texts.catalog, true)?;
+    let _ = catalog_submenu.set_icon(Some(icons.download.clone()));
+
+    let available = models
+        .available
+        .iter()
+        .filter(|entry| !(snapshot.hide_unfit_models && wont_fit(entry, memory)));
+    for (family, entries) in group_by_family(available) {
+        let family_submenu = Submenu::new(app, &family, true)?;
+        for entry in entries {
+            let model_submenu = build_catalog_model_menu(app, texts, entry, memory, icons)?;
+            let _ = family_submenu.append(&model_submenu);
+        }
+        let _ = catalog_submenu.append(&family_submenu);
     }
 
     // Menu principal Models
@@ -77,20 +292,226 @@ fn build_models_menu(
     Ok(models_menu)
 }
 
+/// Top-level submenu holding one entry per pending download
+const DOWNLOADS_MENU_ID: &str = "downloads";
+
+fn download_item_id(model: &str) -> String {
+    format!("download_job_{}", model)
+}
+
+fn build_downloads_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    downloads: &[DownloadJob],
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let submenu = Submenu::with_id(
+        app,
+        DOWNLOADS_MENU_ID,
+        format!("{} ({})", texts.downloads, downloads.len()),
+        true,
+    )?;
+    let _ = submenu.set_icon(Some(icons.download.clone()));
+
+    for job in downloads {
+        let cancel_item = IconMenuItem::with_id(
+            app,
+            format!("cancel_download_{}", job.model),
+            &texts.cancel_download,
+            true,
+            Some(icons.stop.clone()),
+            None::<&str>,
+        )?;
+        let job_submenu = Submenu::with_id_and_items(
+            app,
+            download_item_id(&job.model),
+            download_label(texts, job),
+            true,
+            &[&cancel_item],
+        )?;
+        let _ = submenu.append(&job_submenu);
+    }
+
+    Ok(submenu)
+}
+
+/// Refresh the labels of the Downloads submenu in place, without rebuilding the menu
+pub fn update_download_items(
+    menu: &Menu<tauri::Wry>,
+    texts: &TrayMenuTexts,
+    downloads: &[DownloadJob],
+) {
+    let Some(submenu) = menu
+        .get(DOWNLOADS_MENU_ID)
+        .and_then(|item| item.as_submenu().cloned())
+    else {
+        return;
+    };
+    for job in downloads {
+        if let Some(item) = submenu
+            .get(&download_item_id(&job.model))
+            .and_then(|item| item.as_submenu().cloned())
+        {
+            let _ = item.set_text(download_label(texts, job));
+        }
+    }
+}
+
+fn build_restart_policy_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    policy: &RestartPolicy,
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let submenu = Submenu::new(app, &texts.restart_policy, true)?;
+    let _ = submenu.set_icon(Some(icons.cog.clone()));
+
+    let modes = [
+        (
+            RestartMode::Never,
+            "restart_policy_never",
+            &texts.restart_never,
+        ),
+        (
+            RestartMode::OnFailure,
+            "restart_policy_on_failure",
+            &texts.restart_on_failure,
+        ),
+        (
+            RestartMode::Always,
+            "restart_policy_always",
+            &texts.restart_always,
+        ),
+    ];
+    for (mode, id, label) in modes {
+        let item = CheckMenuItem::with_id(app, id, label, true, policy.mode == mode, None::<&str>)?;
+        let _ = submenu.append(&item);
+    }
+
+    let retries_text = format!("{}: {}", texts.max_retries, policy.max_retries);
+    let retries_i = MenuItem::with_id(
+        app,
+        "restart_max_retries",
+        &retries_text,
+        false,
+        None::<&str>,
+    )?;
+    let _ = submenu.append(&PredefinedMenuItem::separator(app)?);
+    let _ = submenu.append(&retries_i);
+
+    Ok(submenu)
+}
+
+/// "Scheduled start: Mon 09:00 · model"
+fn transition_text(texts: &TrayMenuTexts, transition: &ScheduledTransition) -> String {
+    let label = match transition.kind {
+        TransitionKind::Start => &texts.schedule_start,
+        TransitionKind::Stop => &texts.schedule_stop,
+    };
+    let weekday = transition.at.weekday().num_days_from_monday() as usize;
+    let day = texts
+        .weekdays
+        .get(weekday)
+        .cloned()
+        .unwrap_or_else(|| transition.at.format("%a").to_string());
+    let mut text = format!("{} {} {}", label, day, transition.at.format("%H:%M"));
+    if let Some(model) = &transition.model {
+        text.push_str(&format!(" · {}", model));
+    }
+    text
+}
+
+/// Submenu of an additional instance: model, endpoint, start/stop and logs
+fn build_instance_menu(
+    app: &AppHandle,
+    texts: &TrayMenuTexts,
+    server: &ServerState,
+    icons: &ThemeIcons,
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let title = format!("{} — {}", server.id, status_text(texts, server));
+    let model_text = if server.model.is_empty() {
+        String::from("—")
+    } else {
+        server.model.clone()
+    };
+
+    let model_i = MenuItem::with_id(
+        app,
+        format!("instance_model_{}", server.id),
+        &model_text,
+        false,
+        None::<&str>,
+    )?;
+    let endpoint_i = MenuItem::with_id(
+        app,
+        format!("instance_endpoint_{}", server.id),
+        endpoint_text(server),
+        false,
+        None::<&str>,
+    )?;
+    let start_i = IconMenuItem::with_id(
+        app,
+        format!("instance_start_{}", server.id),
+        &texts.start,
+        matches!(
+            server.status,
+            ServerStatus::Stopped | ServerStatus::Restarting | ServerStatus::Failed
+        ),
+        Some(icons.play.clone()),
+        None::<&str>,
+    )?;
+    let stop_i = IconMenuItem::with_id(
+        app,
+        format!("instance_stop_{}", server.id),
+        &texts.stop,
+        matches!(
+            server.status,
+            ServerStatus::Starting | ServerStatus::Running | ServerStatus::Restarting
+        ),
+        Some(icons.stop.clone()),
+        None::<&str>,
+    )?;
+    let view_logs_i = IconMenuItem::with_id(
+        app,
+        format!("instance_logs_{}", server.id),
+        &texts.view_logs,
+        true,
+        Some(icons.file_clock.clone()),
+        None::<&str>,
+    )?;
+    let separator1 = PredefinedMenuItem::separator(app)?;
+    let separator2 = PredefinedMenuItem::separator(app)?;
+
+    let submenu = Submenu::with_items(
+        app,
+        &title,
+        true,
+        &[
+            &model_i,
+            &endpoint_i,
+            &separator1,
+            &start_i,
+            &stop_i,
+            &separator2,
+            &view_logs_i,
+        ],
+    )?;
+    let _ = submenu.set_icon(Some(icons.status_icon(server)));
+
+    Ok(submenu)
+}
+
 pub fn build_tray_menu(
     app: &AppHandle,
     params: &TrayMenuParams,
+    snapshot: &TraySnapshot,
     icons: &ThemeIcons,
 ) -> tauri::Result<Menu<tauri::Wry>> {
     let texts = &params.texts;
+    let server = &snapshot.server;
 
-    let status_icon = icons.status_icon(params.is_running);
-
-    let server_text = if params.is_running {
-        &texts.running
-    } else {
-        &texts.stopped
-    };
+    let status_icon = icons.status_icon(server);
+    let server_text = status_text(texts, server);
 
     let start_i = IconMenuItem::with_id(
         app,
@@ -109,8 +530,15 @@ pub fn build_tray_menu(
         None::<&str>,
     )?;
 
-    let _ = start_i.set_enabled(!params.is_running);
-    let _ = stop_i.set_enabled(params.is_running);
+    // Start is available whenever no process is alive, Stop also cancels a pending restart
+    let _ = start_i.set_enabled(matches!(
+        server.status,
+        ServerStatus::Stopped | ServerStatus::Restarting | ServerStatus::Failed
+    ));
+    let _ = stop_i.set_enabled(matches!(
+        server.status,
+        ServerStatus::Starting | ServerStatus::Running | ServerStatus::Restarting
+    ));
 
     // Presets submenu
     let presets_submenu = Submenu::new(app, &texts.presets_group, true)?;
@@ -133,12 +561,12 @@ pub fn build_tray_menu(
     let models_submenu = Submenu::new(app, &texts.models_group, true)?;
     let _ = models_submenu.set_icon(Some(icons.cpu.clone()));
 
-    for model_name in &params.installed_models {
-        let is_selected = model_name == &params.selected_model;
+    for entry in &snapshot.models.installed {
+        let is_selected = entry.name == params.selected_model;
         let model_item = CheckMenuItem::with_id(
             app,
-            format!("model_{}", model_name),
-            model_name,
+            format!("model_{}", entry.name),
+            model_label(texts, entry, snapshot.memory),
             true,
             is_selected,
             None::<&str>,
@@ -168,6 +596,9 @@ pub fn build_tray_menu(
         None::<&str>,
     )?;
 
+    let endpoint_i =
+        MenuItem::with_id(app, "endpoint", endpoint_text(server), false, None::<&str>)?;
+
     let view_logs_i = IconMenuItem::with_id(
         app,
         "view_logs",
@@ -197,27 +628,88 @@ pub fn build_tray_menu(
         Submenu::with_items(app, &texts.features, true, &[&asr_item, &embed_item])?;
     let _ = features_submenu.set_icon(Some(icons.cog.clone()));
 
+    let restart_policy_submenu =
+        build_restart_policy_menu(app, texts, &snapshot.restart_policy, icons)?;
+    let auto_port_item = CheckMenuItem::with_id(
+        app,
+        "toggle_auto_port",
+        &texts.auto_port,
+        true,
+        snapshot.port_selection.auto_select,
+        None::<&str>,
+    )?;
+    let schedule_item = CheckMenuItem::with_id(
+        app,
+        "toggle_schedule",
+        &texts.schedule,
+        true,
+        snapshot.schedule_enabled,
+        None::<&str>,
+    )?;
+    let transition_items = snapshot
+        .upcoming
+        .iter()
+        .enumerate()
+        .map(|(i, transition)| {
+            MenuItem::with_id(
+                app,
+                format!("schedule_next_{}", i),
+                transition_text(texts, transition),
+                false,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+
     let server_sep1 = PredefinedMenuItem::separator(app)?;
     let server_sep2 = PredefinedMenuItem::separator(app)?;
     let selection_sep = PredefinedMenuItem::separator(app)?;
 
-    let server_submenu = Submenu::with_items(
-        app,
-        server_text,
-        true,
-        &[
-            &current_model_i,
-            &selection_sep,
-            &presets_submenu,
-            &models_submenu,
-            &features_submenu,
-            &server_sep1,
-            &start_i,
-            &stop_i,
-            &server_sep2,
-            &view_logs_i,
-        ],
-    )?;
+    // Until the next run succeeds
+    let last_failure_i = match snapshot.last_failure {
+        Some(diagnosis)
+            if matches!(
+                server.status,
+                ServerStatus::Stopped | ServerStatus::Restarting | ServerStatus::Failed
+            ) =>
+        {
+            Some(MenuItem::with_id(
+                app,
+                "last_run_failed",
+                format!(
+                    "{}: {}",
+                    texts.last_run_failed,
+                    texts.diagnoses.get(diagnosis)
+                ),
+                false,
+                None::<&str>,
+            )?)
+        }
+        _ => None,
+    };
+
+    let server_submenu =
+        Submenu::with_items(app, &server_text, true, &[&current_model_i, &endpoint_i])?;
+    if let Some(item) = &last_failure_i {
+        server_submenu.append(item)?;
+    }
+    for item in &transition_items {
+        server_submenu.append(item)?;
+    }
+    server_submenu.append_items(&[
+        &selection_sep,
+        &presets_submenu,
+        &models_submenu,
+        &features_submenu,
+        &restart_policy_submenu,
+        &auto_port_item,
+        &schedule_item,
+        &server_sep1,
+        &start_i,
+        &stop_i,
+        &server_sep2,
+        &view_logs_i,
+    ])?;
     let _ = server_submenu.set_icon(Some(status_icon));
 
     let package_info = app.package_info();
@@ -251,25 +743,82 @@ pub fn build_tray_menu(
         Some(icons.power.clone()),
         None::<&str>,
     )?;
+    // Only meaningful while a server process is alive, every one of them is left running
+    let active = std::iter::once(server)
+        .chain(&snapshot.instances)
+        .filter(|state| matches!(state.status, ServerStatus::Starting | ServerStatus::Running))
+        .count();
+    let quit_keep_server_i = MenuItem::with_id(
+        app,
+        "quit_keep_server",
+        if active > 1 {
+            &texts.quit_keep_servers
+        } else {
+            &texts.quit_keep_server
+        },
+        active > 0,
+        None::<&str>,
+    )?;
     let separator = PredefinedMenuItem::separator(app)?;
 
     // Build Models menu
-    let models_menu = build_models_menu(app, params, icons)?;
+    let models_menu = build_models_menu(app, texts, snapshot, icons)?;
 
     let menu = Menu::new(app)?;
     menu.append(&app_info_i)?;
     menu.append(&flm_info_i)?;
     menu.append(&separator_top)?;
     menu.append(&models_menu)?;
+    for model in &snapshot.pending_removals {
+        let undo_i = IconMenuItem::with_id(
+            app,
+            format!("undo_remove_{}", model),
+            format!("{}: {}", texts.undo_removal, model),
+            true,
+            Some(icons.trash.clone()),
+            None::<&str>,
+        )?;
+        menu.append(&undo_i)?;
+    }
+    if !snapshot.downloads.is_empty() {
+        menu.append(&build_downloads_menu(
+            app,
+            texts,
+            &snapshot.downloads,
+            icons,
+        )?)?;
+    }
     menu.append(&server_submenu)?;
+    for instance in &snapshot.instances {
+        menu.append(&build_instance_menu(app, texts, instance, icons)?)?;
+    }
+    if let Some(orphan) = &server.orphan {
+        let labels = OrphanLabels::from_texts(texts);
+        menu.append(&build_orphan_menu(app, orphan, &labels, icons)?)?;
+    }
     menu.append(&settings_i)?;
     menu.append(&separator)?;
+    menu.append(&quit_keep_server_i)?;
     menu.append(&quit_i)?;
 
     Ok(menu)
 }
 
-pub fn build_initial_menu(app: &tauri::App, icons: &ThemeIcons) -> tauri::Result<Menu<tauri::Wry>> {
+/// Menu shown while the server is being stopped before exit
+pub fn build_shutdown_menu(app: &AppHandle, text: &str) -> tauri::Result<Menu<tauri::Wry>> {
+    let progress_i = MenuItem::with_id(app, "shutdown_progress", text, false, None::<&str>)?;
+
+    let menu = Menu::new(app)?;
+    menu.append(&progress_i)?;
+
+    Ok(menu)
+}
+
+pub fn build_initial_menu(
+    app: &AppHandle,
+    icons: &ThemeIcons,
+    orphan: Option<&OrphanServer>,
+) -> tauri::Result<Menu<tauri::Wry>> {
     let package_info = app.package_info();
     let title = format!("{} v{}", package_info.name, package_info.version);
     let app_info_i = MenuItem::with_id(app, "app_info", &title, false, None::<&str>)?;
@@ -313,6 +862,14 @@ pub fn build_initial_menu(app: &tauri::App, icons: &ThemeIcons) -> tauri::Result
     menu.append(&separator_top)?;
     menu.append(&settings_i)?;
     menu.append(&server_submenu)?;
+    if let Some(orphan) = orphan {
+        menu.append(&build_orphan_menu(
+            app,
+            orphan,
+            &OrphanLabels::DEFAULT,
+            icons,
+        )?)?;
+    }
     menu.append(&separator)?;
     menu.append(&quit_i)?;
 
@@ -1,6 +1,13 @@
 pub mod events;
 pub mod icons;
+pub mod labels;
 pub mod menu;
+pub mod removal;
 pub mod setup;
+pub mod state;
+pub mod throttle;
 
 pub use setup::init_tray;
+pub use state::{
+    refresh_download_progress, refresh_tray_menu, show_shutdown_progress, TraySnapshot, TrayState,
+};
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::tray::events::{notification_title, notify};
+use crate::tray::{refresh_tray_menu, TrayState};
+use crate::types::TrayMenuTexts;
+
+/// Delay between the confirmation of a removal and `flm remove`, the tray offers an undo meanwhile
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Confirmed removals waiting for their grace period to end
+#[derive(Default)]
+pub struct PendingRemovals {
+    /// Model → number of its removal, so an undone and confirmed again removal
+    /// is not run by the timer of the first one
+    models: Mutex<HashMap<String, u64>>,
+    next_id: AtomicU64,
+}
+
+impl PendingRemovals {
+    /// Pending models, sorted for a stable menu
+    pub fn pending(&self) -> Vec<String> {
+        let mut models: Vec<String> = self.lock().keys().cloned().collect();
+        models.sort();
+        models
+    }
+
+    /// Returns the number of the removal, `None` when the model is already pending
+    fn schedule(&self, model: &str) -> Option<u64> {
+        let mut models = self.lock();
+        if models.contains_key(model) {
+            return None;
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        models.insert(model.to_string(), id);
+        Some(id)
+    }
+
+    fn cancel(&self, model: &str) -> bool {
+        self.lock().remove(model).is_some()
+    }
+
+    /// Whether the removal `id` is still pending, it is no longer afterwards
+    fn take(&self, model: &str, id: u64) -> bool {
+        let mut models = self.lock();
+        if models.get(model) == Some(&id) {
+            models.remove(model);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, u64>> {
+        self.models.lock().expect("pending removals lock poisoned")
+    }
+}
+
+/// Ask in a native dialog before running a destructive tray action
+pub fn confirm(
+    app: &AppHandle,
+    title: impl FnOnce(&TrayMenuTexts) -> String,
+    message: String,
+    action: impl FnOnce(&AppHandle) + Send + 'static,
+) {
+    let texts = app.state::<TrayState>().params().map(|params| params.texts);
+    let (title, ok, cancel) = match &texts {
+        Some(texts) => (title(texts), texts.confirm.clone(), texts.cancel.clone()),
+        None => (
+            String::from("FLM Companion"),
+            String::from("OK"),
+            String::from("Cancel"),
+        ),
+    };
+
+    let handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title(title)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(ok, cancel))
+        .show(move |confirmed| {
+            if confirmed {
+                action(&handle);
+            }
+        });
+}
+
+/// Confirm the removal of a model, then run it once the grace period is over
+pub fn request_removal(app: &AppHandle, model: &str) {
+    let message = app
+        .state::<TrayState>()
+        .params()
+        .map(|params| format!("{}\n\n{}", model, params.texts.confirm_delete))
+        .unwrap_or_else(|| model.to_string());
+    let model = model.to_string();
+    confirm(
+        app,
+        |texts| texts.delete_model.clone(),
+        message,
+        move |app| schedule_removal(app, model),
+    );
+}
+
+fn schedule_removal(app: &AppHandle, model: String) {
+    let Some(id) = app.state::<PendingRemovals>().schedule(&model) else {
+        return;
+    };
+    refresh_tray_menu(app);
+    let title = notification_title(app, |texts| texts.removal_scheduled);
+    let hint = app
+        .state::<TrayState>()
+        .params()
+        .map(|params| params.texts.undo_removal_hint)
+        .unwrap_or_default();
+    notify(app, title, format!("{} — {}", model, hint));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(GRACE_PERIOD).await;
+        if app.state::<PendingRemovals>().take(&model, id) {
+            refresh_tray_menu(&app);
+            // The window runs `flm remove` and reports the result
+            let _ = app.emit("request-delete-model", &model);
+        }
+    });
+}
+
+/// Keep a model whose removal is still pending
+pub fn undo_removal(app: &AppHandle, model: &str) {
+    if app.state::<PendingRemovals>().cancel(model) {
+        refresh_tray_menu(app);
+        let title = notification_title(app, |texts| texts.removal_undone);
+        notify(app, title, model.to_string());
+    }
+}
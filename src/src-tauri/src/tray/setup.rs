@@ -2,9 +2,11 @@
 use tauri::tray::TrayIconBuilder;
 use tauri::{Manager, Theme};
 
+use crate::server::ServerSupervisor;
 use crate::tray::events::handle_menu_event;
 use crate::tray::icons::ThemeIcons;
 use crate::tray::menu::build_initial_menu;
+use crate::tray::state::spawn_tooltip_refresh;
 
 pub fn init_tray(app: &tauri::App) -> tauri::Result<()> {
     let is_dark = app
@@ -13,15 +15,19 @@ pub fn init_tray(app: &tauri::App) -> tauri::Result<()> {
         .unwrap_or(false);
 
     let icons = ThemeIcons::load(is_dark);
-    let menu = build_initial_menu(app, &icons)?;
+    let orphan = app.state::<ServerSupervisor>().main().state().orphan;
+    let menu = build_initial_menu(app.handle(), &icons, orphan.as_ref())?;
 
     TrayIconBuilder::with_id("main")
         .icon(icons.tray.clone())
+        .tooltip("FLM")
         .menu(&menu)
         .on_menu_event(|app, event| {
             handle_menu_event(app, event.id.as_ref());
         })
         .build(app)?;
+    // Filled in once the frontend has sent its texts
+    spawn_tooltip_refresh(app.handle());
 
     Ok(())
 }
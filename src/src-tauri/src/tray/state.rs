@@ -0,0 +1,292 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tauri::menu::Menu;
+use tauri::{AppHandle, Manager, Theme, Wry};
+
+use crate::catalog::memory::SystemMemory;
+use crate::catalog::ModelStore;
+use crate::download::DownloadManager;
+use crate::server::history::now_millis;
+use crate::server::{Scheduler, ServerSupervisor, SessionHistory, MAIN_INSTANCE};
+use crate::tray::icons::ThemeIcons;
+use crate::tray::labels::tray_tooltip;
+use crate::tray::menu::{
+    build_initial_menu, build_shutdown_menu, build_tray_menu, update_download_items,
+};
+use crate::tray::removal::PendingRemovals;
+use crate::tray::throttle::{Refresh, Throttle};
+use crate::types::{
+    AppConfig, Diagnosis, DownloadJob, ModelInventory, PortSelection, RestartPolicy,
+    ScheduledTransition, ServerState, TrayMenuParams, TrayMenuTexts,
+};
+
+/// Scheduled transitions listed in the server submenu
+const UPCOMING_TRANSITIONS: usize = 2;
+/// Download progress is written to the menu at most this often
+const DOWNLOAD_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// The tooltip uptime counts in minutes
+const TOOLTIP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Last menu parameters sent by the frontend, reused when the backend refreshes the tray
+pub struct TrayState {
+    params: Mutex<Option<TrayMenuParams>>,
+    /// Set once the app is quitting, the menu then stays on the shutdown progress
+    frozen: AtomicBool,
+    /// Menu currently shown, its download labels are updated in place
+    menu: Mutex<Option<Menu<Wry>>>,
+    download_refresh: Mutex<Throttle>,
+    /// Mirror of the `hideUnfitModels` setting, the menu is rebuilt without reading the config
+    hide_unfit_models: AtomicBool,
+    /// Installed RAM, read once at startup
+    total_memory: AtomicU64,
+}
+
+impl Default for TrayState {
+    fn default() -> Self {
+        Self {
+            params: Mutex::default(),
+            frozen: AtomicBool::default(),
+            menu: Mutex::default(),
+            download_refresh: Mutex::new(Throttle::new(DOWNLOAD_REFRESH_INTERVAL)),
+            hide_unfit_models: AtomicBool::default(),
+            total_memory: AtomicU64::default(),
+        }
+    }
+}
+
+impl TrayState {
+    pub fn init_config(&self, config: &AppConfig) {
+        self.set_hide_unfit_models(config.hide_unfit_models);
+        self.total_memory
+            .store(SystemMemory::current().total_bytes, Ordering::Relaxed);
+    }
+
+    pub fn hide_unfit_models(&self) -> bool {
+        self.hide_unfit_models.load(Ordering::Relaxed)
+    }
+
+    pub fn set_hide_unfit_models(&self, hide: bool) {
+        self.hide_unfit_models.store(hide, Ordering::Relaxed);
+    }
+
+    /// Memory the tray checks the models against, without querying the system
+    pub fn memory(&self) -> SystemMemory {
+        SystemMemory::from_total(self.total_memory.load(Ordering::Relaxed))
+    }
+
+    pub fn set_params(&self, params: TrayMenuParams) {
+        *self.params.lock().expect("tray state lock poisoned") = Some(params);
+    }
+
+    pub fn params(&self) -> Option<TrayMenuParams> {
+        self.params
+            .lock()
+            .expect("tray state lock poisoned")
+            .clone()
+    }
+
+    pub fn freeze(&self) {
+        self.frozen.store(true, Ordering::SeqCst);
+    }
+
+    fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::SeqCst)
+    }
+
+    fn set_menu(&self, menu: Menu<Wry>) {
+        *self.menu.lock().expect("tray state lock poisoned") = Some(menu);
+    }
+
+    fn menu(&self) -> Option<Menu<Wry>> {
+        self.menu.lock().expect("tray state lock poisoned").clone()
+    }
+
+    fn download_refresh(&self) -> MutexGuard<'_, Throttle> {
+        self.download_refresh
+            .lock()
+            .expect("tray state lock poisoned")
+    }
+}
+
+/// Backend state displayed by the tray, gathered on each refresh
+pub struct TraySnapshot {
+    /// Main instance, driven by the UI selection
+    pub server: ServerState,
+    /// Additional instances
+    pub instances: Vec<ServerState>,
+    pub restart_policy: RestartPolicy,
+    pub port_selection: PortSelection,
+    pub schedule_enabled: bool,
+    /// Next scheduled starts and stops of the main instance
+    pub upcoming: Vec<ScheduledTransition>,
+    /// Cause of the last run of the main instance, when it failed
+    pub last_failure: Option<Diagnosis>,
+    pub models: ModelInventory,
+    /// Models are flagged, or hidden from the catalog, when they won't fit in it
+    pub memory: SystemMemory,
+    pub hide_unfit_models: bool,
+    /// Confirmed removals that can still be undone
+    pub pending_removals: Vec<String>,
+    /// Queued and running downloads
+    pub downloads: Vec<DownloadJob>,
+}
+
+impl TraySnapshot {
+    fn collect(app: &AppHandle) -> Self {
+        let supervisor = app.state::<ServerSupervisor>();
+        let schedule = app.state::<Scheduler>();
+        let tray_state = app.state::<TrayState>();
+        let mut states = supervisor.states().into_iter();
+        Self {
+            server: states.next().unwrap_or_default(),
+            instances: states.collect(),
+            restart_policy: supervisor.restart_policy(),
+            port_selection: supervisor.port_selection(),
+            schedule_enabled: schedule.state().schedule().enabled,
+            upcoming: schedule.state().upcoming(UPCOMING_TRANSITIONS),
+            last_failure: app
+                .state::<SessionHistory>()
+                .last_failure(MAIN_INSTANCE)
+                .and_then(|record| record.diagnosis),
+            models: app.state::<ModelStore>().inventory().unwrap_or_default(),
+            memory: tray_state.memory(),
+            hide_unfit_models: tray_state.hide_unfit_models(),
+            pending_removals: app.state::<PendingRemovals>().pending(),
+            downloads: app.state::<DownloadManager>().pending(),
+        }
+    }
+}
+
+/// Rebuild the tray menu from the last frontend parameters and the current backend state
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let tray_state = app.state::<TrayState>();
+    if tray_state.is_frozen() {
+        return;
+    }
+    let is_dark = app
+        .get_webview_window("main")
+        .map(|w| matches!(w.theme(), Ok(Theme::Dark)))
+        .unwrap_or(false);
+
+    let icons = ThemeIcons::load(is_dark);
+    let Some(params) = tray_state.params() else {
+        // No texts yet: the initial menu, with the existing server found since startup
+        let orphan = app.state::<ServerSupervisor>().main().state().orphan;
+        if let Ok(menu) = build_initial_menu(app, &icons, orphan.as_ref()) {
+            if let Some(tray) = app.tray_by_id("main") {
+                let _ = tray.set_menu(Some(menu));
+            }
+        }
+        return;
+    };
+    let snapshot = TraySnapshot::collect(app);
+
+    if let Ok(menu) = build_tray_menu(app, &params, &snapshot, &icons) {
+        if let Some(tray) = app.tray_by_id("main") {
+            let _ = tray.set_menu(Some(menu.clone()));
+            tray_state.set_menu(menu);
+        }
+    }
+    if let Some(tray) = app.tray_by_id("main") {
+        let icon = if snapshot.downloads.is_empty() {
+            icons.tray
+        } else {
+            icons.tray_download
+        };
+        let _ = tray.set_icon(Some(icon));
+        let tooltip = tray_tooltip(
+            &params.texts,
+            &snapshot.server,
+            snapshot.last_failure,
+            &snapshot.downloads,
+            now_millis(),
+        );
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+/// Show the progress of the running downloads without rebuilding the menu, throttled.
+/// An update arriving too soon is shown by a refresh at the end of the interval.
+pub fn refresh_download_progress(app: &AppHandle) {
+    let tray_state = app.state::<TrayState>();
+    if tray_state.is_frozen() {
+        return;
+    }
+    let refresh = tray_state.download_refresh().request(Instant::now());
+    match refresh {
+        Refresh::Now => write_download_progress(app),
+        Refresh::After(delay) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let tray_state = app.state::<TrayState>();
+                tray_state.download_refresh().fire(Instant::now());
+                if !tray_state.is_frozen() {
+                    write_download_progress(&app);
+                }
+            });
+        }
+        Refresh::Pending => {}
+    }
+}
+
+fn write_download_progress(app: &AppHandle) {
+    let tray_state = app.state::<TrayState>();
+    let (Some(params), Some(menu)) = (tray_state.params(), tray_state.menu()) else {
+        return;
+    };
+    let downloads = app.state::<DownloadManager>().pending();
+
+    update_download_items(&menu, &params.texts, &downloads);
+    set_tooltip(app, &params.texts, &downloads);
+}
+
+/// Keep the uptime of the tooltip current between state changes
+pub fn spawn_tooltip_refresh(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TOOLTIP_INTERVAL).await;
+            let tray_state = app.state::<TrayState>();
+            if tray_state.is_frozen() {
+                continue;
+            }
+            if let Some(params) = tray_state.params() {
+                let downloads = app.state::<DownloadManager>().pending();
+                set_tooltip(&app, &params.texts, &downloads);
+            }
+        }
+    });
+}
+
+fn set_tooltip(app: &AppHandle, texts: &TrayMenuTexts, downloads: &[DownloadJob]) {
+    let server = app.state::<ServerSupervisor>().main().state();
+    let last_failure = app
+        .state::<SessionHistory>()
+        .last_failure(MAIN_INSTANCE)
+        .and_then(|record| record.diagnosis);
+    if let Some(tray) = app.tray_by_id("main") {
+        let tooltip = tray_tooltip(texts, &server, last_failure, downloads, now_millis());
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+/// Replace the tray menu with a "Stopping server…" notice while quitting
+pub fn show_shutdown_progress(app: &AppHandle) {
+    let tray_state = app.state::<TrayState>();
+    tray_state.freeze();
+
+    let text = tray_state
+        .params()
+        .map(|params| params.texts.stopping_server)
+        .unwrap_or_else(|| String::from("Stopping server…"));
+
+    if let Some(tray) = app.tray_by_id("main") {
+        if let Ok(menu) = build_shutdown_menu(app, &text) {
+            let _ = tray.set_menu(Some(menu));
+        }
+        let _ = tray.set_tooltip(Some(&text));
+    }
+}
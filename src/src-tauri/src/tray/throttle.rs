@@ -0,0 +1,89 @@
+use std::time::{Duration, Instant};
+
+/// What to do with an update arriving at a throttled refresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    Now,
+    /// Too soon, refresh once the interval is over
+    After(Duration),
+    /// A trailing refresh is already scheduled, it will show this update
+    Pending,
+}
+
+/// Refresh at most once per interval, without losing the last update of a burst
+#[derive(Debug)]
+pub struct Throttle {
+    interval: Duration,
+    last: Option<Instant>,
+    trailing: bool,
+}
+
+impl Throttle {
+    pub const fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: None,
+            trailing: false,
+        }
+    }
+
+    pub fn request(&mut self, now: Instant) -> Refresh {
+        if self.trailing {
+            return Refresh::Pending;
+        }
+        match self.last.map(|at| now.saturating_duration_since(at)) {
+            Some(elapsed) if elapsed < self.interval => {
+                self.trailing = true;
+                Refresh::After(self.interval - elapsed)
+            }
+            _ => {
+                self.last = Some(now);
+                Refresh::Now
+            }
+        }
+    }
+
+    /// The scheduled trailing refresh runs
+    pub fn fire(&mut self, now: Instant) {
+        self.trailing = false;
+        self.last = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn first_update_refreshes_at_once() {
+        let mut throttle = Throttle::new(INTERVAL);
+        let start = Instant::now();
+        assert_eq!(throttle.request(start), Refresh::Now);
+        assert_eq!(throttle.request(start + INTERVAL), Refresh::Now);
+    }
+
+    #[test]
+    fn burst_ends_with_a_trailing_refresh() {
+        let mut throttle = Throttle::new(INTERVAL);
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        assert_eq!(throttle.request(at(0)), Refresh::Now);
+        assert_eq!(
+            throttle.request(at(300)),
+            Refresh::After(Duration::from_millis(700))
+        );
+        // Shown by the scheduled refresh
+        assert_eq!(throttle.request(at(600)), Refresh::Pending);
+        assert_eq!(throttle.request(at(900)), Refresh::Pending);
+
+        throttle.fire(at(1000));
+        assert_eq!(
+            throttle.request(at(1200)),
+            Refresh::After(Duration::from_millis(800))
+        );
+        throttle.fire(at(2000));
+        assert_eq!(throttle.request(at(3500)), Refresh::Now);
+    }
+}
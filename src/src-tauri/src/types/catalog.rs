@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// What a model can do, as declared by the catalog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCapabilities {
+    /// Reasoning model
+    pub think: bool,
+    /// Accepts images
+    pub vision: bool,
+    /// Served through the embeddings endpoint
+    pub embed: bool,
+    /// Speech recognition model
+    pub audio: bool,
+}
+
+/// Model of the FLM catalog (`model_list.json`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    /// `family:tag`, as passed to `flm serve`
+    pub name: String,
+    pub family: String,
+    pub tag: String,
+    /// Display name of the model files
+    pub description: Option<String>,
+    /// Directory of the model under the catalog `model_path`
+    pub dir_name: Option<String>,
+    pub url: Option<String>,
+    pub size_bytes: u64,
+    pub modified_at: Option<String>,
+    pub context_length: Option<u32>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub format: Option<String>,
+    /// Files of the model directory, when the catalog lists them
+    pub files: Vec<String>,
+    /// SHA-256 of some of these files, lowercase hex by file name
+    pub checksums: BTreeMap<String, String>,
+    pub capabilities: ModelCapabilities,
+}
+
+/// Files of a model under the catalog `model_path`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiskState {
+    Complete,
+    /// Some files are missing or still being written
+    Partial,
+    Missing,
+    /// No model directory to look into
+    Unknown,
+}
+
+/// Disagreement between `flm list` and the files on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstallIssue {
+    /// Listed as installed, its files are gone
+    MissingFiles,
+    /// Files left by an interrupted pull, or removed halfway
+    PartialFiles,
+    /// Complete on disk but not listed as installed
+    NotListed,
+}
+
+/// Model of the installed or available list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InventoryEntry {
+    pub name: String,
+    /// Missing for installed models unknown to the catalog
+    pub info: Option<ModelInfo>,
+    pub disk: DiskState,
+    pub issue: Option<InstallIssue>,
+}
+
+impl InventoryEntry {
+    /// Installed model that `flm serve` can load as the chat model
+    pub fn is_startable(&self) -> bool {
+        self.issue.is_none()
+            && self
+                .info
+                .as_ref()
+                .is_none_or(|info| !info.capabilities.embed && !info.capabilities.audio)
+    }
+}
+
+/// What is wrong with a file of a model directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileProblem {
+    /// Listed by the catalog, absent from the directory
+    Missing,
+    /// Present but empty
+    Truncated,
+    /// Content differs from the catalog checksum
+    ChecksumMismatch,
+    /// Left by an interrupted download
+    Partial,
+    /// Not listed by the catalog, harmless on its own
+    Extra,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIssue {
+    /// Path relative to the model directory
+    pub file: String,
+    pub problem: FileProblem,
+}
+
+/// Files of an installed model checked against its catalog entry
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub model: String,
+    pub dir: PathBuf,
+    /// Catalog size and bytes found, of the listed files when the catalog lists them
+    pub expected_bytes: u64,
+    pub actual_bytes: u64,
+    /// Files found much smaller than the catalog size, one of them was cut short
+    pub undersized: bool,
+    /// Files whose checksum was compared
+    pub checksums_checked: usize,
+    pub issues: Vec<FileIssue>,
+    /// Nothing but extra files, the model can be served
+    pub healthy: bool,
+}
+
+/// Installed and available models, reconciled from the catalog, `flm list` and the disk
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInventory {
+    pub installed: Vec<InventoryEntry>,
+    pub available: Vec<InventoryEntry>,
+}
@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ServerOptions, DEFAULT_SERVER_PORT};
+
+/// Additional server instance, persisted under `serverInstances`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InstanceConfig {
+    pub id: String,
+    pub model: String,
+    pub options: ServerOptions,
+}
+
+/// When the supervisor restarts `flm serve` after it exits on its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+/// Restart policy, persisted under `restartPolicy` in the app config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    /// Consecutive restarts before giving up
+    pub max_retries: u32,
+    /// Delay before the first restart, doubled on each attempt
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    /// Crashes within this window count towards a crash loop; a longer run resets the counters
+    pub crash_loop_window_secs: u64,
+    pub crash_loop_threshold: u32,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: 5,
+            initial_delay_secs: 2,
+            max_delay_secs: 60,
+            crash_loop_window_secs: 120,
+            crash_loop_threshold: 3,
+        }
+    }
+}
+
+impl RestartPolicy {
+    pub fn crash_loop_window(&self) -> Duration {
+        Duration::from_secs(self.crash_loop_window_secs)
+    }
+
+    /// Exponential backoff for the given attempt (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        let delay = self.initial_delay_secs.saturating_mul(factor);
+        Duration::from_secs(delay.min(self.max_delay_secs))
+    }
+}
+
+/// What to do when the configured port is taken, persisted under `portSelection`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PortSelection {
+    /// Pick the next free port in the range instead of failing
+    pub auto_select: bool,
+    pub range_start: u16,
+    pub range_end: u16,
+}
+
+impl Default for PortSelection {
+    fn default() -> Self {
+        Self {
+            auto_select: false,
+            range_start: DEFAULT_SERVER_PORT,
+            range_end: DEFAULT_SERVER_PORT + 100,
+        }
+    }
+}
+
+impl PortSelection {
+    /// Ports of the range to try, starting after `port` and wrapping around
+    pub fn candidates(&self, port: u16) -> impl Iterator<Item = u16> {
+        let (start, end) = (self.range_start, self.range_end.max(self.range_start));
+        let after = port.saturating_add(1).clamp(start, end);
+        (after..=end)
+            .chain(start..after)
+            .filter(move |candidate| *candidate != port)
+    }
+}
+
+/// Stop the server after a period without API traffic, persisted under `idleStop`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdlePolicy {
+    /// 0 disables the idle stop
+    pub timeout_minutes: u32,
+    /// Restart the server on the next connection to its port
+    pub wake_on_request: bool,
+}
+
+impl IdlePolicy {
+    pub fn timeout(&self) -> Option<Duration> {
+        (self.timeout_minutes > 0)
+            .then(|| Duration::from_secs(u64::from(self.timeout_minutes) * 60))
+    }
+}
+
+/// Start the main server when the companion starts, persisted under `startOnLaunch`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StartOnLaunch {
+    pub enabled: bool,
+    /// Preset to start, the last selected model when unset
+    pub preset: Option<String>,
+    /// Lets the NPU driver finish initializing after boot
+    pub delay_secs: u64,
+}
+
+impl Default for StartOnLaunch {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            preset: None,
+            delay_secs: 10,
+        }
+    }
+}
+
+/// Day of a schedule window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScheduleDay {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl ScheduleDay {
+    /// 0 for Monday
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Weekly window during which the main server should run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduleWindow {
+    pub days: Vec<ScheduleDay>,
+    /// Local time as `HH:MM`, a window ending before it starts runs past midnight
+    pub start: String,
+    pub end: String,
+    /// Model resolved by the frontend, from a preset when `preset` is set
+    pub model: String,
+    pub preset: Option<String>,
+    pub options: ServerOptions,
+}
+
+/// Scheduled start/stop windows, persisted under `schedule`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Schedule {
+    pub enabled: bool,
+    pub windows: Vec<ScheduleWindow>,
+}
+
+/// Part of the frontend `config.json` read by the backend, unknown keys are ignored
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppConfig {
+    /// Directory of the `flm` executable, or just "flm" to look it up in PATH
+    pub flm_path: String,
+    /// Model or preset id selected in the UI
+    pub last_selected_model: String,
+    pub start_on_launch: StartOnLaunch,
+    pub restart_policy: RestartPolicy,
+    pub port_selection: PortSelection,
+    pub server_options: ServerOptions,
+    pub server_instances: Vec<InstanceConfig>,
+    pub schedule: Schedule,
+    pub idle_stop: IdlePolicy,
+    /// Leave catalog models that won't fit in memory out of the tray instead of flagging them
+    pub hide_unfit_models: bool,
+}
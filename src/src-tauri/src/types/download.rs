@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadStatus {
+    /// Waiting for the downloads ahead of it
+    Queued,
+    Downloading,
+    /// Failed, pulled again once `retry_at` is reached
+    Retrying,
+    Completed,
+    /// Failed and out of retries
+    Failed,
+    Cancelled,
+}
+
+impl DownloadStatus {
+    /// Still in the queue, a new pull of the same model is refused
+    pub fn is_pending(self) -> bool {
+        matches!(self, Self::Queued | Self::Downloading | Self::Retrying)
+    }
+}
+
+/// One `flm pull`, persisted with the queue and sent with every change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJob {
+    pub model: String,
+    pub status: DownloadStatus,
+    /// Overall progress, 0 to 100
+    pub percent: Option<f32>,
+    pub bytes_per_sec: Option<u64>,
+    /// File being downloaded and file count, when `flm pull` reports them
+    pub current_file: Option<u32>,
+    pub total_files: Option<u32>,
+    /// Pulls started for this request, retries included
+    pub attempts: u32,
+    /// Repair of an installed model, its files are downloaded again
+    #[serde(default)]
+    pub force: bool,
+    pub error: Option<String>,
+    /// Unix timestamps in milliseconds
+    pub queued_at: u64,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    pub retry_at: Option<u64>,
+}
+
+impl DownloadJob {
+    pub fn new(model: &str, queued_at: u64) -> Self {
+        Self {
+            model: model.to_string(),
+            status: DownloadStatus::Queued,
+            percent: None,
+            bytes_per_sec: None,
+            current_file: None,
+            total_files: None,
+            attempts: 0,
+            force: false,
+            error: None,
+            queued_at,
+            started_at: None,
+            finished_at: None,
+            retry_at: None,
+        }
+    }
+}
+
+/// Installed model suggested for removal when a download does not fit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovableModel {
+    pub name: String,
+    pub size_bytes: u64,
+}
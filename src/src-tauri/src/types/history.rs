@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::ServerOptions;
+
+/// Who or what ended a server session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StopCause {
+    /// Stop from the UI, the tray or a command
+    User,
+    Schedule,
+    Idle,
+    /// Restart to apply new options
+    Reconfigure,
+    /// The companion quit
+    Shutdown,
+    /// The process exited on its own
+    Exited,
+}
+
+/// Likely cause of a failed session, from its exit code and last error lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Diagnosis {
+    PortInUse,
+    ModelNotInstalled,
+    NpuBusy,
+    NpuUnavailable,
+    OutOfMemory,
+    /// Killed by a signal or an access violation
+    Crashed,
+    Unknown,
+}
+
+/// One run of `flm serve`, from spawn to exit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecord {
+    pub instance: String,
+    /// Log session of the run, if logging was available
+    pub log_session: Option<String>,
+    pub model: String,
+    pub options: ServerOptions,
+    /// Unix times in milliseconds
+    pub started_at: u64,
+    pub ready_at: Option<u64>,
+    pub stopped_at: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub stopped_by: Option<StopCause>,
+    /// Last warning and error lines of the output, oldest first
+    pub last_errors: Vec<String>,
+    /// Set when the session failed
+    pub diagnosis: Option<Diagnosis>,
+}
+
+impl SessionRecord {
+    /// The process exited on its own with a non-zero or unknown code
+    pub fn failed(&self) -> bool {
+        self.stopped_by == Some(StopCause::Exited) && self.exit_code != Some(0)
+    }
+}
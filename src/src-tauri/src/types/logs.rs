@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+/// Log file of one server session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSession {
+    /// File stem, used to open the session
+    pub id: String,
+    pub instance: String,
+    /// Unix time in milliseconds
+    pub started_at: u64,
+    /// Size in bytes, rotated parts included
+    pub size: u64,
+    /// Still receiving lines
+    pub active: bool,
+}
+
+/// Slice of the lines of a session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPage {
+    pub session: String,
+    /// Index of the first returned line
+    pub offset: usize,
+    /// Number of lines in the session
+    pub total: usize,
+    pub lines: Vec<String>,
+}
+
+/// Payload of the tray "view-logs" event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewLogsRequest {
+    pub instance: String,
+    /// Latest session of the instance, if any was recorded
+    pub session: Option<String>,
+}
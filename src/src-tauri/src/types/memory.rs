@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+use crate::types::ServerOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryVerdict {
+    Fits,
+    /// Within the shared memory but with little headroom, or more than is free right now
+    Tight,
+    WontFit,
+}
+
+/// Memory a server configuration needs, compared with the memory the NPU can use
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryEstimate {
+    pub model: String,
+    /// Context length the estimate is made for, the model default when the options have none
+    pub ctx_len: u32,
+    pub weights_bytes: u64,
+    pub kv_cache_bytes: u64,
+    /// Whisper model loaded by `--asr`
+    pub asr_bytes: u64,
+    /// Embedding model loaded by `--embed`
+    pub embed_bytes: u64,
+    /// Runtime buffers and NPU kernels
+    pub overhead_bytes: u64,
+    pub total_bytes: u64,
+    /// Half of the RAM, the most Windows lets the NPU share
+    pub shared_memory_bytes: u64,
+    /// RAM free right now
+    pub available_bytes: u64,
+    pub verdict: MemoryVerdict,
+}
+
+/// Payload of the tray "confirm-start" event, a start held back because it won't fit
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryWarning {
+    pub instance: String,
+    pub model: String,
+    pub options: ServerOptions,
+    pub estimate: MemoryEstimate,
+}
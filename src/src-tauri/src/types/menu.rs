@@ -1,5 +1,7 @@
 use serde::Deserialize;
 
+use crate::types::Diagnosis;
+
 /// Preset item for tray menu
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,28 +14,96 @@ pub struct TrayPreset {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TrayMenuParams {
-    pub is_running: bool,
     pub selected_model: String,
     pub presets: Vec<TrayPreset>,
-    pub installed_models: Vec<String>,
-    pub available_models: Vec<String>,
-    pub startable_models: Vec<String>,
     pub asr_enabled: bool,
     pub embed_enabled: bool,
     pub flm_version: String,
     pub texts: TrayMenuTexts,
 }
 
+/// Human-readable failure causes of the session history
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosisTexts {
+    pub port_in_use: String,
+    pub model_not_installed: String,
+    pub npu_busy: String,
+    pub npu_unavailable: String,
+    pub out_of_memory: String,
+    pub crashed: String,
+    pub unknown: String,
+}
+
+impl DiagnosisTexts {
+    pub fn get(&self, diagnosis: Diagnosis) -> &str {
+        match diagnosis {
+            Diagnosis::PortInUse => &self.port_in_use,
+            Diagnosis::ModelNotInstalled => &self.model_not_installed,
+            Diagnosis::NpuBusy => &self.npu_busy,
+            Diagnosis::NpuUnavailable => &self.npu_unavailable,
+            Diagnosis::OutOfMemory => &self.out_of_memory,
+            Diagnosis::Crashed => &self.crashed,
+            Diagnosis::Unknown => &self.unknown,
+        }
+    }
+}
+
+/// Server status as worded in the tray tooltip, the menu titles read "Server (Running)"
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TooltipTexts {
+    pub running: String,
+    pub starting: String,
+    pub stopping: String,
+    pub stopped: String,
+    pub idle: String,
+    pub restarting: String,
+    pub failed: String,
+}
+
 /// Textes localisés pour le menu tray
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TrayMenuTexts {
     pub start: String,
     pub stop: String,
     pub quit: String,
+    pub quit_keep_server: String,
+    /// Same, with several instances running
+    pub quit_keep_servers: String,
+    pub stopping_server: String,
     pub settings: String,
     pub running: String,
     pub stopped: String,
+    pub stopped_idle: String,
+    pub starting: String,
+    pub stopping: String,
+    pub degraded: String,
+    pub unreachable: String,
+    pub restarting: String,
+    pub failed: String,
+    pub crash_loop: String,
+    pub launch_failed: String,
+    pub last_run_failed: String,
+    pub diagnoses: DiagnosisTexts,
+    pub restart_policy: String,
+    pub restart_never: String,
+    pub restart_on_failure: String,
+    pub restart_always: String,
+    pub max_retries: String,
+    pub auto_port: String,
+    pub adopted: String,
+    pub orphan_found: String,
+    pub port_in_use: String,
+    pub adopt_server: String,
+    pub terminate_server: String,
+    pub use_other_port: String,
+    pub schedule: String,
+    pub schedule_start: String,
+    pub schedule_stop: String,
+    /// Short day names, Monday first
+    pub weekdays: Vec<String>,
     pub view_logs: String,
     pub features: String,
     pub asr: String,
@@ -45,5 +115,39 @@ pub struct TrayMenuTexts {
     pub catalog: String,
     pub start_with_model: String,
     pub delete_model: String,
+    /// Confirmation dialogs of the destructive actions
+    pub confirm: String,
+    pub cancel: String,
+    pub confirm_delete: String,
+    pub confirm_terminate: String,
+    /// Removals wait a few seconds, during which the tray offers to undo them
+    pub removal_scheduled: String,
+    pub undo_removal: String,
+    pub undo_removal_hint: String,
+    pub removal_undone: String,
+    /// Notification title of the model actions that failed
+    pub action_failed: String,
+    pub verify_model: String,
+    pub repair_models: String,
+    /// Notification titles of the verification and repair actions
+    pub verify_passed: String,
+    pub verify_failed: String,
+    pub repairing: String,
     pub download_model: String,
+    /// Capability badges of the model entries
+    pub badge_think: String,
+    pub badge_vlm: String,
+    pub badge_embed: String,
+    pub badge_asr: String,
+    /// Marks the models too large for the shared memory
+    pub wont_fit: String,
+    pub downloads: String,
+    pub download_queued: String,
+    pub download_retrying: String,
+    pub cancel_download: String,
+    /// Tooltip parts: download progress, uptime and last failure
+    pub downloading: String,
+    pub uptime: String,
+    pub error: String,
+    pub tooltip: TooltipTexts,
 }
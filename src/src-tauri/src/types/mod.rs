@@ -1,3 +1,17 @@
+pub mod catalog;
+pub mod config;
+pub mod download;
+pub mod history;
+pub mod logs;
+pub mod memory;
 pub mod menu;
+pub mod server;
 
+pub use catalog::*;
+pub use config::*;
+pub use download::*;
+pub use history::*;
+pub use logs::*;
+pub use memory::*;
 pub use menu::*;
+pub use server::*;
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::StopCause;
+
+pub const DEFAULT_SERVER_PORT: u16 = 52625;
+pub const DEFAULT_SERVER_HOST: &str = "127.0.0.1";
+
+/// Options passed to `flm serve` (mirror of the frontend `ServerOptions`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pmode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctx_len: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asr: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub socket: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub q_len: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cors: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preemption: Option<bool>,
+}
+
+impl ServerOptions {
+    /// Port the server listens on, falling back to the FLM default
+    pub fn port(&self) -> u16 {
+        self.port
+            .filter(|port| *port > 0)
+            .unwrap_or(DEFAULT_SERVER_PORT)
+    }
+
+    /// Host the server listens on, falling back to the loopback address
+    pub fn host(&self) -> &str {
+        self.host
+            .as_deref()
+            .filter(|host| !host.is_empty())
+            .unwrap_or(DEFAULT_SERVER_HOST)
+    }
+}
+
+/// Lifecycle of the supervised `flm serve` process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerStatus {
+    #[default]
+    Stopped,
+    Starting,
+    Running,
+    Stopping,
+    /// Exited on its own, waiting for the backoff delay before restarting
+    Restarting,
+    /// The restart policy gave up
+    Failed,
+}
+
+/// Health of the HTTP endpoint, as seen by the periodic `/v1/models` probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerHealth {
+    Starting,
+    Ready,
+    Degraded,
+    Unreachable,
+}
+
+/// Why the supervisor stopped restarting the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureReason {
+    CrashLoop,
+    RetriesExhausted,
+    /// The start on launch did not go through
+    LaunchFailed,
+}
+
+/// Automatic restart scheduled by the restart policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRestart {
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub delay_secs: u64,
+}
+
+/// Server found at startup that the companion did not launch (left over after a crash)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanServer {
+    /// Unknown when the port is taken but the owner could not be identified
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+    /// Whether the process is `flm serve`, only those can be adopted or terminated
+    pub is_flm: bool,
+    pub host: String,
+    pub port: u16,
+    pub model: Option<String>,
+    /// Answers on `/v1/models`
+    pub responding: bool,
+}
+
+impl OrphanServer {
+    /// Adopting or terminating requires a known `flm` process
+    pub fn is_manageable(&self) -> bool {
+        self.is_flm && self.pid.is_some()
+    }
+}
+
+/// How to resolve an orphaned server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrphanAction {
+    /// Monitor the existing process as if the companion had started it
+    Adopt,
+    /// Kill the existing process
+    Terminate,
+    /// Leave it alone and move our server to a free port
+    ChangePort,
+}
+
+/// NPU resource a `flm serve` process holds exclusively
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NpuResource {
+    Llm,
+    Asr,
+    Embeddings,
+}
+
+/// Line of server output, tagged with its instance
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerLog {
+    pub instance: String,
+    pub line: String,
+}
+
+/// Severity of a line of server output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputSeverity {
+    Info,
+    Warning,
+    Error,
+    /// The server cannot continue
+    Fatal,
+}
+
+/// Inference stage a throughput figure refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ThroughputStage {
+    Prefill,
+    Decode,
+}
+
+/// Line of `flm serve` output, recognized by `server::output::parse_line`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum FlmEvent {
+    #[serde(rename_all = "camelCase")]
+    Loading {
+        model: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    LoadProgress {
+        percent: u8,
+    },
+    #[serde(rename_all = "camelCase")]
+    ModelLoaded {
+        model: Option<String>,
+    },
+    /// The server accepts requests
+    #[serde(rename_all = "camelCase")]
+    Ready {
+        port: Option<u16>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Request {
+        method: String,
+        path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Throughput {
+        stage: ThroughputStage,
+        tokens_per_second: f32,
+    },
+    Warning {
+        message: String,
+    },
+    Error {
+        message: String,
+    },
+    Fatal {
+        message: String,
+    },
+    /// Anything else
+    Output {
+        message: String,
+    },
+}
+
+impl FlmEvent {
+    pub fn severity(&self) -> OutputSeverity {
+        match self {
+            Self::Warning { .. } => OutputSeverity::Warning,
+            Self::Error { .. } => OutputSeverity::Error,
+            Self::Fatal { .. } => OutputSeverity::Fatal,
+            _ => OutputSeverity::Info,
+        }
+    }
+}
+
+/// Payload of the parsed output event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerOutput {
+    pub instance: String,
+    pub severity: OutputSeverity,
+    pub event: FlmEvent,
+}
+
+/// Boundary of a schedule window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TransitionKind {
+    Start,
+    Stop,
+}
+
+/// Next start or stop planned by the scheduler
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTransition {
+    pub kind: TransitionKind,
+    /// Local time as `YYYY-MM-DDTHH:MM:SS`
+    pub at: chrono::NaiveDateTime,
+    /// Index of the window in the schedule
+    pub window: usize,
+    /// Model started, preset name when the window uses one
+    pub model: Option<String>,
+}
+
+/// Step of a hot reconfiguration, see `ServerInstance::reconfigure`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "phase", content = "message", rename_all = "camelCase")]
+pub enum ReconfigurePhase {
+    /// Change recorded, waiting for more changes to coalesce
+    Queued,
+    /// Same command line, or no server to restart: stored for the next start
+    Skipped,
+    /// Stopping the server to apply the change
+    Restarting,
+    Applied,
+    Failed(String),
+}
+
+/// Payload of the reconfiguration event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconfigureEvent {
+    pub instance: String,
+    #[serde(flatten)]
+    pub phase: ReconfigurePhase,
+}
+
+/// Snapshot of the supervised server, broadcast on every state change
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerState {
+    /// Instance the state belongs to
+    pub id: String,
+    pub status: ServerStatus,
+    pub health: Option<ServerHealth>,
+    pub model: String,
+    pub options: ServerOptions,
+    pub pid: Option<u32>,
+    pub exit_code: Option<i32>,
+    pub restart: Option<PendingRestart>,
+    pub failure: Option<FailureReason>,
+    /// The process was found running at startup and adopted, it has no stdin pipe
+    pub adopted: bool,
+    /// Pending decision about a server found at startup
+    pub orphan: Option<OrphanServer>,
+    /// Model load progress reported by the server while starting
+    pub load_percent: Option<u8>,
+    /// Stopped after the idle timeout
+    pub idle: bool,
+    /// What ended the last process, set once the instance is stopped
+    pub stopped_by: Option<StopCause>,
+    /// Start of the process, or of its adoption, in milliseconds since the epoch
+    pub started_at: Option<u64>,
+}
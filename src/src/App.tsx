@@ -9,6 +9,7 @@ import { AboutView } from "./components/views/AboutView";
 import { ConfigService } from "./services/config";
 import { AppProvider, useAppContext } from "./contexts";
 import { TooltipProvider } from "./components/ui/tooltip";
+import { MemoryWarningDialog } from "./components/shared/MemoryWarningDialog";
 
 // Wrappers
 function ChatViewWrapper() {
@@ -52,10 +53,11 @@ function ServerViewWrapper() {
 }
 
 function ModelsWrapper() {
-  const { installedModels, loadInstalledModels, hardwareInfo } = useAppContext();
+  const { installedModels, loadInstalledModels, hardwareInfo, downloads } = useAppContext();
   return (
     <ModelsView
       installedModels={installedModels}
+      downloads={downloads}
       onRefresh={() => loadInstalledModels(true)}
       hardwareInfo={hardwareInfo}
     />
@@ -93,7 +95,15 @@ const TAB_COMPONENTS: Record<string, React.ComponentType> = {
 };
 
 function AppContent() {
-  const { activeTab, setActiveTab, serverStatus, selectedModel } = useAppContext();
+  const {
+    activeTab,
+    setActiveTab,
+    serverStatus,
+    selectedModel,
+    memoryWarning,
+    confirmMemoryWarning,
+    dismissMemoryWarning,
+  } = useAppContext();
 
   const renderContent = () => {
     const Component = TAB_COMPONENTS[activeTab] || ChatViewWrapper;
@@ -113,6 +123,11 @@ function AppContent() {
         </div>
       </div>
       <StatusBar serverStatus={serverStatus} selectedModel={selectedModel} version={ConfigService.getAppVersion()} />
+      <MemoryWarningDialog
+        warning={memoryWarning}
+        onConfirm={confirmMemoryWarning}
+        onCancel={dismissMemoryWarning}
+      />
     </div>
   );
 }
@@ -0,0 +1,102 @@
+import { AlertTriangle } from "lucide-react";
+import {
+    Dialog,
+    DialogContent,
+    DialogHeader,
+    DialogTitle,
+    DialogDescription,
+    DialogFooter,
+} from "../ui/dialog";
+import { Button } from "../ui/button";
+import { useTranslation } from "react-i18next";
+import { formatFileSize } from "../../lib/formatters";
+import type { MemoryEstimate, MemoryVerdict, MemoryWarning } from "../../types";
+
+export const VERDICT_COLORS: Record<MemoryVerdict, string> = {
+    fits: "text-green-500",
+    tight: "text-yellow-500",
+    wontFit: "text-red-500",
+};
+
+/**
+ * Rows of a memory estimate, side models only when enabled
+ */
+export function MemoryBreakdown({ estimate }: { estimate: MemoryEstimate }) {
+    const { t } = useTranslation();
+    const rows: [string, number][] = [
+        [t("memory.weights"), estimate.weightsBytes],
+        [t("memory.kv_cache", { ctx: estimate.ctxLen }), estimate.kvCacheBytes],
+        [t("memory.asr"), estimate.asrBytes],
+        [t("memory.embed"), estimate.embedBytes],
+        [t("memory.overhead"), estimate.overheadBytes],
+    ];
+
+    return (
+        <div className="space-y-1 text-xs font-mono">
+            {rows
+                .filter(([, bytes]) => bytes > 0)
+                .map(([label, bytes]) => (
+                    <div key={label} className="flex justify-between text-muted-foreground">
+                        <span>{label}</span>
+                        <span>{formatFileSize(bytes)}</span>
+                    </div>
+                ))}
+            <div className="flex justify-between font-medium text-foreground border-t border-border pt-1">
+                <span>{t("memory.total")}</span>
+                <span>{formatFileSize(estimate.totalBytes)}</span>
+            </div>
+            <div className="flex justify-between text-muted-foreground">
+                <span>{t("memory.shared")}</span>
+                <span>{formatFileSize(estimate.sharedMemoryBytes)}</span>
+            </div>
+            <div className="flex justify-between text-muted-foreground">
+                <span>{t("memory.available")}</span>
+                <span>{formatFileSize(estimate.availableBytes)}</span>
+            </div>
+        </div>
+    );
+}
+
+interface MemoryWarningDialogProps {
+    warning: MemoryWarning | null;
+    onConfirm: () => void;
+    onCancel: () => void;
+}
+
+/**
+ * Asks before starting a configuration that won't fit in the shared memory
+ */
+export function MemoryWarningDialog({ warning, onConfirm, onCancel }: MemoryWarningDialogProps) {
+    const { t } = useTranslation();
+
+    return (
+        <Dialog open={warning !== null} onOpenChange={(open) => !open && onCancel()}>
+            <DialogContent>
+                <DialogHeader>
+                    <DialogTitle className="flex items-center gap-2">
+                        <AlertTriangle size={18} className="text-red-500" />
+                        {t("memory.warning_title")}
+                    </DialogTitle>
+                    {warning && (
+                        <DialogDescription className="pt-2">
+                            {t("memory.warning_desc", {
+                                model: warning.model || t("presets.audio_only"),
+                                total: formatFileSize(warning.estimate.totalBytes),
+                                shared: formatFileSize(warning.estimate.sharedMemoryBytes),
+                            })}
+                        </DialogDescription>
+                    )}
+                </DialogHeader>
+                {warning && <MemoryBreakdown estimate={warning.estimate} />}
+                <DialogFooter>
+                    <Button variant="secondary" onClick={onCancel}>
+                        {t("common.cancel")}
+                    </Button>
+                    <Button variant="destructive" onClick={onConfirm}>
+                        {t("memory.start_anyway")}
+                    </Button>
+                </DialogFooter>
+            </DialogContent>
+        </Dialog>
+    );
+}
@@ -1,4 +1,4 @@
-import { RefreshCw, Trash2, Download } from "lucide-react";
+import { RefreshCw, Trash2, Download, X } from "lucide-react";
 import { Badge } from "../ui/badge";
 import { Button } from "../ui/button";
 import {
@@ -24,6 +24,7 @@ interface ModelCardProps {
     downloadProgress?: number;
     downloadStatus?: string;
     onDownload?: (modelName: string) => void;
+    onCancelDownload?: (modelName: string) => void;
     onDelete?: (modelName: string) => void;
     disabled?: boolean;
 }
@@ -40,6 +41,7 @@ export function ModelCard({
     downloadProgress = 0,
     downloadStatus = "",
     onDownload,
+    onCancelDownload,
     onDelete,
     disabled = false,
 }: ModelCardProps) {
@@ -107,6 +109,17 @@ export function ModelCard({
                 {isDownloading && (
                     <DownloadProgress progress={downloadProgress} status={downloadStatus} />
                 )}
+                {isDownloading && onCancelDownload && (
+                    <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => onCancelDownload(model.name)}
+                        className="h-8 w-8 text-muted-foreground hover:text-red-400"
+                        title={t("models.download_cancel")}
+                    >
+                        <X size={16} />
+                    </Button>
+                )}
 
                 {/* Action Buttons */}
                 {!isDownloading && onDownload && (
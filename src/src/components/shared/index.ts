@@ -1,6 +1,7 @@
 // Shared components - Reusable app-specific components
 export { GenericAlertDialog } from "./GenericAlertDialog";
 export { LogsViewer } from "./LogsViewer";
+export { MemoryBreakdown, MemoryWarningDialog } from "./MemoryWarningDialog";
 export { ModelCard } from "./ModelCard";
 export { ModelInfoDialog } from "./ModelInfoDialog";
 export { ServerOptionsForm } from "./ServerOptionsForm";
@@ -1,8 +1,11 @@
 import { RefreshCw, HardDrive, Download } from "lucide-react";
 import { useEffect, useState } from "react";
-import { FlmService } from "../../services/flm";
+import { FlmService, inventoryModels } from "../../services/flm";
 import { NotificationService } from "../../services/notification";
-import type { FlmModel, HardwareInfo } from "../../types";
+import { listen } from "@tauri-apps/api/event";
+import type { DownloadError, DownloadJob, FlmModel, HardwareInfo, ModelInventory } from "../../types";
+import { DOWNLOAD_EVENT, MODELS_EVENT } from "../../types";
+import { formatFileSize } from "../../lib/formatters";
 import { Card, CardContent } from "../ui/card";
 import { Button } from "../ui/button";
 import { ScrollArea } from "../ui/scroll-area";
@@ -12,17 +15,16 @@ import { ModelCard } from "../shared/ModelCard";
 
 interface ModelsViewProps {
     installedModels: FlmModel[];
+    // Latest state of each download, by model name
+    downloads: Record<string, DownloadJob>;
     onRefresh: () => void;
     hardwareInfo: HardwareInfo | null;
 }
 
-export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsViewProps) => {
+export const ModelsView = ({ installedModels, downloads, onRefresh, hardwareInfo }: ModelsViewProps) => {
     const { t } = useTranslation();
     const [availableModels, setAvailableModels] = useState<FlmModel[]>([]);
     const [loading, setLoading] = useState(false);
-    const [downloadingModel, setDownloadingModel] = useState<string | null>(null);
-    const [downloadProgress, setDownloadProgress] = useState<number>(0);
-    const [downloadStatus, setDownloadStatus] = useState<string>("");
     const [alertOpen, setAlertOpen] = useState(false);
     const [alertMessage, setAlertMessage] = useState("");
 
@@ -41,6 +43,23 @@ export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsV
         loadModels();
     }, []);
 
+    // A finished download moves the model to the installed list
+    useEffect(() => {
+        const unlisten = listen<DownloadJob>(DOWNLOAD_EVENT, (event) => {
+            if (event.payload.status === "completed") {
+                loadModels(true);
+            }
+        });
+        // Pulls and removals made outside the app, or a new catalog after an FLM upgrade
+        const unlistenModels = listen<ModelInventory>(MODELS_EVENT, (event) => {
+            setAvailableModels(inventoryModels(event.payload, "not-installed"));
+        });
+        return () => {
+            unlisten.then((f) => f());
+            unlistenModels.then((f) => f());
+        };
+    }, []);
+
     const handleDelete = async (name: string) => {
         try {
             setLoading(true);
@@ -65,84 +84,61 @@ export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsV
     };
 
     const handleDownload = async (modelName: string) => {
-        if (downloadingModel) return;
-        setDownloadingModel(modelName);
-        setDownloadProgress(0);
-        setDownloadStatus(t("models.download_starting"));
-
-        NotificationService.send(
-            t("app.notification_model_download_start_title"),
-            t("app.notification_model_download_start_body", { model: modelName })
-        );
-
-        let totalFiles = 1;
-        let baseProgress = 0;
-        let currentFileProgress = 0;
-
         try {
-            await FlmService.pullModel(modelName, (data) => {
-                const cleanData = data.replace(/^\[FLM\]\s+/, "").trim();
-
-                // Check for "Downloading X/Y" to set total files
-                const fileCountMatch = cleanData.match(/Downloading (\d+)\/(\d+):/);
-                if (fileCountMatch) {
-                    const current = parseInt(fileCountMatch[1]);
-                    const total = parseInt(fileCountMatch[2]);
-                    totalFiles = total;
-                    baseProgress = ((current - 1) / total) * 100;
-                    currentFileProgress = 0;
-                    setDownloadStatus(t("models.download_file_progress", { current, total }));
-                }
+            await FlmService.queueDownload(modelName);
+        } catch (error) {
+            setAlertMessage(describeDownloadError(error as DownloadError));
+            setAlertOpen(true);
+        }
+    };
 
-                // Check for "Overall progress: P%"
-                const overallMatch = cleanData.match(/Overall progress:\s*([\d.]+)%/);
-                if (overallMatch) {
-                    baseProgress = parseFloat(overallMatch[1]);
-                    currentFileProgress = 0;
-                }
+    const describeDownloadError = (error: DownloadError) => {
+        if (error.kind !== "insufficientSpace") {
+            return t("models.download_error", { error: error.message ?? String(error) });
+        }
+        const { model, requiredBytes, availableBytes, removable } = error.message;
+        let text = t("models.error_insufficient_space", {
+            model,
+            available: formatFileSize(availableBytes),
+            required: formatFileSize(requiredBytes),
+        });
+        if (removable.length > 0) {
+            text += " " + t("models.error_insufficient_space_remove", {
+                models: removable.map((m) => m.name).join(", "),
+                size: formatFileSize(removable.reduce((total, m) => total + m.sizeBytes, 0)),
+            });
+        }
+        return text;
+    };
 
-                // Check for "Downloading: P%"
-                const downloadingMatch = cleanData.match(/Downloading:\s*([\d.]+)%/);
-                if (downloadingMatch) {
-                    currentFileProgress = parseFloat(downloadingMatch[1]);
-                }
+    const handleCancelDownload = async (modelName: string) => {
+        try {
+            await FlmService.cancelDownload(modelName);
+        } catch (error) {
+            console.error("Failed to cancel download:", error);
+        }
+    };
 
-                const totalProgress = Math.min(100, baseProgress + currentFileProgress / totalFiles);
-                setDownloadProgress(Math.round(totalProgress));
+    const isDownloading = (name: string) => {
+        const status = downloads[name]?.status;
+        return status === "queued" || status === "downloading" || status === "retrying";
+    };
 
-                if (
-                    !cleanData.startsWith("Downloading:") &&
-                    !cleanData.startsWith("Overall progress:") &&
-                    cleanData.length > 0
-                ) {
-                    setDownloadStatus(
-                        cleanData.substring(0, 50) + (cleanData.length > 50 ? "..." : "")
-                    );
+    const downloadStatus = (job: DownloadJob | undefined) => {
+        if (!job) return "";
+        const speed = job.bytesPerSec ? ` • ${formatFileSize(job.bytesPerSec)}/s` : "";
+        switch (job.status) {
+            case "queued":
+                return t("models.download_queued");
+            case "retrying":
+                return t("models.download_retrying", { attempt: job.attempts + 1 });
+            case "downloading":
+                if (job.currentFile && job.totalFiles) {
+                    return t("models.download_file_progress", { current: job.currentFile, total: job.totalFiles }) + speed;
                 }
-            });
-
-            setDownloadStatus(t("models.download_complete"));
-            setDownloadProgress(100);
-            // Notify download complete
-            NotificationService.send(
-                t("app.notification_model_download_complete_title"),
-                t("app.notification_model_download_complete_body", { model: modelName })
-            );
-            setTimeout(() => {
-                setDownloadingModel(null);
-                setDownloadProgress(0);
-                setDownloadStatus("");
-                onRefresh();
-                loadModels(true);
-            }, 1000);
-        } catch (error) {
-            // Notify download error
-            NotificationService.send(
-                t("app.notification_model_download_error_title"),
-                t("app.notification_model_download_error_body", { model: modelName })
-            );
-            setDownloadStatus(t("models.download_error", { error }));
-            setTimeout(() => setDownloadingModel(null), 3000);
+                return (job.percent === null ? t("models.download_starting") : t("models.download_downloading")) + speed;
+            default:
+                return "";
         }
     };
 
@@ -170,7 +166,7 @@ export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsV
                         onRefresh();
                         loadModels(true);
                     }}
-                    disabled={loading || !!downloadingModel}
+                    disabled={loading}
                     className="gap-2 bg-secondary text-secondary-foreground hover:bg-secondary/80 border border-border"
                 >
                     <RefreshCw size={16} className={loading ? "animate-spin" : ""} />
@@ -191,7 +187,7 @@ export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsV
                             model={model}
                             isTooLarge={isTooLarge(model)}
                             onDelete={handleDelete}
-                            disabled={!!downloadingModel}
+                            disabled={isDownloading(model.name)}
                         />
                     )}
                 />
@@ -208,11 +204,11 @@ export const ModelsView = ({ installedModels, onRefresh, hardwareInfo }: ModelsV
                             model={model}
                             isTooLarge={isTooLarge(model)}
                             isInstalled={isInstalled(model.name)}
-                            isDownloading={downloadingModel === model.name}
-                            downloadProgress={downloadProgress}
-                            downloadStatus={downloadStatus}
+                            isDownloading={isDownloading(model.name)}
+                            downloadProgress={Math.round(downloads[model.name]?.percent ?? 0)}
+                            downloadStatus={downloadStatus(downloads[model.name])}
                             onDownload={handleDownload}
-                            disabled={!!downloadingModel}
+                            onCancelDownload={handleCancelDownload}
                         />
                     )}
                 />
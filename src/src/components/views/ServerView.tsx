@@ -5,12 +5,15 @@ import { Input } from "../ui/input";
 import { Select, SelectContent, SelectGroup, SelectItem, SelectLabel, SelectTrigger, SelectValue } from "../ui/select";
 import { Switch } from "../ui/switch";
 import { Accordion, AccordionContent, AccordionItem, AccordionTrigger } from "../ui/accordion";
+import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { LogsViewer } from "../shared/LogsViewer";
 import { InfoTooltip } from "../shared/InfoTooltip";
+import { MemoryBreakdown, VERDICT_COLORS } from "../shared/MemoryWarningDialog";
+import { FlmService } from "../../services/flm";
 import { getAllPresets, isPresetId, findPresetById, getPresetDisplayName } from "../../lib/presets";
 import { DEFAULT_PRESETS_CONFIG } from "../../types";
-import type { FlmModel, ServerOptions, ServerStatus, PerformanceMode } from "../../types";
+import type { FlmModel, MemoryEstimate, ServerOptions, ServerStatus, PerformanceMode } from "../../types";
 
 interface ServerViewProps {
     serverStatus: ServerStatus;
@@ -36,6 +39,21 @@ export const ServerView = ({
     setOptions
 }: ServerViewProps) => {
     const { t } = useTranslation();
+    const [estimate, setEstimate] = useState<MemoryEstimate | null>(null);
+
+    // Follow the memory needed by the selection, presets carry their own model
+    useEffect(() => {
+        const model = isPresetId(selectedModel)
+            ? findPresetById(selectedModel, DEFAULT_PRESETS_CONFIG)?.model ?? ""
+            : selectedModel;
+        if (!model && !options.asr && !options.embed) {
+            setEstimate(null);
+            return;
+        }
+        FlmService.estimateMemory(model, options)
+            .then(setEstimate)
+            .catch(() => setEstimate(null));
+    }, [selectedModel, options]);
 
     const handleOptionChange = (key: keyof ServerOptions, value: ServerOptions[keyof ServerOptions]) => {
         setOptions(prev => ({ ...prev, [key]: value }));
@@ -155,6 +173,17 @@ export const ServerView = ({
                                     </SelectContent>
                                 </Select>
                             </div>
+                            {estimate && (
+                                <div className="space-y-2">
+                                    <div className="flex items-center justify-between text-xs">
+                                        <span className="font-medium text-muted-foreground">{t('memory.title')}</span>
+                                        <span className={`font-medium ${VERDICT_COLORS[estimate.verdict]}`}>
+                                            {t(`memory.verdict_${estimate.verdict}`)}
+                                        </span>
+                                    </div>
+                                    <MemoryBreakdown estimate={estimate} />
+                                </div>
+                            )}
                         </CardContent>
                     </Card>
 
@@ -4,10 +4,15 @@ import { Switch } from "../ui/switch";
 import { useTranslation } from "react-i18next";
 import { InfoTooltip } from "../shared/InfoTooltip";
 import { getAvailableLanguages } from "../../i18n";
-import type { Theme } from "../../types";
+import type { StartOnLaunch, Theme } from "../../types";
+import { DEFAULT_SYSTEM_PRESETS } from "../../types";
+import { FlmService } from "../../services/flm";
 import { enable, disable, isEnabled } from '@tauri-apps/plugin-autostart';
 import { useEffect, useState } from 'react';
 
+const LAST_MODEL = "last";
+const LAUNCH_DELAYS = [0, 5, 10, 30, 60];
+
 const SettingItem = ({
     label,
     description,
@@ -41,11 +46,35 @@ export const SettingsView = ({
 }: SettingsViewProps) => {
     const { t, i18n } = useTranslation();
     const [autostartEnabled, setAutostartEnabled] = useState(false);
+    const [startOnLaunch, setStartOnLaunch] = useState<StartOnLaunch | null>(null);
+    const [hideUnfitModels, setHideUnfitModels] = useState(false);
 
     useEffect(() => {
         isEnabled().then(setAutostartEnabled).catch(console.error);
+        FlmService.getStartOnLaunch().then(setStartOnLaunch).catch(console.error);
+        FlmService.getHideUnfitModels().then(setHideUnfitModels).catch(console.error);
     }, []);
 
+    const updateStartOnLaunch = async (changes: Partial<StartOnLaunch>) => {
+        if (!startOnLaunch) return;
+        const next = { ...startOnLaunch, ...changes };
+        try {
+            await FlmService.setStartOnLaunch(next);
+            setStartOnLaunch(next);
+        } catch (error) {
+            console.error('Failed to save start on launch:', error);
+        }
+    };
+
+    const toggleHideUnfitModels = async (checked: boolean) => {
+        try {
+            await FlmService.setHideUnfitModels(checked);
+            setHideUnfitModels(checked);
+        } catch (error) {
+            console.error('Failed to save hide unfit models:', error);
+        }
+    };
+
     const toggleAutostart = async (checked: boolean) => {
         try {
             if (checked) {
@@ -98,6 +127,58 @@ export const SettingsView = ({
                                 onCheckedChange={toggleAutostart}
                             />
                         </SettingItem>
+                        <SettingItem label={t('settings.start_server_on_launch')} description={t('settings.start_server_on_launch_desc')}>
+                            <Switch
+                                checked={startOnLaunch?.enabled ?? false}
+                                disabled={!startOnLaunch}
+                                onCheckedChange={(enabled) => updateStartOnLaunch({ enabled })}
+                            />
+                        </SettingItem>
+                        {startOnLaunch?.enabled && (
+                            <>
+                                <SettingItem label={t('settings.start_server_target')}>
+                                    <Select
+                                        value={startOnLaunch.preset ?? LAST_MODEL}
+                                        onValueChange={(value) => updateStartOnLaunch({ preset: value === LAST_MODEL ? null : value })}
+                                    >
+                                        <SelectTrigger className="w-40">
+                                            <SelectValue />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            <SelectItem value={LAST_MODEL}>{t('settings.start_server_last_model')}</SelectItem>
+                                            {DEFAULT_SYSTEM_PRESETS.map((preset) => (
+                                                <SelectItem key={preset.id} value={preset.id}>
+                                                    {preset.nameKey ? t(preset.nameKey) : preset.name}
+                                                </SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                </SettingItem>
+                                <SettingItem label={t('settings.start_server_delay')} description={t('settings.start_server_delay_desc')}>
+                                    <Select
+                                        value={String(startOnLaunch.delaySecs)}
+                                        onValueChange={(value) => updateStartOnLaunch({ delaySecs: Number(value) })}
+                                    >
+                                        <SelectTrigger className="w-40">
+                                            <SelectValue />
+                                        </SelectTrigger>
+                                        <SelectContent>
+                                            {LAUNCH_DELAYS.map((delay) => (
+                                                <SelectItem key={delay} value={String(delay)}>
+                                                    {t('settings.start_server_delay_value', { count: delay })}
+                                                </SelectItem>
+                                            ))}
+                                        </SelectContent>
+                                    </Select>
+                                </SettingItem>
+                            </>
+                        )}
+                        <SettingItem label={t('settings.hide_unfit_models')} description={t('settings.hide_unfit_models_desc')}>
+                            <Switch
+                                checked={hideUnfitModels}
+                                onCheckedChange={toggleHideUnfitModels}
+                            />
+                        </SettingItem>
                         <SettingItem label={t('settings.theme')}>
                             <Select
                                 value={theme}
@@ -5,7 +5,7 @@ import { useServerManager } from "../hooks/useServerManager";
 import { useTrayMenu } from "../hooks/useTrayMenu";
 import { ConfigService } from "../services/config";
 import { NotificationService } from "../services/notification";
-import type { Theme, ServerStatus, ServerOptions, FlmModel, HardwareInfo } from "../types";
+import type { Theme, ServerStatus, ServerOptions, FlmModel, HardwareInfo, DownloadJob, MemoryWarning } from "../types";
 
 interface AppContextType {
     // Config
@@ -23,6 +23,7 @@ interface AppContextType {
     selectedModel: string;
     setSelectedModel: (model: string) => void;
     hardwareInfo: HardwareInfo | null;
+    downloads: Record<string, DownloadJob>;
     loadInstalledModels: (force?: boolean) => void;
     loadHardwareInfo: (force?: boolean) => Promise<void>;
 
@@ -34,6 +35,9 @@ interface AppContextType {
     handleToggleServer: (options?: ServerOptions) => Promise<void>;
     addLog: (log: string) => void;
     clearLogs: () => void;
+    memoryWarning: MemoryWarning | null;
+    confirmMemoryWarning: () => Promise<void>;
+    dismissMemoryWarning: () => void;
 
     // Navigation
     activeTab: string;
@@ -86,11 +90,7 @@ export function AppProvider({ children }: AppProviderProps) {
 
     // Tray menu sync
     useTrayMenu({
-        serverStatus: server.serverStatus,
         selectedModel: models.selectedModel,
-        installedModels: models.installedModels,
-        availableModels: models.availableModels,
-        runnableModels: models.runnableModels,
         serverOptions: server.serverOptions,
     });
 
@@ -118,6 +118,7 @@ export function AppProvider({ children }: AppProviderProps) {
         selectedModel: models.selectedModel,
         setSelectedModel: models.setSelectedModel,
         hardwareInfo: models.hardwareInfo,
+        downloads: models.downloads,
         loadInstalledModels: models.loadInstalledModels,
         loadHardwareInfo: models.loadHardwareInfo,
 
@@ -129,6 +130,9 @@ export function AppProvider({ children }: AppProviderProps) {
         handleToggleServer: server.handleToggleServer,
         addLog: server.addLog,
         clearLogs: server.clearLogs,
+        memoryWarning: server.memoryWarning,
+        confirmMemoryWarning: server.confirmMemoryWarning,
+        dismissMemoryWarning: server.dismissMemoryWarning,
 
         // Navigation
         activeTab,
@@ -1,10 +1,11 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { useTranslation } from "react-i18next";
-import { FlmService } from "../services/flm";
+import { FlmService, inventoryModels } from "../services/flm";
 import { NotificationService } from "../services/notification";
 import { isPresetId } from "../lib/presets";
-import type { FlmModel, HardwareInfo } from "../types";
+import type { DownloadJob, FlmModel, HardwareInfo, ModelInventory } from "../types";
+import { DOWNLOAD_EVENT, MODELS_EVENT } from "../types";
 
 interface UseModelsManagerProps {
     flmPath: string;
@@ -19,6 +20,8 @@ interface UseModelsManagerReturn {
     selectedModel: string;
     setSelectedModel: (model: string) => void;
     hardwareInfo: HardwareInfo | null;
+    // Latest state of each download, by model name
+    downloads: Record<string, DownloadJob>;
     loadInstalledModels: (force?: boolean) => void;
     loadAvailableModels: (force?: boolean) => void;
     loadHardwareInfo: (force?: boolean) => Promise<void>;
@@ -35,29 +38,33 @@ export function useModelsManager({
     const [availableModels, setAvailableModels] = useState<FlmModel[]>([]);
     const [selectedModel, setSelectedModel] = useState<string>(initialSelectedModel);
     const [hardwareInfo, setHardwareInfo] = useState<HardwareInfo | null>(null);
+    const [downloads, setDownloads] = useState<Record<string, DownloadJob>>({});
 
     // Refs for event listeners
+    const downloadsRef = useRef(downloads);
     const installedModelsRef = useRef(installedModels);
     useEffect(() => {
         installedModelsRef.current = installedModels;
     }, [installedModels]);
 
-    const loadInstalledModels = useCallback((force = false) => {
-        FlmService.listModels("installed", force).then((models) => {
-            setInstalledModels(models);
-
-            const runnable = models.filter(m => !m.isEmbed && !m.isAudio);
-            setRunnableModels(runnable);
-
-            setSelectedModel((prev) => {
-                if (prev && isPresetId(prev)) return prev;
-                if (prev && runnable.some((m) => m.name === prev)) return prev;
-                if (prev === "") return "";
-                return runnable.length > 0 ? runnable[0].name : "";
-            });
+    const applyInstalledModels = useCallback((models: FlmModel[]) => {
+        setInstalledModels(models);
+
+        const runnable = models.filter(m => !m.isEmbed && !m.isAudio && !m.issue);
+        setRunnableModels(runnable);
+
+        setSelectedModel((prev) => {
+            if (prev && isPresetId(prev)) return prev;
+            if (prev && runnable.some((m) => m.name === prev)) return prev;
+            if (prev === "") return "";
+            return runnable.length > 0 ? runnable[0].name : "";
         });
     }, []);
 
+    const loadInstalledModels = useCallback((force = false) => {
+        FlmService.listModels("installed", force).then(applyInstalledModels);
+    }, [applyInstalledModels]);
+
     const loadAvailableModels = useCallback((force = false) => {
         FlmService.listModels("not-installed", force).then((models) => {
             setAvailableModels(models);
@@ -86,23 +93,29 @@ export function useModelsManager({
         }
     }, [t, loadInstalledModels, loadAvailableModels]);
 
-    const handleDownloadModel = useCallback(async (modelName: string) => {
-        try {
+    // Notify the milestones of a download, retries stay silent until the last one fails
+    const handleDownloadChanged = useCallback((job: DownloadJob) => {
+        const previous = downloadsRef.current[job.model];
+        downloadsRef.current = { ...downloadsRef.current, [job.model]: job };
+        setDownloads(downloadsRef.current);
+        if (previous?.status === job.status) return;
+
+        if (job.status === "downloading" && job.attempts === 1) {
             NotificationService.send(
                 t("app.notification_model_download_start_title"),
-                t("app.notification_model_download_start_body", { model: modelName })
+                t("app.notification_model_download_start_body", { model: job.model })
             );
-            await FlmService.pullModel(modelName, () => { });
+        } else if (job.status === "completed") {
             NotificationService.send(
                 t("app.notification_model_download_complete_title"),
-                t("app.notification_model_download_complete_body", { model: modelName })
+                t("app.notification_model_download_complete_body", { model: job.model })
             );
             loadInstalledModels(true);
             loadAvailableModels(true);
-        } catch {
+        } else if (job.status === "failed") {
             NotificationService.send(
                 t("app.notification_model_download_error_title"),
-                t("app.notification_model_download_error_body", { model: modelName })
+                t("app.notification_model_download_error_body", { model: job.model })
             );
         }
     }, [t, loadInstalledModels, loadAvailableModels]);
@@ -114,6 +127,11 @@ export function useModelsManager({
         loadInstalledModels();
         loadAvailableModels();
         loadHardwareInfo();
+        // The queue survives restarts, pick up downloads left by the previous run
+        FlmService.listDownloads().then((jobs) => {
+            downloadsRef.current = Object.fromEntries(jobs.map((job) => [job.model, job]));
+            setDownloads(downloadsRef.current);
+        }).catch((error) => console.error("Failed to list downloads:", error));
     }, [flmPath, isConfigLoaded, loadInstalledModels, loadAvailableModels, loadHardwareInfo]);
 
     // Set initial selected model from config
@@ -129,15 +147,22 @@ export function useModelsManager({
             handleDeleteModel(event.payload);
         });
 
-        const unlistenDownload = listen<string>("request-download-model", (event) => {
-            handleDownloadModel(event.payload);
+        const unlistenDownload = listen<DownloadJob>(DOWNLOAD_EVENT, (event) => {
+            handleDownloadChanged(event.payload);
+        });
+
+        // The backend watches the model directory and the catalog, changes arrive already reconciled
+        const unlistenModels = listen<ModelInventory>(MODELS_EVENT, (event) => {
+            applyInstalledModels(inventoryModels(event.payload, "installed"));
+            setAvailableModels(inventoryModels(event.payload, "not-installed"));
         });
 
         return () => {
             unlistenDelete.then((f) => f());
             unlistenDownload.then((f) => f());
+            unlistenModels.then((f) => f());
         };
-    }, [handleDeleteModel, handleDownloadModel]);
+    }, [handleDeleteModel, handleDownloadChanged, applyInstalledModels]);
 
     return {
         installedModels,
@@ -146,6 +171,7 @@ export function useModelsManager({
         selectedModel,
         setSelectedModel,
         hardwareInfo,
+        downloads,
         loadInstalledModels,
         loadAvailableModels,
         loadHardwareInfo,
@@ -3,10 +3,65 @@ import { listen } from "@tauri-apps/api/event";
 import { useTranslation } from "react-i18next";
 import { FlmService } from "../services/flm";
 import { NotificationService } from "../services/notification";
-import type { ServerStatus, ServerOptions, FlmModel } from "../types";
-import { DEFAULT_SERVER_OPTIONS, DEFAULT_PRESETS_CONFIG } from "../types";
+import type {
+    ServerStatus,
+    ServerOptions,
+    ServerState,
+    ServerLog,
+    ServerError,
+    ReconfigureEvent,
+    ServerOutput,
+    ViewLogsRequest,
+    SupervisorStatus,
+    FlmModel,
+    MemoryWarning,
+} from "../types";
+import {
+    DEFAULT_SERVER_OPTIONS,
+    DEFAULT_PRESETS_CONFIG,
+    MAIN_INSTANCE,
+    SERVER_LOG_EVENT,
+    SERVER_PORT_EVENT,
+    SERVER_OUTPUT_EVENT,
+    SERVER_RECONFIGURE_EVENT,
+    SERVER_STATE_EVENT,
+} from "../types";
 import { isPresetId, findPresetById } from "../lib/presets";
 
+// Lines of a recorded session shown in the logs view
+const LOG_HISTORY_LINES = 1000;
+
+// Get actual model name (extract from preset if needed)
+function resolveModel(selection: string): string {
+    if (isPresetId(selection)) {
+        const preset = findPresetById(selection, DEFAULT_PRESETS_CONFIG);
+        return preset?.model || "";
+    }
+    return selection;
+}
+
+// Map the supervisor lifecycle onto the three states shown by the UI
+function toServerStatus(status: SupervisorStatus): ServerStatus {
+    switch (status) {
+        // The process is still alive while stopping
+        case "stopping":
+            return "running";
+        case "restarting":
+            return "starting";
+        case "failed":
+            return "stopped";
+        default:
+            return status;
+    }
+}
+
+function isServerError<K extends ServerError["kind"]>(
+    error: unknown,
+    kind: K
+): error is Extract<ServerError, { kind: K }> {
+    return typeof error === "object" && error !== null && (error as ServerError).kind === kind;
+}
+
 interface UseServerManagerProps {
     selectedModel: string;
     setSelectedModel: (model: string) => void;
@@ -24,6 +79,10 @@ interface UseServerManagerReturn {
     handleToggleServer: (options?: ServerOptions) => Promise<void>;
     addLog: (log: string) => void;
     clearLogs: () => void;
+    // Start held back because the configuration won't fit in the shared memory
+    memoryWarning: MemoryWarning | null;
+    confirmMemoryWarning: () => Promise<void>;
+    dismissMemoryWarning: () => void;
 }
 
 export function useServerManager({
@@ -37,14 +96,15 @@ export function useServerManager({
     const { t } = useTranslation();
     const [serverStatus, setServerStatus] = useState<ServerStatus>("stopped");
     const [logs, setLogs] = useState<string[]>([]);
+    const [memoryWarning, setMemoryWarning] = useState<MemoryWarning | null>(null);
     const [serverOptions, setServerOptions] = useState<ServerOptions>({
         ...DEFAULT_SERVER_OPTIONS,
         ...initialServerOptions,
     });
-    const [pendingRestart, setPendingRestart] = useState<ServerOptions | null>(null);
 
     // Refs pour les closures dans les event listeners
     const serverStatusRef = useRef(serverStatus);
+    const previousStatusRef = useRef<SupervisorStatus>("stopped");
     const selectedModelRef = useRef(selectedModel);
     const serverOptionsRef = useRef(serverOptions);
     const installedModelsRef = useRef(installedModels);
@@ -99,100 +159,224 @@ export function useServerManager({
         setLogs([]);
     }, []);
 
+    // Show the end of a recorded session, the logs outlive the app
+    const loadLogSession = useCallback(async (session: string) => {
+        try {
+            const page = await FlmService.tailServerLog(session, LOG_HISTORY_LINES);
+            setLogs(page.lines);
+        } catch (error) {
+            console.error("Failed to load server log:", error);
+        }
+    }, []);
+
+    const startServer = useCallback(
+        async (model: string, options: ServerOptions, instance: string = MAIN_INSTANCE) => {
+            const isMain = instance === MAIN_INSTANCE;
+            if (isMain) {
+                setServerStatus("starting");
+                setLogs([]);
+                addLog(t("app.log_starting_server", { model: model || "None" }));
+            }
+
+            try {
+                await FlmService.startServer(model, options, instance);
+            } catch (error) {
+                if (isMain) setServerStatus("stopped");
+                // Conflicts are already logged by the supervisor
+                if (isServerError(error, "portInUse")) {
+                    const { port, process, pid } = error.message;
+                    NotificationService.send(
+                        t("app.notification_port_in_use_title"),
+                        t("app.notification_port_in_use_body", {
+                            port,
+                            owner: process ?? (pid !== null ? `PID ${pid}` : "?"),
+                        })
+                    );
+                } else if (isServerError(error, "npuConflict")) {
+                    NotificationService.send(
+                        t("app.notification_npu_conflict_title"),
+                        t("app.notification_npu_conflict_body", {
+                            instance: error.message.instance,
+                            resource: t(`app.npu_resource_${error.message.resource}`),
+                        })
+                    );
+                } else {
+                    addLog(t("app.log_start_error", { error }));
+                }
+            }
+        },
+        [addLog, t]
+    );
+
     const handleToggleServer = useCallback(
         async (options?: ServerOptions) => {
             if (serverStatusRef.current === "running") {
                 try {
-                    await FlmService.stopServer(addLog);
+                    await FlmService.stopServer();
                 } catch (error) {
                     addLog(t("app.log_stop_error", { error }));
                 }
-            } else {
-                setServerStatus("starting");
-                setLogs([]);
+                return;
+            }
 
-                // Get actual model name (extract from preset if needed)
-                let actualModel = selectedModelRef.current;
-                if (isPresetId(actualModel)) {
-                    const preset = findPresetById(actualModel, DEFAULT_PRESETS_CONFIG);
-                    actualModel = preset?.model || "";
-                }
+            const actualModel = resolveModel(selectedModelRef.current);
+            const optionsToUse = options || serverOptionsRef.current;
+            // Without a catalog there is nothing to estimate, the start goes ahead
+            const estimate = await FlmService.estimateMemory(actualModel, optionsToUse).catch(() => null);
+            if (estimate?.verdict === "wontFit") {
+                setMemoryWarning({ instance: MAIN_INSTANCE, model: actualModel, options: optionsToUse, estimate });
+                return;
+            }
+            await startServer(actualModel, optionsToUse);
+        },
+        [addLog, startServer, t]
+    );
+
+    const confirmMemoryWarning = useCallback(async () => {
+        if (!memoryWarning) return;
+        setMemoryWarning(null);
+        await startServer(memoryWarning.model, memoryWarning.options, memoryWarning.instance);
+    }, [memoryWarning, startServer]);
 
-                addLog(t("app.log_starting_server", { model: actualModel || "None" }));
+    const dismissMemoryWarning = useCallback(() => {
+        setMemoryWarning(null);
+    }, []);
 
-                // Notify server starting (only if window not visible)
+    // Starts from the tray that won't fit wait for a confirmation in the window
+    useEffect(() => {
+        const unlisten = listen<MemoryWarning>("confirm-start", (event) => {
+            setMemoryWarning(event.payload);
+        });
+        return () => {
+            unlisten.then((f) => f());
+        };
+    }, []);
+
+    // Keep the supervisor launch config (used by the tray) in sync with the UI selection
+    useEffect(() => {
+        if (!isConfigLoaded) return;
+        FlmService.setServerLaunchConfig(resolveModel(selectedModel), serverOptions);
+    }, [isConfigLoaded, selectedModel, serverOptions]);
+
+    // Server state and logs broadcast by the Rust supervisor
+    useEffect(() => {
+        FlmService.listLogSessions(MAIN_INSTANCE).then(([latest]) => {
+            if (latest) loadLogSession(latest.id);
+        });
+
+        FlmService.getServerState().then((state) => {
+            previousStatusRef.current = state.status;
+            setServerStatus(toServerStatus(state.status));
+
+            if (state.orphan) {
                 NotificationService.send(
-                    t("app.notification_server_starting_title"),
-                    t("app.notification_server_starting_body", { model: actualModel || "None" })
+                    t("app.notification_orphan_server_title"),
+                    t("app.notification_orphan_server_body", { port: state.orphan.port })
                 );
+            }
+        });
 
-                try {
-                    const optionsToUse = options || serverOptionsRef.current;
-
-                    await FlmService.startServer(
-                        actualModel,
-                        optionsToUse,
-                        (log) => {
-                            addLog(log);
-                            if (log.includes("Enter 'exit' to stop the server:")) {
-                                setServerStatus("running");
-                                NotificationService.send(
-                                    t("app.notification_server_started_title"),
-                                    t("app.notification_server_started_body", {
-                                        model: actualModel || "None",
-                                    })
-                                );
-                            }
-                            if (log.includes("[SYSTEM] Server stopped with code")) {
-                                setServerStatus("stopped");
-                                if (!log.includes("code 0")) {
-                                    NotificationService.send(
-                                        t("app.notification_server_error_title"),
-                                        t("app.notification_server_error_body")
-                                    );
-                                } else {
-                                    NotificationService.send(
-                                        t("app.notification_server_stopped_title"),
-                                        t("app.notification_server_stopped_body")
-                                    );
-                                }
-                            }
-                        }
+        const unlistenState = listen<ServerState>(SERVER_STATE_EVENT, (event) => {
+            const state = event.payload;
+            // Additional instances are handled from the tray
+            if (state.id !== MAIN_INSTANCE) return;
+            const previous = previousStatusRef.current;
+            previousStatusRef.current = state.status;
+            setServerStatus(toServerStatus(state.status));
+
+            if (state.status === previous) return;
+            const model = state.model || "None";
+
+            if (state.status === "starting") {
+                NotificationService.send(
+                    t("app.notification_server_starting_title"),
+                    t("app.notification_server_starting_body", { model })
+                );
+            } else if (state.status === "running") {
+                NotificationService.send(
+                    t("app.notification_server_started_title"),
+                    t("app.notification_server_started_body", { model })
+                );
+            } else if (state.status === "restarting" && state.restart) {
+                NotificationService.send(
+                    t("app.notification_server_restarting_title"),
+                    t("app.notification_server_restarting_body", {
+                        attempt: state.restart.attempt,
+                        max: state.restart.maxRetries,
+                        delay: state.restart.delaySecs,
+                    })
+                );
+            } else if (state.status === "failed" && state.failure !== "launchFailed") {
+                // Launch failures are notified by the backend, the window may not exist yet
+                NotificationService.send(
+                    t("app.notification_server_failed_title"),
+                    t(state.failure === "crashLoop"
+                        ? "app.notification_server_crash_loop_body"
+                        : "app.notification_server_failed_body")
+                );
+            } else if (state.status === "stopped") {
+                if (state.idle) {
+                    NotificationService.send(
+                        t("app.notification_server_idle_title"),
+                        t("app.notification_server_idle_body")
+                    );
+                } else if (
+                    // Kills and signals leave no exit code, a requested stop may end with one
+                    state.stoppedBy === "exited" &&
+                    state.exitCode != null &&
+                    state.exitCode !== 0
+                ) {
+                    NotificationService.send(
+                        t("app.notification_server_error_title"),
+                        t("app.notification_server_error_body")
+                    );
+                } else {
+                    NotificationService.send(
+                        t("app.notification_server_stopped_title"),
+                        t("app.notification_server_stopped_body")
                     );
-                } catch (error) {
-                    setServerStatus("stopped");
-                    addLog(t("app.log_start_error", { error }));
                 }
             }
-        },
-        [addLog, t]
-    );
+        });
 
-    // Handle pending restart after server stops
-    useEffect(() => {
-        if (serverStatus === "stopped" && pendingRestart) {
-            const optionsToUse = pendingRestart;
-            setPendingRestart(null);
-            setTimeout(() => {
-                handleToggleServer(optionsToUse);
-            }, 300);
-        }
-    }, [serverStatus, pendingRestart, handleToggleServer]);
+        const unlistenLog = listen<ServerLog>(SERVER_LOG_EVENT, (event) => {
+            const { instance, line } = event.payload;
+            addLog(instance === MAIN_INSTANCE ? line : `[${instance}] ${line}`);
+        });
 
-    // Event listeners
-    useEffect(() => {
-        const unlistenStart = listen("request-start-server", () => {
-            if (serverStatusRef.current === "stopped") {
-                handleToggleServer();
-            }
+        // The supervisor moved the server away from a port held by another process
+        const unlistenPort = listen<number>(SERVER_PORT_EVENT, (event) => {
+            setServerOptions((prev) => ({ ...prev, port: event.payload }));
         });
 
-        const unlistenStop = listen("request-stop-server", () => {
-            if (serverStatusRef.current === "running") {
-                handleToggleServer();
+        const unlistenReconfigure = listen<ReconfigureEvent>(SERVER_RECONFIGURE_EVENT, (event) => {
+            const { instance, phase, message } = event.payload;
+            if (phase === "failed") {
+                addLog(`[ERROR] ${instance === MAIN_INSTANCE ? "" : `[${instance}] `}${message ?? ""}`);
             }
         });
 
+        // Surface errors the server cannot recover from
+        const unlistenOutput = listen<ServerOutput>(SERVER_OUTPUT_EVENT, (event) => {
+            const { instance, event: output } = event.payload;
+            if (instance !== MAIN_INSTANCE || output.kind !== "fatal") return;
+            NotificationService.send(
+                t("app.notification_server_fatal_title"),
+                t("app.notification_server_fatal_body", { message: output.message })
+            );
+        });
+
+        return () => {
+            unlistenState.then((f) => f());
+            unlistenLog.then((f) => f());
+            unlistenPort.then((f) => f());
+            unlistenReconfigure.then((f) => f());
+            unlistenOutput.then((f) => f());
+        };
+    }, [addLog, loadLogSession, t]);
+
+    // Event listeners
+    useEffect(() => {
         const unlistenSelectModel = listen<string>("select-model", async (event) => {
             const newSelection = event.payload;
 
@@ -223,45 +407,36 @@ export function useServerManager({
             setSelectedModel(newSelection);
             setServerOptions(newOptions);
 
+            // The backend coalesces rapid changes and restarts the server once
             if (serverStatusRef.current === "running") {
-                setPendingRestart(newOptions);
-                await FlmService.stopServer((log) => addLog(log));
+                await FlmService.reconfigureServer(resolveModel(newSelection), newOptions);
             }
         });
 
-        const unlistenViewLogs = listen("view-logs", () => {
+        const unlistenViewLogs = listen<ViewLogsRequest>("view-logs", async (event) => {
+            const { instance, session } = event.payload;
+            if (instance === MAIN_INSTANCE && session) {
+                await loadLogSession(session);
+            }
             onNavigateToLogs?.();
         });
 
-        const unlistenToggleAsr = listen("toggle-asr", async () => {
-            const newOptions = { ...serverOptionsRef.current, asr: !serverOptionsRef.current.asr };
-            setServerOptions(newOptions);
-
-            if (serverStatusRef.current === "running") {
-                setPendingRestart(newOptions);
-                await FlmService.stopServer((log) => addLog(log));
-            }
+        // The tray already reconfigured the server, only keep the options in sync
+        const unlistenToggleAsr = listen("toggle-asr", () => {
+            setServerOptions((prev) => ({ ...prev, asr: !prev.asr }));
         });
 
-        const unlistenToggleEmbed = listen("toggle-embed", async () => {
-            const newOptions = { ...serverOptionsRef.current, embed: !serverOptionsRef.current.embed };
-            setServerOptions(newOptions);
-
-            if (serverStatusRef.current === "running") {
-                setPendingRestart(newOptions);
-                await FlmService.stopServer((log) => addLog(log));
-            }
+        const unlistenToggleEmbed = listen("toggle-embed", () => {
+            setServerOptions((prev) => ({ ...prev, embed: !prev.embed }));
         });
 
         return () => {
-            unlistenStart.then((f) => f());
-            unlistenStop.then((f) => f());
             unlistenSelectModel.then((f) => f());
             unlistenViewLogs.then((f) => f());
             unlistenToggleAsr.then((f) => f());
             unlistenToggleEmbed.then((f) => f());
         };
-    }, [handleToggleServer, setSelectedModel, addLog, onNavigateToLogs]);
+    }, [setSelectedModel, onNavigateToLogs, loadLogSession]);
 
     return {
         serverStatus,
@@ -271,5 +446,8 @@ export function useServerManager({
         handleToggleServer,
         addLog,
         clearLogs,
+        memoryWarning,
+        confirmMemoryWarning,
+        dismissMemoryWarning,
     };
 }
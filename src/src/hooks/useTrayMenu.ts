@@ -1,7 +1,7 @@
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useTranslation } from "react-i18next";
-import type { ServerStatus, ServerOptions, FlmModel } from "../types";
+import type { ServerOptions } from "../types";
 import { DEFAULT_PRESETS_CONFIG } from "../types";
 import { getAllPresets, getPresetDisplayName } from "../lib/presets";
 import { FlmService } from "../services/flm";
@@ -12,20 +12,12 @@ interface TrayPreset {
 }
 
 interface UseTrayMenuProps {
-    serverStatus: ServerStatus;
     selectedModel: string;
-    installedModels: FlmModel[];
-    availableModels: FlmModel[];
-    runnableModels: FlmModel[];
     serverOptions: ServerOptions;
 }
 
 export function useTrayMenu({
-    serverStatus,
     selectedModel,
-    installedModels,
-    availableModels,
-    runnableModels,
     serverOptions,
 }: UseTrayMenuProps): void {
     const { t } = useTranslation();
@@ -49,12 +41,8 @@ export function useTrayMenu({
 
         invoke("update_tray_menu", {
             params: {
-                isRunning: serverStatus === "running",
                 selectedModel: selectedModel,
                 presets: presets,
-                installedModels: installedModels.map((m) => m.name),
-                availableModels: availableModels.map((m) => m.name),
-                startableModels: runnableModels.map((m) => m.name),
                 asrEnabled: serverOptions.asr,
                 embedEnabled: serverOptions.embed,
                 flmVersion: flmVersion,
@@ -62,9 +50,47 @@ export function useTrayMenu({
                     start: t("tray.start"),
                     stop: t("tray.stop"),
                     quit: t("tray.quit"),
+                    quitKeepServer: t("tray.quit_keep_server"),
+                    quitKeepServers: t("tray.quit_keep_servers"),
+                    stoppingServer: t("tray.stopping_server"),
                     settings: t("tray.settings"),
                     running: t("tray.server_running"),
                     stopped: t("tray.server_stopped"),
+                    stoppedIdle: t("tray.server_stopped_idle"),
+                    starting: t("tray.server_starting"),
+                    stopping: t("tray.server_stopping"),
+                    degraded: t("tray.server_degraded"),
+                    unreachable: t("tray.server_unreachable"),
+                    restarting: t("tray.server_restarting"),
+                    failed: t("tray.server_failed"),
+                    crashLoop: t("tray.server_crash_loop"),
+                    launchFailed: t("tray.server_launch_failed"),
+                    lastRunFailed: t("tray.last_run_failed"),
+                    diagnoses: {
+                        portInUse: t("diagnosis.port_in_use"),
+                        modelNotInstalled: t("diagnosis.model_not_installed"),
+                        npuBusy: t("diagnosis.npu_busy"),
+                        npuUnavailable: t("diagnosis.npu_unavailable"),
+                        outOfMemory: t("diagnosis.out_of_memory"),
+                        crashed: t("diagnosis.crashed"),
+                        unknown: t("diagnosis.unknown"),
+                    },
+                    restartPolicy: t("tray.restart_policy"),
+                    restartNever: t("tray.restart_never"),
+                    restartOnFailure: t("tray.restart_on_failure"),
+                    restartAlways: t("tray.restart_always"),
+                    maxRetries: t("tray.max_retries"),
+                    autoPort: t("tray.auto_port"),
+                    adopted: t("tray.server_adopted"),
+                    orphanFound: t("tray.orphan_found"),
+                    portInUse: t("tray.port_in_use"),
+                    adoptServer: t("tray.adopt_server"),
+                    terminateServer: t("tray.terminate_server"),
+                    useOtherPort: t("tray.use_other_port"),
+                    schedule: t("tray.schedule"),
+                    scheduleStart: t("tray.schedule_start"),
+                    scheduleStop: t("tray.schedule_stop"),
+                    weekdays: t("tray.weekdays", { returnObjects: true }) as string[],
                     viewLogs: t("tray.view_logs"),
                     features: t("tray.features"),
                     asr: t("tray.asr"),
@@ -76,9 +102,44 @@ export function useTrayMenu({
                     catalog: t("tray.catalog"),
                     startWithModel: t("tray.start_with_model"),
                     deleteModel: t("tray.delete_model"),
+                    confirm: t("tray.confirm"),
+                    cancel: t("common.cancel"),
+                    confirmDelete: t("tray.confirm_delete"),
+                    confirmTerminate: t("tray.confirm_terminate"),
+                    removalScheduled: t("tray.removal_scheduled"),
+                    undoRemoval: t("tray.undo_removal"),
+                    undoRemovalHint: t("tray.undo_removal_hint"),
+                    removalUndone: t("tray.removal_undone"),
+                    actionFailed: t("tray.action_failed"),
+                    verifyModel: t("tray.verify_model"),
+                    repairModels: t("tray.repair_models"),
+                    verifyPassed: t("tray.verify_passed"),
+                    verifyFailed: t("tray.verify_failed"),
+                    repairing: t("tray.repairing"),
                     downloadModel: t("tray.download_model"),
+                    badgeThink: t("tray.badge_think"),
+                    badgeVlm: t("tray.badge_vlm"),
+                    badgeEmbed: t("tray.badge_embed"),
+                    badgeAsr: t("tray.badge_asr"),
+                    wontFit: t("tray.wont_fit"),
+                    downloads: t("tray.downloads"),
+                    downloadQueued: t("tray.download_queued"),
+                    downloadRetrying: t("tray.download_retrying"),
+                    cancelDownload: t("tray.cancel_download"),
+                    downloading: t("tray.downloading"),
+                    uptime: t("tray.uptime"),
+                    error: t("tray.error"),
+                    tooltip: {
+                        running: t("tray.tooltip_running"),
+                        starting: t("tray.tooltip_starting"),
+                        stopping: t("tray.tooltip_stopping"),
+                        stopped: t("tray.tooltip_stopped"),
+                        idle: t("tray.tooltip_idle"),
+                        restarting: t("tray.tooltip_restarting"),
+                        failed: t("tray.tooltip_failed"),
+                    },
                 },
             },
         });
-    }, [serverStatus, selectedModel, installedModels, availableModels, runnableModels, serverOptions, flmVersion, t]);
+    }, [selectedModel, serverOptions, flmVersion, t]);
 }
@@ -41,7 +41,8 @@ export function formatTime(date: Date = new Date()): string {
  * Get CSS class for log line based on content
  */
 export function getLogColorClass(log: string): string {
-    if (log.includes("[ERROR]")) return "text-red-400";
+    if (log.includes("[ERROR]") || log.includes("[FLM ERR]")) return "text-red-400";
+    if (log.includes("[FLM WARN]")) return "text-orange-400";
     if (log.includes("[FLM]")) return "text-blue-400";
     if (log.includes("[SYSTEM]")) return "text-yellow-400";
     return "text-muted-foreground";
@@ -49,7 +49,18 @@ export const ConfigService = {
                 await mkdir("", { baseDir: BaseDirectory.AppConfig, recursive: true });
             }
 
-            await writeTextFile(CONFIG_FILENAME, JSON.stringify(config, null, 2), {
+            // Keep the keys written by the backend (e.g. restartPolicy)
+            let existing = {};
+            if (await exists(CONFIG_FILENAME, { baseDir: BaseDirectory.AppConfig })) {
+                try {
+                    existing = JSON.parse(await readTextFile(CONFIG_FILENAME, { baseDir: BaseDirectory.AppConfig }));
+                } catch {
+                    existing = {};
+                }
+            }
+
+            const merged = { ...existing, ...config };
+            await writeTextFile(CONFIG_FILENAME, JSON.stringify(merged, null, 2), {
                 baseDir: BaseDirectory.AppConfig,
             });
         } catch (error) {
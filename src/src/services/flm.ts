@@ -1,38 +1,77 @@
+import { invoke } from "@tauri-apps/api/core";
 import { Command, Child, TerminatedPayload } from "@tauri-apps/plugin-shell";
-import { readTextFile } from "@tauri-apps/plugin-fs";
-import { ConfigService } from "./config";
-import type { FlmModel, FlmStatus, HardwareInfo, ServerOptions } from "../types";
-import { MODEL_LIST_FILENAME } from "../types";
+import type {
+    DownloadJob,
+    FlmModel,
+    FlmStatus,
+    HardwareInfo,
+    InstanceConfig,
+    LogPage,
+    LogSession,
+    MemoryEstimate,
+    ModelCatalog,
+    ModelInfo,
+    ModelInventory,
+    OrphanAction,
+    Schedule,
+    ScheduledTransition,
+    ServerOptions,
+    ServerState,
+    SessionRecord,
+    StartOnLaunch,
+    VerifyReport,
+} from "../types";
 
 // Ré-export des types pour la compatibilité
-export type { FlmModel, FlmStatus, HardwareInfo, ServerOptions };
+export type { FlmModel, FlmStatus, HardwareInfo, ServerOptions, ServerState };
 
-interface ModelListJson {
-    model_path: string;
-    models: Record<string, Record<string, ModelJsonEntry>>;
-}
+let chatProcess: Child | null = null;
+let metadataCache: Record<string, FlmModel> | null = null;
+let hardwareInfoCache: HardwareInfo | null = null;
+
+function toFlmModel(model: ModelInfo): FlmModel {
+    // Format size to GB/MB
+    const sizeBytes = model.sizeBytes;
+    let sizeStr = "";
+    if (sizeBytes > 1024 * 1024 * 1024) {
+        sizeStr = `${(sizeBytes / (1024 * 1024 * 1024)).toFixed(1)}GB`;
+    } else {
+        sizeStr = `${(sizeBytes / (1024 * 1024)).toFixed(0)}MB`;
+    }
 
-interface ModelJsonEntry {
-    name: string;
-    url: string;
-    modified_at: string;
-    size: number;
-    default_context_length: number;
-    vlm?: boolean;
-    details: {
-        format: string;
-        family: string;
-        think: boolean;
-        parameter_size: string;
-        quantization_level: string;
+    return {
+        name: model.name,
+        size: sizeStr,
+        modified: model.modifiedAt ?? "-",
+        realSize: sizeBytes,
+        description: model.description ?? undefined,
+        family: model.family,
+        isThink: model.capabilities.think,
+        isVlm: model.capabilities.vision,
+        isEmbed: model.capabilities.embed,
+        isAudio: model.capabilities.audio,
+        contextLength: model.contextLength ?? undefined,
+        quantization: model.quantization ?? undefined,
+        url: model.url ?? undefined,
+        parameterSize: model.parameterSize ?? undefined
     };
 }
 
-let serverProcess: Child | null = null;
-let metadataCache: Record<string, FlmModel> | null = null;
-let installedModelsCache: FlmModel[] | null = null;
-let availableModelsCache: FlmModel[] | null = null;
-let hardwareInfoCache: HardwareInfo | null = null;
+/**
+ * Models of an inventory, as returned by `model_inventory` or pushed with MODELS_EVENT
+ */
+export function inventoryModels(inventory: ModelInventory, filter: 'all' | 'installed' | 'not-installed'): FlmModel[] {
+    const entries = filter === 'installed'
+        ? inventory.installed
+        : filter === 'not-installed'
+            ? inventory.available
+            : [...inventory.installed, ...inventory.available];
+
+    return entries.map((entry) => ({
+        ...(entry.info ? toFlmModel(entry.info) : { name: entry.name, size: "-", modified: "-" }),
+        issue: entry.issue ?? undefined,
+    }));
+}
 
 function getDirectory(path: string): string {
     const lastSlash = Math.max(path.lastIndexOf("/"), path.lastIndexOf("\\"));
@@ -42,7 +81,7 @@ function getDirectory(path: string): string {
 
 export const FlmService = {
     /**
-     * Get rich metadata from local JSON file
+     * Get rich metadata from the FLM catalog (`model_list.json`, parsed by the backend)
      */
     async getModelsMetadata(forceRefresh = false): Promise<Record<string, FlmModel>> {
         if (metadataCache && !forceRefresh) {
@@ -50,67 +89,16 @@ export const FlmService = {
         }
 
         try {
-            const config = await ConfigService.loadConfig();
-            let flmPath = config.flmPath;
-
-            // If path is not configured, try to detect it
-            if (!flmPath || flmPath === "flm") {
-                const detected = await this.findFlmPath();
-                if (detected) {
-                    flmPath = detected;
-                }
-            }
-
-            // flmPath is the directory containing model_list.json
-            let modelListPath;
-            if (flmPath && flmPath !== "flm") {
-                const cleanPath = flmPath.replace(/[\\/]+$/, '');
-                const separator = cleanPath.includes('\\') ? '\\' : '/';
-                modelListPath = `${cleanPath}${separator}${MODEL_LIST_FILENAME}`;
-            } else {
-                // Fallback to default install location if just "flm" is configured
-                modelListPath = `C:\\Program Files\\flm\\${MODEL_LIST_FILENAME}`;
-            }
-
-            const content = await readTextFile(modelListPath);
-            const data: ModelListJson = JSON.parse(content);
+            const catalog = await invoke<ModelCatalog>("get_model_catalog");
             const metadata: Record<string, FlmModel> = {};
 
-            for (const [family, variants] of Object.entries(data.models)) {
-                for (const [tag, details] of Object.entries(variants)) {
-                    const fullName = `${family}:${tag}`;
-
-                    // Format size to GB/MB
-                    const sizeBytes = details.size;
-                    let sizeStr = "";
-                    if (sizeBytes > 1024 * 1024 * 1024) {
-                        sizeStr = `${(sizeBytes / (1024 * 1024 * 1024)).toFixed(1)}GB`;
-                    } else {
-                        sizeStr = `${(sizeBytes / (1024 * 1024)).toFixed(0)}MB`;
-                    }
-
-                    metadata[fullName] = {
-                        name: fullName,
-                        size: sizeStr,
-                        modified: details.modified_at,
-                        realSize: details.size,
-                        description: details.name,
-                        family: details.details.family,
-                        isThink: details.details.think,
-                        isVlm: details.vlm || false,
-                        isEmbed: fullName.toLowerCase().includes("embed"),
-                        isAudio: fullName.toLowerCase().includes("whisper"),
-                        contextLength: details.default_context_length,
-                        quantization: details.details.quantization_level,
-                        url: details.url,
-                        parameterSize: details.details.parameter_size
-                    };
-                }
+            for (const model of catalog.models) {
+                metadata[model.name] = toFlmModel(model);
             }
             metadataCache = metadata;
             return metadata;
         } catch (error) {
-            console.warn(`Could not read ${MODEL_LIST_FILENAME}:`, error);
+            console.warn("Could not read the model catalog:", error);
             return {};
         }
     },
@@ -196,76 +184,14 @@ export const FlmService = {
 
     /**
      * List models with optional filter
-     * Parses output of `flm list`
+     * The backend reconciles `flm list` with the catalog and the model files
      * @param filter 'all' | 'installed' | 'not-installed'
-     * @param forceRefresh Force refresh of the cache
+     * @param forceRefresh Read the catalog, the CLI and the disk again
      */
     async listModels(filter: 'all' | 'installed' | 'not-installed' = 'installed', forceRefresh = false): Promise<FlmModel[]> {
-        if (filter === 'installed' && installedModelsCache && !forceRefresh) {
-            return installedModelsCache;
-        }
-        if (filter === 'not-installed' && availableModelsCache && !forceRefresh) {
-            return availableModelsCache;
-        }
-
         try {
-            const metadata = await this.getModelsMetadata();
-
-            const args = ["list", "--quiet"];
-            if (filter !== 'all') {
-                args.push("--filter", filter);
-            }
-
-            console.log(`[FLM] Executing: flm ${args.join(' ')}`);
-            const command = Command.create("flm", args);
-            const output = await command.execute();
-
-            if (output.code !== 0) {
-                console.error(`Failed to list models (filter=${filter}):`, output.stderr);
-                return [];
-            }
-
-            const lines = output.stdout.split("\n").filter((l: string) => l.trim() !== "");
-            const results: FlmModel[] = [];
-
-            for (const line of lines) {
-                const cleanLine = line.trim().replace(/^[-*+•>]\s+/, '');
-                const parts = cleanLine.split(/\s+/);
-
-                if (parts.length === 0) continue;
-
-                const name = parts[0];
-
-                if (name === "NAME" || name.startsWith("---") || name.startsWith("Model")) continue;
-
-                if (metadata[name]) {
-                    results.push(metadata[name]);
-                } else {
-                    let size = "-";
-                    if (name.includes(':')) {
-                        const split = name.split(':');
-                        if (split.length > 1) {
-                            size = split[split.length - 1];
-                        }
-                    }
-
-                    results.push({
-                        name: name,
-                        size: size,
-                        modified: "-",
-                        isEmbed: name.toLowerCase().includes("embed"),
-                        isAudio: name.toLowerCase().includes("whisper"),
-                    });
-                }
-            }
-
-            if (filter === 'installed') {
-                installedModelsCache = results;
-            } else if (filter === 'not-installed') {
-                availableModelsCache = results;
-            }
-
-            return results;
+            const inventory = await invoke<ModelInventory>("model_inventory", { refresh: forceRefresh });
+            return inventoryModels(inventory, filter);
         } catch (error) {
             console.error("Failed to list models:", error);
             return [];
@@ -273,154 +199,192 @@ export const FlmService = {
     },
 
     /**
-     * Start the FLM server
+     * Start the FLM server (the process is owned by the Rust supervisor)
      */
-    async startServer(modelName: string, options: ServerOptions, onLog: (log: string) => void): Promise<void> {
-        if (serverProcess) {
-            throw new Error("Server is already running");
-        }
+    async startServer(modelName: string, options: ServerOptions, instance?: string): Promise<ServerState> {
+        return invoke<ServerState>("start_server", { model: modelName, options, instance: instance ?? null });
+    },
 
-        try {
-            const args = ["serve"];
-            if (modelName) {
-                args.push(modelName);
-            }
+    /**
+     * Estimate the memory of a model with the given options against the shared memory
+     */
+    async estimateMemory(modelName: string, options: ServerOptions): Promise<MemoryEstimate> {
+        return invoke<MemoryEstimate>("estimate_memory", { model: modelName, options });
+    },
 
-            if (options.pmode) args.push("--pmode", options.pmode);
-            if (options.ctxLen && options.ctxLen > 0) args.push("--ctx-len", options.ctxLen.toString());
-            if (options.port && options.port > 0) args.push("--port", options.port.toString());
-            if (options.host) args.push("--host", options.host);
-            if (options.socket && options.socket > 0) args.push("--socket", options.socket.toString());
-            if (options.qLen && options.qLen > 0) args.push("--q-len", options.qLen.toString());
+    /**
+     * Stop the FLM server (graceful 'exit', then kill after a timeout)
+     */
+    async stopServer(): Promise<void> {
+        await invoke("stop_server");
+    },
 
-            // Boolean flags that take 0 or 1
-            if (options.asr !== undefined) args.push("--asr", options.asr ? "1" : "0");
-            if (options.embed !== undefined) args.push("--embed", options.embed ? "1" : "0");
-            if (options.cors !== undefined) args.push("--cors", options.cors ? "1" : "0");
-            if (options.preemption !== undefined) args.push("--preemption", options.preemption ? "1" : "0");
+    /**
+     * Get the current state of the supervised server
+     */
+    async getServerState(): Promise<ServerState> {
+        return invoke<ServerState>("server_status");
+    },
 
-            console.log(`[FLM] Starting server with args: ${args.join(' ')}`);
-            onLog(`[SYSTEM] Executing: flm ${args.join(' ')}`);
+    /**
+     * Set the model and options used when the server is started from the tray
+     */
+    async setServerLaunchConfig(modelName: string, options: ServerOptions): Promise<void> {
+        await invoke("set_server_launch_config", { model: modelName, options });
+    },
 
-            const command = Command.create("flm", args);
+    /**
+     * Apply a new model or options to the running server, restarted once if its command line changes
+     */
+    async reconfigureServer(modelName: string, options: ServerOptions): Promise<void> {
+        await invoke("reconfigure_server", { model: modelName, options });
+    },
 
-            command.on('close', (data: TerminatedPayload) => {
-                console.log(`command finished with code ${data.code} and signal ${data.signal}`);
-                serverProcess = null;
-                onLog(`[SYSTEM] Server stopped with code ${data.code}`);
-            });
+    /**
+     * List every server instance, the main one first
+     */
+    async listServerInstances(): Promise<ServerState[]> {
+        return invoke<ServerState[]>("list_server_instances");
+    },
 
-            command.on('error', (error: string) => {
-                console.error(`command error: "${error}"`);
-                onLog(`[ERROR] ${error}`);
-            });
+    /**
+     * Register an additional server instance (e.g. ASR/embeddings next to the LLM)
+     */
+    async addServerInstance(instance: InstanceConfig): Promise<ServerState> {
+        return invoke<ServerState>("add_server_instance", { instance });
+    },
 
-            command.stdout.on('data', (line: string) => {
-                onLog(`[FLM] ${line}`);
-            });
+    /**
+     * Remove a stopped additional instance
+     */
+    async removeServerInstance(id: string): Promise<void> {
+        await invoke("remove_server_instance", { instance: id });
+    },
 
-            command.stderr.on('data', (line: string) => {
-                onLog(`[FLM ERR] ${line}`);
-            });
+    /**
+     * Adopt, terminate or avoid the server found running at startup
+     */
+    async resolveOrphanServer(action: OrphanAction): Promise<ServerState> {
+        return invoke<ServerState>("resolve_orphan_server", { action });
+    },
 
-            serverProcess = await command.spawn();
-            onLog(`[SYSTEM] Server process started (PID: ${serverProcess.pid})`);
+    /**
+     * Start the server when the companion starts, applied on the next launch
+     */
+    async getStartOnLaunch(): Promise<StartOnLaunch> {
+        return invoke<StartOnLaunch>("get_start_on_launch");
+    },
 
-        } catch (error) {
-            console.error("Failed to start server:", error);
-            throw error;
-        }
+    async setStartOnLaunch(settings: StartOnLaunch): Promise<void> {
+        await invoke("set_start_on_launch", { settings });
     },
 
     /**
-     * Stop the FLM server
+     * Hide the catalog models that won't fit in memory from the tray instead of flagging them
      */
-    async stopServer(onLog?: (log: string) => void): Promise<void> {
-        if (serverProcess) {
-            try {
-                if (onLog) onLog("[SYSTEM] Sending 'exit' command to server...");
+    async getHideUnfitModels(): Promise<boolean> {
+        return invoke<boolean>("get_hide_unfit_models");
+    },
+
+    async setHideUnfitModels(hide: boolean): Promise<void> {
+        await invoke("set_hide_unfit_models", { hide });
+    },
 
-                const encoder = new TextEncoder();
-                await serverProcess.write(encoder.encode("exit\r\n"));
+    /**
+     * Weekly start/stop windows of the main server
+     */
+    async getSchedule(): Promise<Schedule> {
+        return invoke<Schedule>("get_schedule");
+    },
 
-                if (onLog) onLog("[SYSTEM] Exit command sent. Waiting for graceful shutdown...");
+    /**
+     * Replace the schedule, it is persisted by the backend
+     */
+    async setSchedule(schedule: Schedule): Promise<void> {
+        await invoke("set_schedule", { schedule });
+    },
 
-                await new Promise<void>((resolve) => {
-                    const timeoutId = setTimeout(() => {
-                        if (serverProcess) {
-                            if (onLog) onLog("[SYSTEM] Server did not exit gracefully, forcing kill...");
-                            console.log("Server did not exit gracefully, forcing kill...");
-                            serverProcess.kill().catch((e) => {
-                                console.error("Error killing process:", e);
-                            });
-                        }
-                        resolve();
-                    }, 5000);
-
-                    const intervalId = setInterval(() => {
-                        if (!serverProcess) {
-                            clearTimeout(timeoutId);
-                            clearInterval(intervalId);
-                            resolve();
-                        }
-                    }, 100);
-                });
-
-            } catch (e) {
-                console.error("Failed to write exit command, forcing kill", e);
-                if (onLog) onLog(`[ERROR] Failed to write exit command: ${e}. Forcing kill...`);
-                await serverProcess.kill();
-            }
-        }
+    /**
+     * Next scheduled starts and stops
+     */
+    async upcomingSchedule(count?: number): Promise<ScheduledTransition[]> {
+        return invoke<ScheduledTransition[]>("upcoming_schedule", { count });
     },
 
     /**
-     * Pull a new model
+     * Server sessions recorded on disk, newest first
      */
-    pullModel(modelName: string, onProgress: (data: string) => void): Promise<void> {
-        installedModelsCache = null;
-        availableModelsCache = null;
-        return new Promise((resolve, reject) => {
-            let errorOutput = "";
+    async listLogSessions(instance?: string): Promise<LogSession[]> {
+        return invoke<LogSession[]>("list_log_sessions", { instance });
+    },
 
-            try {
-                const command = Command.create("flm", ["pull", modelName]);
+    /**
+     * Server sessions newest first, with the likely cause of the failed ones
+     */
+    async serverHistory(instance?: string, limit?: number): Promise<SessionRecord[]> {
+        return invoke<SessionRecord[]>("server_history", { instance, limit });
+    },
 
-                command.on('close', (data: TerminatedPayload) => {
-                    if (data.code === 0) {
-                        resolve();
-                    } else {
-                        reject(new Error(errorOutput || `Process exited with code ${data.code}`));
-                    }
-                });
+    /**
+     * Read `limit` lines of a session starting at `offset`
+     */
+    async readServerLog(session: string, offset: number, limit: number): Promise<LogPage> {
+        return invoke<LogPage>("read_server_log", { session, offset, limit });
+    },
 
-                command.on('error', (error: string) => {
-                    reject(new Error(error));
-                });
+    /**
+     * Last `count` lines of a session
+     */
+    async tailServerLog(session: string, count: number): Promise<LogPage> {
+        return invoke<LogPage>("tail_server_log", { session, count });
+    },
+
+    /**
+     * Downloads of the backend queue, the finished ones included
+     */
+    async listDownloads(): Promise<DownloadJob[]> {
+        return invoke<DownloadJob[]>("list_downloads");
+    },
 
-                command.stdout.on('data', (line: string) => {
-                    onProgress(line.toString());
-                });
+    /**
+     * Queue `flm pull` for a model, progress is reported through DOWNLOAD_EVENT
+     */
+    async queueDownload(modelName: string): Promise<DownloadJob> {
+        return invoke<DownloadJob>("queue_download", { model: modelName });
+    },
 
-                command.stderr.on('data', (line: string) => {
-                    const text = line.toString();
-                    errorOutput += text;
-                    onProgress(text);
-                });
+    /**
+     * Cancel a queued or running download
+     */
+    async cancelDownload(modelName: string): Promise<DownloadJob> {
+        return invoke<DownloadJob>("cancel_download", { model: modelName });
+    },
 
-                command.spawn().catch(reject);
-            } catch (error) {
-                reject(error);
-            }
-        });
+    /**
+     * Queue a failed or cancelled download again
+     */
+    async retryDownload(modelName: string): Promise<DownloadJob> {
+        return invoke<DownloadJob>("retry_download", { model: modelName });
+    },
+
+    /**
+     * Check the files of an installed model against the catalog
+     */
+    async verifyModel(modelName: string): Promise<VerifyReport> {
+        return invoke<VerifyReport>("verify_model", { model: modelName });
+    },
+
+    /**
+     * Verify models (all installed ones by default) and queue a new pull of the damaged ones
+     */
+    async repairModels(modelNames?: string[]): Promise<VerifyReport[]> {
+        return invoke<VerifyReport[]>("repair_models", { models: modelNames ?? null });
     },
 
     /**
      * Remove a model
      */
     async removeModel(modelName: string): Promise<void> {
-        installedModelsCache = null;
-        availableModelsCache = null;
         const command = Command.create("flm", ["remove", modelName]);
         const output = await command.execute();
         if (output.code !== 0) {
@@ -432,7 +396,7 @@ export const FlmService = {
      * Start interactive chat session
      */
     async startChat(modelName: string, options: ServerOptions, onData: (data: { type: 'stdout' | 'stderr' | 'exit', content?: string, code?: number }) => void): Promise<void> {
-        if (serverProcess) {
+        if (chatProcess) {
             throw new Error("A process is already running");
         }
 
@@ -450,7 +414,7 @@ export const FlmService = {
             const command = Command.create("flm", args);
 
             command.on('close', (data: TerminatedPayload) => {
-                serverProcess = null;
+                chatProcess = null;
                 onData({ type: 'exit', code: data.code ?? undefined });
             });
 
@@ -466,7 +430,7 @@ export const FlmService = {
                 onData({ type: 'stderr', content: line });
             });
 
-            serverProcess = await command.spawn();
+            chatProcess = await command.spawn();
 
         } catch (error) {
             console.error("Failed to start chat:", error);
@@ -478,17 +442,45 @@ export const FlmService = {
      * Send message to chat session
      */
     async sendChatMessage(message: string): Promise<void> {
-        if (!serverProcess) return;
+        if (!chatProcess) return;
 
         const encoder = new TextEncoder();
-        await serverProcess.write(encoder.encode(message + "\n"));
+        await chatProcess.write(encoder.encode(message + "\n"));
     },
 
     /**
      * Stop chat session
      */
     async stopChat(): Promise<void> {
-        await this.stopServer();
+        if (!chatProcess) return;
+
+        try {
+            const encoder = new TextEncoder();
+            await chatProcess.write(encoder.encode("exit\r\n"));
+
+            await new Promise<void>((resolve) => {
+                const timeoutId = setTimeout(() => {
+                    if (chatProcess) {
+                        console.log("Chat did not exit gracefully, forcing kill...");
+                        chatProcess.kill().catch((e) => {
+                            console.error("Error killing process:", e);
+                        });
+                    }
+                    resolve();
+                }, 5000);
+
+                const intervalId = setInterval(() => {
+                    if (!chatProcess) {
+                        clearTimeout(timeoutId);
+                        clearInterval(intervalId);
+                        resolve();
+                    }
+                }, 100);
+            });
+        } catch (e) {
+            console.error("Failed to write exit command, forcing kill", e);
+            await chatProcess.kill();
+        }
     },
 
     /**
@@ -4,6 +4,18 @@ export type Theme = "dark" | "light" | "system";
 
 export type ServerStatus = "stopped" | "running" | "starting";
 
+// Status reported by the Rust server supervisor
+export type SupervisorStatus =
+    | "stopped"
+    | "starting"
+    | "running"
+    | "stopping"
+    | "restarting"
+    | "failed";
+
+// Health of the HTTP endpoint, probed by the Rust supervisor
+export type ServerHealth = "starting" | "ready" | "degraded" | "unreachable";
+
 export type PerformanceMode = "powersaver" | "balanced" | "performance" | "turbo";
 
 export interface ServerOptions {
@@ -19,10 +31,334 @@ export interface ServerOptions {
     preemption?: boolean;
 }
 
+export type FailureReason = "crashLoop" | "retriesExhausted" | "launchFailed";
+
+export interface PendingRestart {
+    attempt: number;
+    maxRetries: number;
+    delaySecs: number;
+}
+
+// Server found running at startup that the companion did not launch
+export interface OrphanServer {
+    pid: number | null;
+    processName: string | null;
+    isFlm: boolean;
+    host: string;
+    port: number;
+    model: string | null;
+    responding: boolean;
+}
+
+export type OrphanAction = "adopt" | "terminate" | "changePort";
+
+export type NpuResource = "llm" | "asr" | "embeddings";
+
+// Line of server output, tagged with the instance that produced it
+export interface ServerLog {
+    instance: string;
+    line: string;
+}
+
+export interface LogSession {
+    id: string;
+    instance: string;
+    // Unix time in milliseconds
+    startedAt: number;
+    size: number;
+    active: boolean;
+}
+
+export type StopCause = "user" | "schedule" | "idle" | "reconfigure" | "shutdown" | "exited";
+
+export type Diagnosis =
+    | "portInUse"
+    | "modelNotInstalled"
+    | "npuBusy"
+    | "npuUnavailable"
+    | "outOfMemory"
+    | "crashed"
+    | "unknown";
+
+// One run of `flm serve`, times are Unix milliseconds
+export interface SessionRecord {
+    instance: string;
+    logSession: string | null;
+    model: string;
+    options: ServerOptions;
+    startedAt: number;
+    readyAt: number | null;
+    stoppedAt: number | null;
+    exitCode: number | null;
+    stoppedBy: StopCause | null;
+    lastErrors: string[];
+    // Set when the session failed
+    diagnosis: Diagnosis | null;
+}
+
+export interface LogPage {
+    session: string;
+    offset: number;
+    total: number;
+    lines: string[];
+}
+
+// Payload of the tray "view-logs" event
+export interface ViewLogsRequest {
+    instance: string;
+    session: string | null;
+}
+
+export type OutputSeverity = "info" | "warning" | "error" | "fatal";
+
+// Line of `flm serve` output recognized by the backend
+export type FlmEvent =
+    | { kind: "loading"; model: string | null }
+    | { kind: "loadProgress"; percent: number }
+    | { kind: "modelLoaded"; model: string | null }
+    | { kind: "ready"; port: number | null }
+    | { kind: "request"; method: string; path: string }
+    | { kind: "throughput"; stage: "prefill" | "decode"; tokensPerSecond: number }
+    | { kind: "warning"; message: string }
+    | { kind: "error"; message: string }
+    | { kind: "fatal"; message: string }
+    | { kind: "output"; message: string };
+
+export interface ServerOutput {
+    instance: string;
+    severity: OutputSeverity;
+    event: FlmEvent;
+}
+
+export type ReconfigurePhase = "queued" | "skipped" | "restarting" | "applied" | "failed";
+
+export interface ReconfigureEvent {
+    instance: string;
+    phase: ReconfigurePhase;
+    // Error message, only for "failed"
+    message?: string;
+}
+
+// Additional server instance, stored under `serverInstances` in config.json
+export interface InstanceConfig {
+    id: string;
+    model: string;
+    options: ServerOptions;
+}
+
+export interface ServerState {
+    id: string;
+    status: SupervisorStatus;
+    health: ServerHealth | null;
+    model: string;
+    options: ServerOptions;
+    pid: number | null;
+    exitCode: number | null;
+    restart: PendingRestart | null;
+    failure: FailureReason | null;
+    adopted: boolean;
+    orphan: OrphanServer | null;
+    // Model load progress while starting
+    loadPercent: number | null;
+    // Stopped after the idle timeout
+    idle: boolean;
+    // What ended the last process, set once stopped
+    stoppedBy: StopCause | null;
+    // Start of the process (or its adoption), ms since the epoch
+    startedAt: number | null;
+}
+
+export type RestartMode = "never" | "onFailure" | "always";
+
+// Owned by the Rust supervisor, stored under `restartPolicy` in config.json
+export interface RestartPolicy {
+    mode: RestartMode;
+    maxRetries: number;
+    initialDelaySecs: number;
+    maxDelaySecs: number;
+    crashLoopWindowSecs: number;
+    crashLoopThreshold: number;
+}
+
+// Owned by the Rust supervisor, stored under `portSelection` in config.json
+export interface PortSelection {
+    autoSelect: boolean;
+    rangeStart: number;
+    rangeEnd: number;
+}
+
+// Stored under `idleStop` in config.json
+export interface IdlePolicy {
+    // 0 disables the idle stop
+    timeoutMinutes: number;
+    // Restart the server on the next connection to its port
+    wakeOnRequest: boolean;
+}
+
+// Stored under `startOnLaunch` in config.json
+export interface StartOnLaunch {
+    enabled: boolean;
+    // Preset id to start, the last selected model when null
+    preset: string | null;
+    delaySecs: number;
+}
+
+export type ScheduleDay = "mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun";
+
+// Weekly window during which the main server runs, times are local "HH:MM"
+export interface ScheduleWindow {
+    days: ScheduleDay[];
+    start: string;
+    // Before `start` for a window running past midnight
+    end: string;
+    // Actual model, resolved from the preset when `preset` is set
+    model: string;
+    preset: string | null;
+    options: ServerOptions;
+}
+
+// Stored under `schedule` in config.json
+export interface Schedule {
+    enabled: boolean;
+    windows: ScheduleWindow[];
+}
+
+export interface ScheduledTransition {
+    kind: "start" | "stop";
+    // Local time, "YYYY-MM-DDTHH:MM:SS"
+    at: string;
+    window: number;
+    model: string | null;
+}
+
+// Error returned by the server commands (Rust `ServerError`)
+export type ServerError =
+    | { kind: "alreadyRunning" | "notRunning" }
+    | { kind: "spawn" | "io" | "orphan"; message: string }
+    | { kind: "portInUse"; message: { port: number; pid: number | null; process: string | null } }
+    | { kind: "unknownInstance" | "invalidInstance"; message: string }
+    | { kind: "npuConflict"; message: { instance: string; resource: NpuResource } };
+
+export interface ModelCapabilities {
+    think: boolean;
+    vision: boolean;
+    embed: boolean;
+    audio: boolean;
+}
+
+// Entry of `model_list.json`, parsed by the backend
+export interface ModelInfo {
+    // `family:tag`
+    name: string;
+    family: string;
+    tag: string;
+    description: string | null;
+    // Directory of the model under `modelPath`
+    dirName: string | null;
+    url: string | null;
+    sizeBytes: number;
+    modifiedAt: string | null;
+    contextLength: number | null;
+    parameterSize: string | null;
+    quantization: string | null;
+    format: string | null;
+    capabilities: ModelCapabilities;
+    // Files expected in the model directory, empty when the catalog does not list them
+    files: string[];
+    // SHA-256 of some of these files, by file name
+    checksums: Record<string, string>;
+}
+
+export interface ModelCatalog {
+    // Directory holding the downloaded models
+    modelPath: string | null;
+    models: ModelInfo[];
+    // Malformed entries left out of `models`
+    skipped: { kind: "invalidEntry"; message: { model: string; message: string } }[];
+}
+
+export type DiskState = "complete" | "partial" | "missing" | "unknown";
+
+export type InstallIssue = "missingFiles" | "partialFiles" | "notListed";
+
+// Catalog entry reconciled with `flm list` and the model directory
+export interface InventoryEntry {
+    name: string;
+    // Null for models `flm list` reports but the catalog no longer has
+    info: ModelInfo | null;
+    disk: DiskState;
+    issue: InstallIssue | null;
+}
+
+export interface ModelInventory {
+    installed: InventoryEntry[];
+    available: InventoryEntry[];
+}
+
+export type FileProblem = "missing" | "truncated" | "checksumMismatch" | "partial" | "extra";
+
+export interface FileIssue {
+    // Relative to the model directory, empty for the directory itself
+    file: string;
+    problem: FileProblem;
+}
+
+// Files of an installed model checked against the catalog
+export interface VerifyReport {
+    model: string;
+    dir: string;
+    expectedBytes: number;
+    actualBytes: number;
+    // Files much smaller than the catalog size
+    undersized: boolean;
+    checksumsChecked: number;
+    issues: FileIssue[];
+    // Nothing but extra files
+    healthy: boolean;
+}
+
+export type DownloadStatus = "queued" | "downloading" | "retrying" | "completed" | "failed" | "cancelled";
+
+// One `flm pull` of the backend download queue
+export interface DownloadJob {
+    model: string;
+    status: DownloadStatus;
+    // Overall progress, 0 to 100
+    percent: number | null;
+    bytesPerSec: number | null;
+    currentFile: number | null;
+    totalFiles: number | null;
+    // Pulls started for this request, retries included
+    attempts: number;
+    // Repair of an installed model
+    force: boolean;
+    error: string | null;
+    // Unix timestamps in milliseconds
+    queuedAt: number;
+    startedAt: number | null;
+    finishedAt: number | null;
+    retryAt: number | null;
+}
+
+export interface RemovableModel {
+    name: string;
+    sizeBytes: number;
+}
+
+// Error returned by the download commands (Rust `DownloadError`)
+export type DownloadError =
+    | { kind: "alreadyQueued" | "notQueued" | "notRetryable" | "invalidModel"; message: string }
+    | {
+        kind: "insufficientSpace";
+        message: { model: string; requiredBytes: number; availableBytes: number; removable: RemovableModel[] };
+    };
+
 export interface FlmModel {
     name: string;
     size: string;
     modified: string;
+    // Set when the CLI and the model files disagree
+    issue?: InstallIssue;
     // Extended metadata
     realSize?: number;
     description?: string;
@@ -47,12 +383,46 @@ export interface HardwareInfo {
     sharedMemoryBytes: number;
 }
 
+export type MemoryVerdict = "fits" | "tight" | "wontFit";
+
+// Memory a server configuration needs, from `estimate_memory`
+export interface MemoryEstimate {
+    model: string;
+    // Context length estimated, the model default when the options have none
+    ctxLen: number;
+    weightsBytes: number;
+    kvCacheBytes: number;
+    asrBytes: number;
+    embedBytes: number;
+    overheadBytes: number;
+    totalBytes: number;
+    // Half of the RAM, the most the NPU can share
+    sharedMemoryBytes: number;
+    availableBytes: number;
+    verdict: MemoryVerdict;
+}
+
+// Start held back because it won't fit, sent by the tray with "confirm-start"
+export interface MemoryWarning {
+    instance: string;
+    model: string;
+    options: ServerOptions;
+    estimate: MemoryEstimate;
+}
+
 export interface AppConfig {
     theme: Theme;
     startMinimized: boolean;
     flmPath: string;
     lastSelectedModel: string;
     serverOptions: ServerOptions;
+    restartPolicy?: RestartPolicy;
+    portSelection?: PortSelection;
+    serverInstances?: InstanceConfig[];
+    schedule?: Schedule;
+    idleStop?: IdlePolicy;
+    startOnLaunch?: StartOnLaunch;
+    hideUnfitModels?: boolean;
 }
 
 export interface FlmStatus {
@@ -120,4 +490,19 @@ export const DEFAULT_APP_CONFIG: AppConfig = {
 
 export const CONFIG_FILENAME = "config.json";
 
-export const MODEL_LIST_FILENAME = "model_list.json";
+export const SERVER_STATE_EVENT = "server-state-changed";
+
+// Instance driven by the UI selection, additional instances are managed from the tray
+export const MAIN_INSTANCE = "main";
+
+export const SERVER_LOG_EVENT = "server-log";
+
+export const SERVER_PORT_EVENT = "server-port-changed";
+
+export const SERVER_RECONFIGURE_EVENT = "server-reconfigure";
+
+export const SERVER_OUTPUT_EVENT = "server-output";
+
+export const DOWNLOAD_EVENT = "download-changed";
+
+export const MODELS_EVENT = "models-changed";